@@ -0,0 +1,576 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpStream, UnixStream};
+
+use super::{ApiError, ImageHealthcheck};
+
+pub const DEFAULT_DOCKER_SOCKET: &str = "/var/run/docker.sock";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+const PULL_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineEndpoint {
+    Unix(PathBuf),
+    Tcp(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DockerEngine {
+    endpoint: EngineEndpoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageSummary {
+    #[serde(rename = "RepoTags")]
+    repo_tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageInspect {
+    #[serde(rename = "Config")]
+    config: Option<InspectConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InspectConfig {
+    #[serde(rename = "ExposedPorts")]
+    exposed_ports: Option<BTreeMap<String, IgnoredAny>>,
+    #[serde(rename = "Volumes")]
+    volumes: Option<BTreeMap<String, IgnoredAny>>,
+    #[serde(rename = "Healthcheck")]
+    healthcheck: Option<ImageHealthcheck>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullProgress {
+    status: Option<String>,
+    id: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LocalImageConfig {
+    pub exposed_ports: Vec<u16>,
+    pub volumes: Vec<String>,
+    pub healthcheck: Option<ImageHealthcheck>,
+}
+
+struct EngineResponse {
+    status: u16,
+    body: Vec<u8>,
+}
+
+#[derive(Default)]
+struct StreamLines {
+    pending: Vec<u8>,
+    line: Vec<u8>,
+    headers_read: bool,
+    chunked: bool,
+    chunk_left: usize,
+    finished: bool,
+}
+
+impl EngineEndpoint {
+    pub fn parse(host: &str) -> Result<Self, String> {
+        if let Some(path) = host.strip_prefix("unix://") {
+            Ok(EngineEndpoint::Unix(PathBuf::from(path)))
+        } else if let Some(address) = host.strip_prefix("tcp://") {
+            Ok(EngineEndpoint::Tcp(
+                address.trim_end_matches('/').to_string(),
+            ))
+        } else {
+            Err(format!(
+                "unsupported DOCKER_HOST '{host}' (use unix:// or tcp://)"
+            ))
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            EngineEndpoint::Unix(path) => format!("unix://{}", path.display()),
+            EngineEndpoint::Tcp(address) => format!("tcp://{address}"),
+        }
+    }
+}
+
+fn find_header_end(raw: &[u8]) -> Option<usize> {
+    raw.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+fn find_crlf(raw: &[u8]) -> Option<usize> {
+    raw.windows(2).position(|window| window == b"\r\n")
+}
+
+fn chunk_size(line: &[u8]) -> Result<usize, ApiError> {
+    let size_text = String::from_utf8_lossy(line);
+    let size_text = size_text.split(';').next().unwrap_or_default().trim();
+    usize::from_str_radix(size_text, 16)
+        .map_err(|_| format!("invalid chunk size '{size_text}'").into())
+}
+
+fn is_chunked(head: &str) -> bool {
+    head.lines().skip(1).any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    })
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = find_crlf(body).ok_or("truncated chunked body")?;
+        let size = chunk_size(&body[..line_end])?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(decoded);
+        }
+        if body.len() < size {
+            return Err("truncated chunk".into());
+        }
+        decoded.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+}
+
+fn parse_response(raw: &[u8]) -> Result<EngineResponse, ApiError> {
+    let header_end = find_header_end(raw).ok_or("malformed engine response")?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or("missing HTTP status line")?;
+
+    let body = &raw[header_end + 4..];
+    let body = if is_chunked(&head) {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+    Ok(EngineResponse { status, body })
+}
+
+impl StreamLines {
+    fn feed(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(bytes);
+        if !self.headers_read {
+            let Some(header_end) = find_header_end(&self.pending) else {
+                return Vec::new();
+            };
+            self.chunked = is_chunked(&String::from_utf8_lossy(&self.pending[..header_end]));
+            self.pending.drain(..header_end + 4);
+            self.headers_read = true;
+        }
+
+        if !self.chunked {
+            self.line.append(&mut self.pending);
+        }
+        while self.chunked && !self.finished {
+            if self.chunk_left > 0 {
+                let take = self.chunk_left.min(self.pending.len());
+                self.line.extend(self.pending.drain(..take));
+                self.chunk_left -= take;
+                if self.chunk_left > 0 {
+                    break;
+                }
+            }
+            if self.pending.starts_with(b"\r\n") {
+                self.pending.drain(..2);
+            }
+            let Some(line_end) = find_crlf(&self.pending) else {
+                break;
+            };
+            match chunk_size(&self.pending[..line_end]) {
+                Ok(size) if size > 0 => self.chunk_left = size,
+                _ => self.finished = true,
+            }
+            self.pending.drain(..line_end + 2);
+        }
+
+        let mut lines = Vec::new();
+        while let Some(newline) = self.line.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.line.drain(..=newline).collect();
+            lines.push(String::from_utf8_lossy(&line).trim().to_string());
+        }
+        lines
+    }
+}
+
+async fn exchange<S>(
+    mut stream: S,
+    request: &str,
+    on_read: &mut impl FnMut(&[u8]),
+) -> Result<Vec<u8>, ApiError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.write_all(request.as_bytes()).await?;
+    let mut raw = Vec::new();
+    let mut buffer = [0; 8192];
+    loop {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(raw);
+        }
+        raw.extend_from_slice(&buffer[..read]);
+        on_read(&buffer[..read]);
+    }
+}
+
+fn split_reference(reference: &str) -> (&str, &str) {
+    match reference.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name, tag),
+        _ => (reference, "latest"),
+    }
+}
+
+fn container_port(spec: &str) -> Option<u16> {
+    spec.split('/').next()?.parse().ok()
+}
+
+impl DockerEngine {
+    pub fn new(endpoint: EngineEndpoint) -> Self {
+        Self { endpoint }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("DOCKER_HOST") {
+            Ok(host) if !host.trim().is_empty() => {
+                EngineEndpoint::parse(host.trim()).map(Self::new)
+            }
+            _ => Ok(Self::new(EngineEndpoint::Unix(PathBuf::from(
+                DEFAULT_DOCKER_SOCKET,
+            )))),
+        }
+    }
+
+    pub fn endpoint(&self) -> &EngineEndpoint {
+        &self.endpoint
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        path: &str,
+        timeout: Duration,
+    ) -> Result<EngineResponse, ApiError> {
+        self.request_streaming(method, path, timeout, |_| {}).await
+    }
+
+    async fn request_streaming(
+        &self,
+        method: &str,
+        path: &str,
+        timeout: Duration,
+        mut on_read: impl FnMut(&[u8]),
+    ) -> Result<EngineResponse, ApiError> {
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"
+        );
+        let raw = tokio::time::timeout(timeout, async {
+            match &self.endpoint {
+                EngineEndpoint::Unix(path) => {
+                    exchange(UnixStream::connect(path).await?, &request, &mut on_read).await
+                }
+                EngineEndpoint::Tcp(address) => {
+                    exchange(TcpStream::connect(address).await?, &request, &mut on_read).await
+                }
+            }
+        })
+        .await
+        .map_err(|_| format!("docker engine at {} timed out", self.endpoint.describe()))??;
+        parse_response(&raw)
+    }
+
+    pub async fn list_images(&self) -> Result<Vec<String>, ApiError> {
+        let response = self.request("GET", "/images/json", REQUEST_TIMEOUT).await?;
+        if response.status != 200 {
+            return Err(format!("listing images returned {}", response.status).into());
+        }
+        let summaries: Vec<ImageSummary> = serde_json::from_slice(&response.body)?;
+        let mut images: Vec<String> = summaries
+            .into_iter()
+            .flat_map(|summary| summary.repo_tags.unwrap_or_default())
+            .filter(|tag| tag != "<none>:<none>")
+            .collect();
+        images.sort();
+        images.dedup();
+        Ok(images)
+    }
+
+    pub async fn inspect_image(
+        &self,
+        reference: &str,
+    ) -> Result<Option<LocalImageConfig>, ApiError> {
+        let response = self
+            .request("GET", &format!("/images/{reference}/json"), REQUEST_TIMEOUT)
+            .await?;
+        match response.status {
+            200 => {}
+            404 => return Ok(None),
+            status => return Err(format!("inspecting {reference} returned {status}").into()),
+        }
+
+        let inspect: ImageInspect = serde_json::from_slice(&response.body)?;
+        let Some(config) = inspect.config else {
+            return Ok(Some(LocalImageConfig::default()));
+        };
+        let mut exposed_ports: Vec<u16> = config
+            .exposed_ports
+            .unwrap_or_default()
+            .keys()
+            .filter_map(|spec| container_port(spec))
+            .collect();
+        exposed_ports.sort_unstable();
+        exposed_ports.dedup();
+        Ok(Some(LocalImageConfig {
+            exposed_ports,
+            volumes: config.volumes.unwrap_or_default().into_keys().collect(),
+            healthcheck: config.healthcheck,
+        }))
+    }
+
+    pub async fn pull_image(
+        &self,
+        reference: &str,
+        mut progress: impl FnMut(&str),
+    ) -> Result<(), ApiError> {
+        let (name, tag) = split_reference(reference);
+        let mut lines = StreamLines::default();
+        let mut last = String::new();
+        let response = self
+            .request_streaming(
+                "POST",
+                &format!("/images/create?fromImage={name}&tag={tag}"),
+                PULL_TIMEOUT,
+                |bytes| {
+                    for line in lines.feed(bytes) {
+                        let Ok(PullProgress {
+                            status: Some(status),
+                            id,
+                            ..
+                        }) = serde_json::from_str(&line)
+                        else {
+                            continue;
+                        };
+                        let message = match id {
+                            Some(id) => format!("{id}: {status}"),
+                            None => status,
+                        };
+                        if message != last {
+                            progress(&message);
+                            last = message;
+                        }
+                    }
+                },
+            )
+            .await?;
+        let body = String::from_utf8_lossy(&response.body);
+        if response.status != 200 {
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|value| value.get("message")?.as_str().map(ToString::to_string))
+                .unwrap_or_else(|| format!("status {}", response.status));
+            return Err(format!("pull {reference} failed: {message}").into());
+        }
+
+        for line in body.lines().filter(|line| !line.trim().is_empty()) {
+            if let Ok(PullProgress {
+                error: Some(error), ..
+            }) = serde_json::from_str(line)
+            {
+                return Err(format!("pull {reference} failed: {error}").into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::UnixListener;
+
+    fn chunked(lines: &[&str]) -> String {
+        let mut response = String::from("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n");
+        for line in lines {
+            response.push_str(&format!("{:x}\r\n{line}\n\r\n", line.len() + 1));
+        }
+        response.push_str("0\r\n\r\n");
+        response
+    }
+
+    async fn serve_once(socket: PathBuf, response: String) -> String {
+        let listener = UnixListener::bind(&socket).unwrap();
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = vec![0; 1024];
+        let read = stream.read(&mut request).await.unwrap();
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8_lossy(&request[..read]).to_string()
+    }
+
+    fn socket_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("acdc-engine-{name}-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn json_response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[tokio::test]
+    async fn list_images_returns_sorted_tags() {
+        let socket = socket_path("list");
+        let server = tokio::spawn(serve_once(
+            socket.clone(),
+            json_response(
+                "200 OK",
+                r#"[{"RepoTags":["redis:7","nginx:latest"]},{"RepoTags":["<none>:<none>"]},{"RepoTags":null},{"RepoTags":["redis:7"]}]"#,
+            ),
+        ));
+        while !socket.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let engine = DockerEngine::new(EngineEndpoint::Unix(socket.clone()));
+        let images = engine.list_images().await;
+        let request = server.await.unwrap();
+        std::fs::remove_file(&socket).unwrap();
+
+        assert!(request.starts_with("GET /images/json "));
+        assert_eq!(images.unwrap(), ["nginx:latest", "redis:7"]);
+    }
+
+    #[tokio::test]
+    async fn inspect_image_reads_ports_and_volumes() {
+        let socket = socket_path("inspect");
+        let server = tokio::spawn(serve_once(
+            socket.clone(),
+            json_response(
+                "200 OK",
+                r#"{"Config":{"ExposedPorts":{"6379/tcp":{},"80/tcp":{},"80/udp":{}},"Volumes":{"/data":{}}}}"#,
+            ),
+        ));
+        while !socket.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let engine = DockerEngine::new(EngineEndpoint::Unix(socket.clone()));
+        let config = engine.inspect_image("redis:7").await;
+        let request = server.await.unwrap();
+        std::fs::remove_file(&socket).unwrap();
+
+        assert!(request.starts_with("GET /images/redis:7/json "));
+        let config = config.unwrap().unwrap();
+        assert_eq!(config.exposed_ports, [80, 6379]);
+        assert_eq!(config.volumes, ["/data"]);
+        assert!(config.healthcheck.is_none());
+    }
+
+    #[tokio::test]
+    async fn inspect_image_treats_404_as_missing() {
+        let socket = socket_path("missing");
+        let server = tokio::spawn(serve_once(
+            socket.clone(),
+            json_response("404 Not Found", r#"{"message":"No such image"}"#),
+        ));
+        while !socket.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let engine = DockerEngine::new(EngineEndpoint::Unix(socket.clone()));
+        let config = engine.inspect_image("redis:nope").await;
+        server.await.unwrap();
+        std::fs::remove_file(&socket).unwrap();
+
+        assert!(config.unwrap().is_none());
+    }
+
+    #[test]
+    fn stream_lines_waits_for_complete_lines_across_chunks() {
+        let response = concat!(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n",
+            "c\r\n{\"status\":\"P\r\n",
+            "a\r\nulling\"}\n{\r\n",
+            "f\r\n\"status\":\"Done\"\r\n",
+            "2\r\n}\n\r\n",
+            "0\r\n\r\n",
+        );
+        let mut stream = StreamLines::default();
+        let mut lines = Vec::new();
+        for byte in response.as_bytes() {
+            lines.extend(stream.feed(std::slice::from_ref(byte)));
+        }
+
+        assert_eq!(lines, [r#"{"status":"Pulling"}"#, r#"{"status":"Done"}"#]);
+    }
+
+    #[tokio::test]
+    async fn pull_image_reports_progress() {
+        let socket = socket_path("pull");
+        let server = tokio::spawn(serve_once(
+            socket.clone(),
+            chunked(&[
+                r#"{"status":"Pulling from library/redis"}"#,
+                r#"{"status":"Downloading","id":"a1","progress":"1"}"#,
+                r#"{"status":"Downloading","id":"a1","progress":"2"}"#,
+                r#"{"status":"Pull complete","id":"a1"}"#,
+            ]),
+        ));
+        while !socket.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let engine = DockerEngine::new(EngineEndpoint::Unix(socket.clone()));
+        let mut progress = Vec::new();
+        let result = engine
+            .pull_image("redis:7", |line| progress.push(line.to_string()))
+            .await;
+        let request = server.await.unwrap();
+        std::fs::remove_file(&socket).unwrap();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert!(request.starts_with("POST /images/create?fromImage=redis&tag=7 "));
+        assert_eq!(
+            progress,
+            [
+                "Pulling from library/redis",
+                "a1: Downloading",
+                "a1: Pull complete"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pull_image_surfaces_stream_errors() {
+        let socket = socket_path("error");
+        let server = tokio::spawn(serve_once(
+            socket.clone(),
+            chunked(&[r#"{"status":"Pulling"}"#, r#"{"error":"manifest unknown"}"#]),
+        ));
+        while !socket.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let engine = DockerEngine::new(EngineEndpoint::Unix(socket.clone()));
+        let result = engine.pull_image("redis:nope", |_| {}).await;
+        server.await.unwrap();
+        std::fs::remove_file(&socket).unwrap();
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("manifest unknown"), "{error}");
+    }
+}
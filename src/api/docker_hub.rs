@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
-use serde::de::IgnoredAny;
 use serde::Deserialize;
+use serde::de::IgnoredAny;
 
 use super::{ApiError, http_client};
 
@@ -47,6 +47,22 @@ struct ConfigBlob {
 struct ImageConfig {
     #[serde(rename = "ExposedPorts")]
     exposed_ports: Option<BTreeMap<String, IgnoredAny>>,
+    #[serde(rename = "Healthcheck")]
+    healthcheck: Option<ImageHealthcheck>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageHealthcheck {
+    #[serde(rename = "Test", default)]
+    pub test: Vec<String>,
+    #[serde(rename = "Interval")]
+    pub interval_ns: Option<u64>,
+    #[serde(rename = "Timeout")]
+    pub timeout_ns: Option<u64>,
+    #[serde(rename = "StartPeriod")]
+    pub start_period_ns: Option<u64>,
+    #[serde(rename = "Retries")]
+    pub retries: Option<u32>,
 }
 
 async fn get_registry_token(image: &str) -> Result<String, ApiError> {
@@ -84,7 +100,11 @@ fn preferred_index_manifest(manifests: &[ManifestDescriptor]) -> Option<&Manifes
         .or_else(|| manifests.first())
 }
 
-async fn fetch_manifest(image: &str, reference: &str, token: &str) -> Result<ManifestEnvelope, ApiError> {
+async fn fetch_manifest(
+    image: &str,
+    reference: &str,
+    token: &str,
+) -> Result<ManifestEnvelope, ApiError> {
     let manifest_url = format!("https://registry-1.docker.io/v2/{image}/manifests/{reference}");
     let manifest = http_client()
         .get(manifest_url)
@@ -119,11 +139,11 @@ pub async fn list_docker_hub_tags(namespace: &str, repo: &str) -> Result<Vec<Str
     Ok(tags_resp.tags.unwrap_or_default())
 }
 
-pub async fn list_docker_hub_exposed_ports(
+async fn fetch_image_config(
     namespace: &str,
     repo: &str,
     tag: &str,
-) -> Result<Vec<u16>, ApiError> {
+) -> Result<Option<ImageConfig>, ApiError> {
     let image = format!("{namespace}/{repo}");
     let token = get_registry_token(&image).await?;
 
@@ -138,7 +158,7 @@ pub async fn list_docker_hub_exposed_ports(
 
     let config_digest = match manifest.config {
         Some(config) => config.digest,
-        None => return Ok(Vec::new()),
+        None => return Ok(None),
     };
 
     let blob_url = format!("https://registry-1.docker.io/v2/{image}/blobs/{config_digest}");
@@ -151,8 +171,16 @@ pub async fn list_docker_hub_exposed_ports(
         .json()
         .await?;
 
-    let mut ports: Vec<u16> = blob
-        .config
+    Ok(blob.config)
+}
+
+pub async fn list_docker_hub_exposed_ports(
+    namespace: &str,
+    repo: &str,
+    tag: &str,
+) -> Result<Vec<u16>, ApiError> {
+    let mut ports: Vec<u16> = fetch_image_config(namespace, repo, tag)
+        .await?
         .and_then(|config| config.exposed_ports)
         .map(|ports| {
             ports
@@ -166,3 +194,14 @@ pub async fn list_docker_hub_exposed_ports(
     ports.dedup();
     Ok(ports)
 }
+
+pub async fn fetch_docker_hub_healthcheck(
+    namespace: &str,
+    repo: &str,
+    tag: &str,
+) -> Result<Option<ImageHealthcheck>, ApiError> {
+    Ok(fetch_image_config(namespace, repo, tag)
+        .await?
+        .and_then(|config| config.healthcheck)
+        .filter(|healthcheck| !healthcheck.test.is_empty()))
+}
@@ -1,15 +1,17 @@
+mod docker_engine;
 mod docker_hub;
 mod ranking;
 mod repo_resolution;
 
 use std::sync::OnceLock;
 
-pub use docker_hub::{list_docker_hub_exposed_ports, list_docker_hub_tags};
-pub use ranking::{filter_tags, search_docker_hub_tags};
-pub use repo_resolution::{
-    auto_search_docker_hub_tags,
-    resolve_docker_hub_repository,
+pub use docker_engine::{DockerEngine, LocalImageConfig};
+pub use docker_hub::{
+    ImageHealthcheck, fetch_docker_hub_healthcheck, list_docker_hub_exposed_ports,
+    list_docker_hub_tags,
 };
+pub use ranking::{filter_tags, search_docker_hub_tags};
+pub use repo_resolution::{auto_search_docker_hub_tags, resolve_docker_hub_repository};
 
 pub type ApiError = Box<dyn std::error::Error + Send + Sync>;
 
@@ -38,6 +38,24 @@ enum Commands {
         #[arg(short, long, default_value_t = 15)]
         limit: usize,
     },
+    New {
+        #[arg(short, long)]
+        template: Option<String>,
+    },
+    Init,
+    Open {
+        path: Option<std::path::PathBuf>,
+    },
+    ImportRun {
+        command: String,
+    },
+    Export {
+        #[arg(short, long)]
+        format: String,
+        path: Option<std::path::PathBuf>,
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
     Completions {
         #[arg(value_enum)]
         shell: Shell,
@@ -70,12 +88,12 @@ fn main() {
                 .unwrap();
 
             let tags = runtime
-                .block_on(api::search_docker_hub_tags(&namespace, &repo, &query, limit))
+                .block_on(api::search_docker_hub_tags(
+                    &namespace, &repo, &query, limit,
+                ))
                 .unwrap();
 
-            println!(
-                "search namespace={namespace} repo={repo} query='{query}' limit={limit}"
-            );
+            println!("search namespace={namespace} repo={repo} query='{query}' limit={limit}");
             for tag in tags {
                 println!("{tag}");
             }
@@ -109,6 +127,18 @@ fn main() {
                 }
             }
         }
+        Some(Commands::New { template }) => match template {
+            Some(name) => tui::run_with_template(&name).unwrap(),
+            None => tui::print_templates(),
+        },
+        Some(Commands::Init) => tui::run_init().unwrap(),
+        Some(Commands::Open { path }) => tui::run_open(path).unwrap(),
+        Some(Commands::ImportRun { command }) => tui::run_import(&command).unwrap(),
+        Some(Commands::Export {
+            format,
+            path,
+            output,
+        }) => tui::run_export(&format, path, output).unwrap(),
         Some(Commands::Completions { shell }) => {
             let mut cmd = Cli::command();
             generate(shell, &mut cmd, "acdc", &mut std::io::stdout());
@@ -1,24 +1,114 @@
-use crate::tui::tab::Tab;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+use crate::tui::{
+    build::BuildConfig,
+    command::{CommandDraft, CommandField, CommandSpec},
+    compose_files::{self, ComposeFile, Extends, LoadedProject},
+    deploy::DeployConfig,
+    detail::DetailSection,
+    engine::{EngineQueries, PullTask},
+    env_files::{self, EnvFile, EnvFileKeys, EnvFileMode},
+    environments::{self, Environment},
+    export::ExportFormat,
+    healthcheck,
+    paths::{self, PathAssist},
+    ports::{self, PortChoice},
+    properties::{KeyValue, PropertySheet, ServiceOptions},
+    runner::{ComposeRunner, LogViewer},
+    scan::ScanProposal,
+    secrets,
+    tab::Tab,
+    templates::StackTemplate,
+    volumes::{MountOptions, MountType},
+};
+
+#[derive(Debug, Clone, Default)]
 pub struct ImageEntry {
     pub service_name: String,
     pub namespace: String,
     pub repo: String,
     pub tag: String,
+    pub build: Option<BuildConfig>,
     pub port_mapping: String,
-    pub command: Option<String>,
+    pub command: Option<CommandSpec>,
     pub mounts: Vec<VolumeMount>,
     pub env_vars: Vec<EnvVar>,
+    pub env_files: Vec<EnvFile>,
+    pub depends_on: Vec<String>,
+    pub secrets: Vec<String>,
+    pub healthcheck: Option<Healthcheck>,
+    pub options: ServiceOptions,
+    pub deploy: DeployConfig,
+    pub source_file: Option<String>,
+    pub extends: Option<Extends>,
+}
+
+impl ImageEntry {
+    pub fn image_ref(&self) -> Option<String> {
+        if self.repo.is_empty() {
+            None
+        } else if self.namespace == "library" {
+            Some(format!("{}:{}", self.repo, self.tag))
+        } else {
+            Some(format!("{}/{}:{}", self.namespace, self.repo, self.tag))
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match (self.image_ref(), self.build.as_ref()) {
+            (Some(image_ref), Some(build)) => format!("{image_ref} (build {})", build.context),
+            (Some(image_ref), None) => image_ref,
+            (None, Some(build)) => format!("build {}", build.context),
+            (None, None) => match self.extends.as_ref() {
+                Some(extends) => format!("extends {}", extends.service),
+                None => "no image".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthcheckForm {
+    Cmd,
+    CmdShell,
+}
+
+impl HealthcheckForm {
+    pub fn next(self) -> Self {
+        match self {
+            HealthcheckForm::Cmd => HealthcheckForm::CmdShell,
+            HealthcheckForm::CmdShell => HealthcheckForm::Cmd,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HealthcheckForm::Cmd => "CMD",
+            HealthcheckForm::CmdShell => "CMD-SHELL",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Healthcheck {
+    pub form: HealthcheckForm,
+    pub test: String,
+    pub interval: Option<String>,
+    pub timeout: Option<String>,
+    pub retries: Option<u32>,
+    pub start_period: Option<String>,
+    pub disable: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct VolumeMount {
     pub source: String,
     pub target: String,
+    pub options: MountOptions,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnvVar {
     pub key: String,
     pub value: String,
@@ -27,6 +117,91 @@ pub struct EnvVar {
 #[derive(Debug, Clone)]
 pub struct VolumeEntry {
     pub name: String,
+    pub driver: Option<String>,
+    pub driver_opts: Vec<KeyValue>,
+    pub external: bool,
+    pub volume_name: Option<String>,
+    pub labels: Vec<KeyValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeUsage {
+    pub service: String,
+    pub environment: Option<String>,
+    pub target: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SecretEntry {
+    pub name: String,
+    pub file: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SecretStorage {
+    DotEnv,
+    File,
+}
+
+impl SecretStorage {
+    pub fn next(self) -> Self {
+        match self {
+            SecretStorage::DotEnv => SecretStorage::File,
+            SecretStorage::File => SecretStorage::DotEnv,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SecretStorage::DotEnv => ".env file",
+            SecretStorage::File => "secrets: file",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildField {
+    Context,
+    Dockerfile,
+    Target,
+    Args,
+    CacheFrom,
+    Image,
+    Name,
+}
+
+impl BuildField {
+    pub fn next(self) -> Self {
+        match self {
+            BuildField::Context => BuildField::Dockerfile,
+            BuildField::Dockerfile => BuildField::Target,
+            BuildField::Target => BuildField::Args,
+            BuildField::Args => BuildField::CacheFrom,
+            BuildField::CacheFrom => BuildField::Image,
+            BuildField::Image => BuildField::Name,
+            BuildField::Name => BuildField::Context,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrideField {
+    Ports,
+    Env,
+    Mounts,
+    Replicas,
+}
+
+impl OverrideField {
+    pub fn next(self) -> Self {
+        match self {
+            OverrideField::Ports => OverrideField::Env,
+            OverrideField::Env => OverrideField::Mounts,
+            OverrideField::Mounts => OverrideField::Replicas,
+            OverrideField::Replicas => OverrideField::Ports,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -50,6 +225,20 @@ impl ConfigureField {
 pub enum ModalState {
     AddImageType {
         input: String,
+        local_images: Vec<String>,
+        selected: Option<usize>,
+    },
+    ConfirmPullImage {
+        reference: String,
+    },
+    ConfirmComposeDown,
+    ImportRun {
+        input: String,
+        report: Option<Vec<String>>,
+    },
+    Export {
+        format: ExportFormat,
+        written: Option<String>,
     },
     SelectImageTag {
         image_term: String,
@@ -73,6 +262,18 @@ pub enum ModalState {
         container_port_typed: bool,
         service_name_typed: bool,
     },
+    EditBuild {
+        existing_index: Option<usize>,
+        context_input: String,
+        dockerfile_input: String,
+        target_input: String,
+        args_input: String,
+        cache_from_input: String,
+        image_input: String,
+        service_name_input: String,
+        active_field: BuildField,
+        context_assist: PathAssist,
+    },
     ConfirmDeleteImage {
         index: usize,
     },
@@ -80,6 +281,10 @@ pub enum ModalState {
     AddVolume {
         input: String,
     },
+    DeleteVolume {
+        volume_index: usize,
+        selected_option: usize,
+    },
     SelectImageVolumeSource {
         image_index: usize,
         selected_option: usize,
@@ -106,6 +311,7 @@ pub enum ModalState {
         active_field: MountInputField,
         local_path_typed: bool,
         target_typed: bool,
+        path_assist: PathAssist,
     },
     RemoveImageMount {
         image_index: usize,
@@ -121,10 +327,115 @@ pub enum ModalState {
         image_index: usize,
         selected_env: usize,
     },
+    ImportEnvFile {
+        image_index: usize,
+        path_input: String,
+        mode: EnvFileMode,
+        required: bool,
+        raw: bool,
+        active_field: EnvFileField,
+        path_assist: PathAssist,
+    },
     SetImageCommand {
         image_index: usize,
+        field: CommandField,
+        command: CommandDraft,
+        entrypoint: CommandDraft,
+        input: String,
+    },
+    SelectTemplate {
+        templates: Vec<StackTemplate>,
+        selected: usize,
+    },
+    ReviewScan {
+        proposals: Vec<ScanProposal>,
+        notes: Vec<String>,
+        selected: usize,
+    },
+    AddEnvironment {
         input: String,
     },
+    EditOverride {
+        image_index: usize,
+        environment_index: usize,
+        port_input: String,
+        env_input: String,
+        mounts_input: String,
+        replicas_input: String,
+        active_field: OverrideField,
+    },
+    GenerateSecret {
+        image_index: usize,
+        key_input: String,
+        storage: SecretStorage,
+    },
+    ServiceProperties {
+        image_index: usize,
+        sheet: PropertySheet,
+        selected: usize,
+        input: Option<String>,
+    },
+    VolumeProperties {
+        volume_index: usize,
+        selected: usize,
+        input: Option<String>,
+    },
+    MountProperties {
+        image_index: usize,
+        mount_index: usize,
+        selected: usize,
+        input: Option<String>,
+    },
+    DuplicateService {
+        image_index: usize,
+        name_input: String,
+        clone_volumes: bool,
+    },
+    ServiceDetail {
+        image_index: usize,
+        section: DetailSection,
+        selected: usize,
+        marked: Vec<usize>,
+        input: Option<String>,
+        adding: bool,
+    },
+    EditHealthcheck {
+        image_index: usize,
+        form: HealthcheckForm,
+        test_input: String,
+        interval_input: String,
+        timeout_input: String,
+        retries_input: String,
+        start_period_input: String,
+        disable: bool,
+        active_field: HealthcheckField,
+        edited: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthcheckField {
+    Form,
+    Test,
+    Interval,
+    Timeout,
+    Retries,
+    StartPeriod,
+    Disable,
+}
+
+impl HealthcheckField {
+    pub fn next(self) -> Self {
+        match self {
+            HealthcheckField::Form => HealthcheckField::Test,
+            HealthcheckField::Test => HealthcheckField::Interval,
+            HealthcheckField::Interval => HealthcheckField::Timeout,
+            HealthcheckField::Timeout => HealthcheckField::Retries,
+            HealthcheckField::Retries => HealthcheckField::StartPeriod,
+            HealthcheckField::StartPeriod => HealthcheckField::Disable,
+            HealthcheckField::Disable => HealthcheckField::Form,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -157,6 +468,25 @@ impl MountExistingField {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvFileField {
+    Path,
+    Mode,
+    Required,
+    Format,
+}
+
+impl EnvFileField {
+    pub fn next(self) -> Self {
+        match self {
+            EnvFileField::Path => EnvFileField::Mode,
+            EnvFileField::Mode => EnvFileField::Required,
+            EnvFileField::Required => EnvFileField::Format,
+            EnvFileField::Format => EnvFileField::Path,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum MountInputField {
     Source,
@@ -187,35 +517,250 @@ impl FocusArea {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListOrder {
+    Manual,
+    Alphabetical,
+    Dependencies,
+}
+
+impl ListOrder {
+    pub fn label(self) -> &'static str {
+        match self {
+            ListOrder::Manual => "manual",
+            ListOrder::Alphabetical => "alphabetical",
+            ListOrder::Dependencies => "dependency order",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ListOrder::Manual => ListOrder::Alphabetical,
+            ListOrder::Alphabetical => ListOrder::Dependencies,
+            ListOrder::Dependencies => ListOrder::Manual,
+        }
+    }
+}
+
+fn cycle_members<'a>(stuck: &[&'a ImageEntry]) -> Vec<&'a str> {
+    let mut members = stuck.to_vec();
+    loop {
+        let before = members.len();
+        let snapshot = members.clone();
+        members.retain(|image| {
+            snapshot
+                .iter()
+                .any(|other| other.depends_on.contains(&image.service_name))
+        });
+        if members.len() == before {
+            return members
+                .iter()
+                .map(|image| image.service_name.as_str())
+                .collect();
+        }
+    }
+}
+
+pub fn dependency_order(images: &[ImageEntry]) -> (Vec<&ImageEntry>, Option<String>) {
+    let mut ordered: Vec<&ImageEntry> = Vec::with_capacity(images.len());
+    let mut remaining: Vec<&ImageEntry> = images.iter().collect();
+    let mut cycle = None;
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|image| {
+            image.depends_on.iter().all(|dependency| {
+                ordered
+                    .iter()
+                    .any(|placed| placed.service_name == *dependency)
+                    || !images.iter().any(|other| other.service_name == *dependency)
+            })
+        });
+        if ready.is_none() && cycle.is_none() {
+            cycle = Some(format!(
+                "depends_on cycle between {}; keeping their list order",
+                cycle_members(&remaining).join(", ")
+            ));
+        }
+        ordered.push(remaining.remove(ready.unwrap_or(0)));
+    }
+    (ordered, cycle)
+}
+
+pub fn default_service_name(repo: &str, current_len: usize) -> String {
+    let base: String = repo
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if base.is_empty() {
+        format!("service_{}", current_len + 1)
+    } else {
+        base
+    }
+}
+
+pub fn yaml_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
 pub struct App {
     pub focus: FocusArea,
     pub active_tab: Tab,
     pub project_name: String,
+    pub compose_name: Option<String>,
+    pub compose_file: String,
+    pub includes: Vec<String>,
+    pub compose_files: Vec<ComposeFile>,
+    pub extends_sources: Vec<ImageEntry>,
+    pub dropped_content: Vec<String>,
     pub command_log: Vec<String>,
     pub images: Vec<ImageEntry>,
     pub images_selected: usize,
     pub volumes: Vec<VolumeEntry>,
     pub volumes_selected: usize,
+    pub list_order: ListOrder,
+    sorted_signature: Option<u64>,
+    pub preview_scroll: u16,
+    pub dotenv: Vec<EnvVar>,
+    pub env_file_keys: EnvFileKeys,
+    pub secrets: Vec<SecretEntry>,
+    pub environments: Vec<Environment>,
+    pub active_environment: Option<usize>,
     pub modal: Option<ModalState>,
+    pub runner: ComposeRunner,
+    pub pulls: Vec<PullTask>,
+    pub engine: EngineQueries,
+    pub log_viewer: Option<LogViewer>,
+    pub listening_ports: Vec<u16>,
+    pub check_host_ports: bool,
 }
 
 impl App {
     pub fn new() -> Self {
         let project_name = std::env::current_dir()
             .ok()
-            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+            .and_then(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
             .unwrap_or_else(|| "unknown-project".to_string());
 
         Self {
             focus: FocusArea::Sidebar,
             active_tab: Tab::Project,
             project_name,
+            compose_name: None,
+            compose_file: environments::BASE_COMPOSE_FILE.to_string(),
+            includes: Vec::new(),
+            compose_files: Vec::new(),
+            extends_sources: Vec::new(),
+            dropped_content: Vec::new(),
             command_log: vec!["ready".to_string()],
             images: Vec::new(),
             images_selected: 0,
             volumes: Vec::new(),
             volumes_selected: 0,
+            list_order: ListOrder::Manual,
+            sorted_signature: None,
+            preview_scroll: 0,
+            dotenv: Vec::new(),
+            env_file_keys: EnvFileKeys::default(),
+            secrets: Vec::new(),
+            environments: environments::DEFAULT_ENVIRONMENTS
+                .iter()
+                .map(|name| Environment::new(name))
+                .collect(),
+            active_environment: None,
             modal: None,
+            runner: ComposeRunner::new(),
+            pulls: Vec::new(),
+            engine: EngineQueries::new(),
+            log_viewer: None,
+            listening_ports: Vec::new(),
+            check_host_ports: true,
+        }
+    }
+
+    pub fn load_project(&mut self, project: LoadedProject) -> Vec<String> {
+        let mut logs = vec![format!(
+            "opened {}: {} services from {} files",
+            project.root_file,
+            project.images.len(),
+            project.files.len() + 1
+        )];
+        logs.extend(compose_files::unresolved_extends(
+            &project.root_file,
+            &project.images,
+            &project.extends_sources,
+        ));
+        logs.extend(project.warnings);
+        logs.extend(project.dropped.iter().cloned());
+        self.dropped_content = project.dropped;
+
+        if let Some(name) = project.name.as_ref() {
+            self.project_name = name.clone();
+        }
+        self.compose_name = project.name;
+        self.compose_file = project.root_file;
+        self.includes = project.includes;
+        self.compose_files = project.files;
+        self.extends_sources = project.extends_sources;
+        self.images = project.images;
+        self.images_selected = 0;
+        self.volumes = project.volumes;
+        self.volumes_selected = 0;
+        self.secrets = project.secrets;
+        for environment in project.environments {
+            logs.push(format!(
+                "loaded {} overrides from {}",
+                environment.name,
+                environment.file_name()
+            ));
+            match self
+                .environments
+                .iter_mut()
+                .find(|existing| existing.name == environment.name)
+            {
+                Some(existing) => *existing = environment,
+                None => self.environments.push(environment),
+            }
+        }
+        self.refresh_env_files();
+        logs
+    }
+
+    pub fn refresh_env_files(&mut self) {
+        let base = self.project_dir();
+        self.env_file_keys.refresh(&base, &self.images);
+    }
+
+    pub fn compose_run_files(&self) -> Vec<String> {
+        let mut files = vec![self.compose_file.clone()];
+        if let Some(environment) = self.active_environment()
+            && std::path::Path::new(&environment.file_name()).exists()
+        {
+            files.push(environment.file_name());
+        }
+        files
+    }
+
+    pub fn poll_runtime(&mut self) {
+        let files = self.compose_run_files();
+        for line in self.runner.poll(&files) {
+            self.push_log(line);
+        }
+        let pulled: Vec<String> = self.pulls.iter_mut().flat_map(PullTask::poll).collect();
+        self.pulls.retain(|pull| !pull.finished);
+        for line in pulled {
+            self.push_log(line);
+        }
+        if let Some(viewer) = self.log_viewer.as_mut() {
+            viewer.poll();
         }
     }
 
@@ -226,9 +771,428 @@ impl App {
         }
     }
 
+    fn taken_host_ports(&self, except: Option<usize>) -> Vec<(u16, &str)> {
+        let excepted = except
+            .and_then(|index| self.images.get(index))
+            .map(|image| image.service_name.as_str());
+        let overrides = self
+            .environments
+            .iter()
+            .flat_map(|environment| &environment.overrides)
+            .filter(|service_override| Some(service_override.service.as_str()) != excepted)
+            .filter_map(|service_override| {
+                let port = ports::host_port(service_override.port_mapping.as_deref()?)?;
+                Some((port, service_override.service.as_str()))
+            });
+        self.images
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| Some(*index) != except)
+            .filter_map(|(_, image)| {
+                ports::host_port(&image.port_mapping)
+                    .map(|port| (port, image.service_name.as_str()))
+            })
+            .chain(overrides)
+            .collect()
+    }
+
+    pub fn refresh_listening_ports(&mut self) {
+        if !self.check_host_ports {
+            self.listening_ports.clear();
+            return;
+        }
+        let published: Vec<u16> = self
+            .images
+            .iter()
+            .filter(|image| {
+                self.runner
+                    .status_for(&image.service_name)
+                    .is_some_and(|status| status.state == "running")
+            })
+            .filter_map(|image| ports::host_port(&image.port_mapping))
+            .collect();
+        self.listening_ports = ports::listening_ports();
+        self.listening_ports
+            .retain(|port| !published.contains(port));
+    }
+
+    pub fn allocate_host_port(&self, container: u16, except: Option<usize>) -> PortChoice {
+        ports::allocate(
+            container,
+            &self.taken_host_ports(except),
+            &self.listening_ports,
+        )
+    }
+
+    pub fn explain_host_port(&self, host: u16, container: u16, except: Option<usize>) -> String {
+        let choice = self.allocate_host_port(container, except);
+        let explanation = if choice.host == host {
+            choice.reason
+        } else {
+            match ports::conflict(host, &self.taken_host_ports(except), &self.listening_ports) {
+                Some(conflict) => format!(
+                    "warning: {} (next free: {})",
+                    conflict.describe(host),
+                    choice.host
+                ),
+                None => format!("{host} is free"),
+            }
+        };
+        if self.check_host_ports {
+            explanation
+        } else {
+            format!("{explanation} (host port check off)")
+        }
+    }
+
     pub fn next_port_mapping(&self) -> String {
-        let host_port = 8000 + self.images.len() as u16;
-        format!("{host_port}:80")
+        self.suggested_port_mapping(None)
+    }
+
+    pub fn suggested_port_mapping(&self, suggested_container_port: Option<u16>) -> String {
+        self.allocate_host_port(suggested_container_port.unwrap_or(80), None)
+            .mapping()
+    }
+
+    pub fn unique_service_name(&self, base: &str) -> String {
+        if !self.images.iter().any(|image| image.service_name == base) {
+            return base.to_string();
+        }
+
+        let mut suffix = 2;
+        loop {
+            let candidate = format!("{base}_{suffix}");
+            if !self
+                .images
+                .iter()
+                .any(|image| image.service_name == candidate)
+            {
+                return candidate;
+            }
+            suffix += 1;
+        }
+    }
+
+    pub fn unique_volume_name(&self, base: &str) -> String {
+        let mut candidate = base.to_string();
+        let mut suffix = 2;
+        while self.volumes.iter().any(|volume| volume.name == candidate) {
+            candidate = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        candidate
+    }
+
+    pub fn free_port_mapping(&self, mapping: &str) -> String {
+        let (Some((host, container)), Some(host_port)) =
+            (mapping.rsplit_once(':'), ports::host_port(mapping))
+        else {
+            return mapping.to_string();
+        };
+        let port = self.allocate_host_port(host_port, None).host;
+        if port == host_port {
+            return mapping.to_string();
+        }
+        match host.rsplit_once(':') {
+            Some((address, _)) => format!("{address}:{port}:{container}"),
+            None => format!("{port}:{container}"),
+        }
+    }
+
+    pub fn duplicate_name(&self, index: usize) -> String {
+        let base = self
+            .images
+            .get(index)
+            .map(|image| default_service_name(&image.service_name, self.images.len()))
+            .unwrap_or_default();
+        self.unique_service_name(&base)
+    }
+
+    pub fn duplicate_service(
+        &mut self,
+        index: usize,
+        name: &str,
+        clone_volumes: bool,
+    ) -> Vec<String> {
+        let Some(mut image) = self.images.get(index).cloned() else {
+            return Vec::new();
+        };
+        let mut logs = Vec::new();
+        let source = image.service_name.clone();
+        image.service_name =
+            self.unique_service_name(&default_service_name(name, self.images.len()));
+
+        image.port_mapping = self.free_port_mapping(&image.port_mapping);
+
+        for mount in image
+            .mounts
+            .iter_mut()
+            .filter(|mount| mount.is_named_volume())
+        {
+            let volume = self
+                .volumes
+                .iter()
+                .find(|volume| volume.name == mount.source)
+                .cloned();
+            if !clone_volumes || volume.as_ref().is_some_and(|volume| volume.external) {
+                logs.push(format!(
+                    "{} shares volume {} with {source}",
+                    image.service_name, mount.source
+                ));
+                continue;
+            }
+            let mut copy = volume.unwrap_or_else(|| VolumeEntry::new(mount.source.clone()));
+            let candidate =
+                self.unique_volume_name(&format!("{}_{}", mount.source, image.service_name));
+            logs.push(format!("cloned volume {} as {candidate}", mount.source));
+            copy.name = candidate.clone();
+            copy.volume_name = None;
+            mount.source = candidate;
+            self.volumes.push(copy);
+        }
+
+        logs.insert(
+            0,
+            format!(
+                "duplicated {source} as {}{}",
+                image.service_name,
+                if image.port_mapping.is_empty() {
+                    String::new()
+                } else {
+                    format!(" on {}", image.port_mapping)
+                }
+            ),
+        );
+        self.ensure_named_volumes(&image);
+        self.images.insert(index + 1, image);
+        self.images_selected = index + 1;
+        logs
+    }
+
+    pub fn move_image(&mut self, up: bool) -> bool {
+        let index = self.images_selected;
+        let target = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1)
+        };
+        match target.filter(|target| *target < self.images.len() && index < self.images.len()) {
+            Some(target) => {
+                self.images.swap(index, target);
+                self.images_selected = target;
+                self.list_order = ListOrder::Manual;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn move_volume(&mut self, up: bool) -> bool {
+        let index = self.volumes_selected;
+        let target = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1)
+        };
+        match target.filter(|target| *target < self.volumes.len() && index < self.volumes.len()) {
+            Some(target) => {
+                self.volumes.swap(index, target);
+                self.volumes_selected = target;
+                self.list_order = ListOrder::Manual;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn list_signature(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.list_order.hash(&mut hasher);
+        for image in &self.images {
+            image.service_name.hash(&mut hasher);
+            image.depends_on.hash(&mut hasher);
+            for mount in &image.mounts {
+                mount.source.hash(&mut hasher);
+            }
+        }
+        for volume in &self.volumes {
+            volume.name.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    pub fn apply_list_order(&mut self) {
+        if self.list_order == ListOrder::Manual || self.modal.is_some() {
+            return;
+        }
+        if self.sorted_signature == Some(self.list_signature()) {
+            return;
+        }
+        let selected_image = self
+            .images
+            .get(self.images_selected)
+            .map(|image| image.service_name.clone());
+        let selected_volume = self
+            .volumes
+            .get(self.volumes_selected)
+            .map(|volume| volume.name.clone());
+
+        match self.list_order {
+            ListOrder::Manual => {}
+            ListOrder::Alphabetical => {
+                self.images
+                    .sort_by(|left, right| left.service_name.cmp(&right.service_name));
+                self.volumes
+                    .sort_by(|left, right| left.name.cmp(&right.name));
+            }
+            ListOrder::Dependencies => {
+                let (ordered, cycle) = dependency_order(&self.images);
+                self.images = ordered.into_iter().cloned().collect();
+                if let Some(cycle) = cycle {
+                    self.push_log(cycle);
+                }
+                let images = &self.images;
+                self.volumes.sort_by_key(|volume| {
+                    images
+                        .iter()
+                        .position(|image| {
+                            image.mounts.iter().any(|mount| mount.source == volume.name)
+                        })
+                        .unwrap_or(images.len())
+                });
+            }
+        }
+
+        if let Some(name) = selected_image {
+            self.images_selected = self
+                .images
+                .iter()
+                .position(|image| image.service_name == name)
+                .unwrap_or(self.images_selected);
+        }
+        if let Some(name) = selected_volume {
+            self.volumes_selected = self
+                .volumes
+                .iter()
+                .position(|volume| volume.name == name)
+                .unwrap_or(self.volumes_selected);
+        }
+        self.sorted_signature = Some(self.list_signature());
+    }
+
+    pub fn ensure_named_volumes(&mut self, image: &ImageEntry) {
+        for mount in &image.mounts {
+            if mount.is_named_volume()
+                && !self
+                    .volumes
+                    .iter()
+                    .any(|volume| volume.name == mount.source)
+            {
+                self.volumes.push(VolumeEntry::new(mount.source.clone()));
+            }
+        }
+    }
+
+    pub fn volume_usage(&self, name: &str) -> Vec<VolumeUsage> {
+        let mut usage: Vec<VolumeUsage> = self
+            .images
+            .iter()
+            .flat_map(|image| {
+                image
+                    .mounts
+                    .iter()
+                    .filter(|mount| mount.is_named_volume() && mount.source == name)
+                    .map(|mount| VolumeUsage {
+                        service: image.service_name.clone(),
+                        environment: None,
+                        target: mount.container_path().to_string(),
+                    })
+            })
+            .collect();
+        for environment in &self.environments {
+            for service in &environment.overrides {
+                usage.extend(
+                    service
+                        .mounts
+                        .iter()
+                        .filter(|mount| mount.is_named_volume() && mount.source == name)
+                        .map(|mount| VolumeUsage {
+                            service: service.service.clone(),
+                            environment: Some(environment.name.clone()),
+                            target: mount.container_path().to_string(),
+                        }),
+                );
+            }
+        }
+        usage
+    }
+
+    pub fn unmount_volume(&mut self, name: &str) -> usize {
+        let mut removed = 0;
+        let mut unmount = |mounts: &mut Vec<VolumeMount>| {
+            let before = mounts.len();
+            mounts.retain(|mount| !(mount.is_named_volume() && mount.source == name));
+            removed += before - mounts.len();
+        };
+        for image in &mut self.images {
+            unmount(&mut image.mounts);
+        }
+        for environment in &mut self.environments {
+            for service in &mut environment.overrides {
+                unmount(&mut service.mounts);
+            }
+            environment.overrides.retain(|service| !service.is_empty());
+        }
+        removed
+    }
+
+    pub fn retarget_volume(&mut self, from: &str, to: &str) -> usize {
+        let mounts = self
+            .images
+            .iter_mut()
+            .flat_map(|image| image.mounts.iter_mut())
+            .chain(
+                self.environments
+                    .iter_mut()
+                    .flat_map(|environment| environment.overrides.iter_mut())
+                    .flat_map(|service| service.mounts.iter_mut()),
+            );
+        let mut moved = 0;
+        for mount in mounts.filter(|mount| mount.is_named_volume() && mount.source == from) {
+            mount.source = to.to_string();
+            moved += 1;
+        }
+        moved
+    }
+
+    pub fn project_dir(&self) -> PathBuf {
+        Path::new(&self.compose_file)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    pub fn missing_bind_dirs(&self) -> Vec<String> {
+        let base = self.project_dir();
+        let mut missing: Vec<String> = self
+            .images
+            .iter()
+            .flat_map(|image| image.mounts.iter())
+            .chain(
+                self.environments
+                    .iter()
+                    .flat_map(|environment| environment.overrides.iter())
+                    .flat_map(|service| service.mounts.iter()),
+            )
+            .filter(|mount| mount.kind() == MountType::Bind && !mount.source.starts_with('$'))
+            .filter(|mount| Path::new(&mount.source).extension().is_none())
+            .filter(|mount| !paths::exists(&base, &mount.source))
+            .map(|mount| mount.source.clone())
+            .collect();
+        missing.sort();
+        missing.dedup();
+        missing
     }
 
     pub fn total_exposed_ports(&self) -> usize {
@@ -238,55 +1202,555 @@ impl App {
             .count()
     }
 
+    pub fn secret_warnings(&self) -> Vec<String> {
+        self.images
+            .iter()
+            .flat_map(|image| {
+                image
+                    .env_vars
+                    .iter()
+                    .filter(|env| {
+                        secrets::is_secret_key(&env.key)
+                            && !env.value.is_empty()
+                            && !secrets::is_interpolated(&env.value)
+                    })
+                    .map(move |env| {
+                        format!("{}: {} holds a literal secret", image.service_name, env.key)
+                    })
+            })
+            .collect()
+    }
+
+    pub fn service_has_healthcheck(&self, service_name: &str) -> bool {
+        self.images.iter().any(|image| {
+            image.service_name == service_name
+                && image
+                    .healthcheck
+                    .as_ref()
+                    .is_some_and(|healthcheck| !healthcheck.disable)
+        })
+    }
+
+    pub fn active_environment(&self) -> Option<&Environment> {
+        self.active_environment
+            .and_then(|index| self.environments.get(index))
+    }
+
+    pub fn environment_label(&self) -> String {
+        match self.active_environment() {
+            Some(environment) => environment.name.clone(),
+            None => "base".to_string(),
+        }
+    }
+
+    pub fn cycle_environment(&mut self) {
+        self.active_environment = match self.active_environment {
+            None if !self.environments.is_empty() => Some(0),
+            Some(index) if index + 1 < self.environments.len() => Some(index + 1),
+            _ => None,
+        };
+    }
+
+    pub fn rename_service(&mut self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+        for environment in &mut self.environments {
+            environment.rename_service(from, to);
+        }
+        for dependency in self
+            .images
+            .iter_mut()
+            .flat_map(|image| image.depends_on.iter_mut())
+        {
+            if dependency == from {
+                *dependency = to.to_string();
+            }
+        }
+    }
+
+    pub fn remove_service(&mut self, index: usize) -> Option<ImageEntry> {
+        if index >= self.images.len() {
+            return None;
+        }
+        let removed = self.images.remove(index);
+        if self.images.is_empty() {
+            self.images_selected = 0;
+        } else if self.images_selected >= self.images.len() {
+            self.images_selected = self.images.len() - 1;
+        }
+        for environment in &mut self.environments {
+            environment.remove_service(&removed.service_name);
+        }
+        for image in &mut self.images {
+            image
+                .depends_on
+                .retain(|name| *name != removed.service_name);
+        }
+        Some(removed)
+    }
+
     pub fn compose_yaml(&self) -> String {
+        self.render_file(None)
+    }
+
+    pub fn service_yaml(&self, index: usize) -> Option<String> {
+        self.images
+            .get(index)
+            .map(|image| self.render_services(std::slice::from_ref(image), true))
+    }
+
+    pub fn compose_files_output(&self) -> Vec<(String, String)> {
+        self.compose_files
+            .iter()
+            .map(|file| (file.path.clone(), self.render_file(Some(file))))
+            .collect()
+    }
+
+    pub fn resolved_images(&self) -> Vec<ImageEntry> {
+        compose_files::resolve_extends(&self.compose_file, &self.images, &self.extends_sources)
+    }
+
+    pub fn effective_images(&self) -> Vec<ImageEntry> {
+        let resolved = self.resolved_images();
+        match self.active_environment() {
+            Some(environment) => environment.merged_images(&resolved),
+            None => resolved,
+        }
+    }
+
+    pub fn compose_preview(&self) -> String {
+        let images = self.effective_images();
+        let mut header = String::new();
+        if !self.compose_files.is_empty() {
+            let files: Vec<&str> = self
+                .compose_files
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect();
+            header.push_str(&format!("# includes: {}\n", files.join(", ")));
+        }
+        if self.images.iter().any(|image| image.extends.is_some()) {
+            header.push_str("# extends resolved\n");
+        }
+
+        match self.active_environment() {
+            Some(environment) => format!(
+                "# merged: {} + {}\n# run: {}\n{header}{}",
+                self.compose_file,
+                environment.file_name(),
+                environment.compose_command(&self.compose_file),
+                self.render_compose(&images, true)
+            ),
+            None => format!("{header}{}", self.render_compose(&images, true)),
+        }
+    }
+
+    pub fn environment_files(&self) -> Vec<(String, String)> {
+        self.environments
+            .iter()
+            .filter(|environment| environment.has_overrides())
+            .map(|environment| (environment.file_name(), environment.render(&self.images)))
+            .collect()
+    }
+
+    fn resource_file(&self, mut uses: impl FnMut(&ImageEntry) -> bool) -> Option<&str> {
+        self.images
+            .iter()
+            .filter(|image| uses(image))
+            .map(|image| image.source_file.as_deref())
+            .min_by_key(|file| match file {
+                None => 0,
+                Some(path) => {
+                    1 + self
+                        .compose_files
+                        .iter()
+                        .position(|known| known.path == *path)
+                        .unwrap_or(self.compose_files.len())
+                }
+            })
+            .flatten()
+    }
+
+    fn render_file(&self, file: Option<&ComposeFile>) -> String {
+        let path = file.map(|file| file.path.as_str());
+        let images: Vec<ImageEntry> = self
+            .images
+            .iter()
+            .filter(|image| image.source_file.as_deref() == path)
+            .cloned()
+            .collect();
+        let volumes: Vec<&VolumeEntry> = self
+            .volumes
+            .iter()
+            .filter(|volume| {
+                self.resource_file(|image| {
+                    image.mounts.iter().any(|mount| mount.source == volume.name)
+                }) == path
+            })
+            .collect();
+        let secrets: Vec<&SecretEntry> = self
+            .secrets
+            .iter()
+            .filter(|secret| {
+                self.resource_file(|image| image.secrets.contains(&secret.name)) == path
+            })
+            .collect();
+        let includes = match file {
+            Some(file) => &file.includes,
+            None => &self.includes,
+        };
+
+        let mut output = String::new();
+        if file.is_none()
+            && let Some(name) = self.compose_name.as_ref()
+        {
+            output.push_str(&format!("name: {name}\n\n"));
+        }
+        if !includes.is_empty() {
+            output.push_str("include:\n");
+            for include in includes {
+                output.push_str(&format!("  - {include}\n"));
+            }
+            output.push('\n');
+        }
+        if images.is_empty() && file.is_some() {
+            output.push_str("services: {}\n");
+        } else if !images.is_empty() || includes.is_empty() {
+            output.push_str(&self.render_services(&images, false));
+        }
+        Self::render_resources(&mut output, &volumes, &secrets);
+        output
+    }
+
+    fn render_resources(output: &mut String, volumes: &[&VolumeEntry], secrets: &[&SecretEntry]) {
+        if !volumes.is_empty() {
+            output.push_str("\nvolumes:\n");
+            for volume in volumes {
+                volume.render(output);
+            }
+        }
+
+        if !secrets.is_empty() {
+            output.push_str("\nsecrets:\n");
+            for secret in secrets {
+                output.push_str(&format!("  {}:\n    file: {}\n", secret.name, secret.file));
+            }
+        }
+    }
+
+    fn render_compose(&self, images: &[ImageEntry], mask_secrets: bool) -> String {
+        let mut output = self.render_services(images, mask_secrets);
+        let volumes: Vec<&VolumeEntry> = self.volumes.iter().collect();
+        let secrets: Vec<&SecretEntry> = self.secrets.iter().collect();
+        Self::render_resources(&mut output, &volumes, &secrets);
+        output
+    }
+
+    fn render_services(&self, images: &[ImageEntry], mask_secrets: bool) -> String {
         let mut output = String::from("services:\n");
 
-        if self.images.is_empty() {
+        if images.is_empty() {
             output.push_str("  # No services yet\n");
             output.push_str("  # Press n in Images tab to add one\n");
             return output;
         }
 
-        for image in &self.images {
-            let image_ref = if image.namespace == "library" {
-                format!("{}:{}", image.repo, image.tag)
-            } else {
-                format!("{}/{}:{}", image.namespace, image.repo, image.tag)
-            };
+        for image in images {
+            output.push_str(&format!("  {}:\n", image.service_name));
+            if let Some(extends) = image.extends.as_ref() {
+                extends.render(&mut output);
+            }
+            if let Some(image_ref) = image.image_ref() {
+                output.push_str(&format!("    image: {image_ref}\n"));
+            }
+            if let Some(build) = image.build.as_ref() {
+                build.render(&mut output);
+            }
 
-            output.push_str(&format!(
-                "  {}:\n    image: {}\n    ports:\n      - \"{}\"\n",
-                image.service_name, image_ref, image.port_mapping
-            ));
+            if !image.port_mapping.is_empty() {
+                output.push_str(&format!("    ports:\n      - \"{}\"\n", image.port_mapping));
+            }
 
             if let Some(command) = image.command.as_ref() {
-                if !command.is_empty() {
-                    output.push_str(&format!("    command: {}\n", command));
-                }
+                command.render("command", &mut output);
             }
 
             if !image.mounts.is_empty() {
                 output.push_str("    volumes:\n");
                 for mount in &image.mounts {
-                    output.push_str(&format!("      - {}:{}\n", mount.source, mount.target));
+                    mount.render(&mut output);
                 }
             }
 
+            env_files::render(&image.env_files, &mut output);
             if !image.env_vars.is_empty() {
                 output.push_str("    environment:\n");
                 for env in &image.env_vars {
-                    output.push_str(&format!("      {}: {}\n", env.key, env.value));
+                    let value = if mask_secrets {
+                        secrets::masked_value(env)
+                    } else {
+                        env.value.as_str()
+                    };
+                    output.push_str(&format!("      {}: {}\n", env.key, yaml_quote(value)));
                 }
             }
-        }
 
-        if !self.volumes.is_empty() {
-            output.push_str("\nvolumes:\n");
-            for volume in &self.volumes {
-                output.push_str(&format!("  {}:\n", volume.name));
+            image.options.render(&mut output);
+            image.deploy.render(&mut output);
+
+            if let Some(healthcheck) = image.healthcheck.as_ref() {
+                output.push_str("    healthcheck:\n");
+                if healthcheck.disable {
+                    output.push_str("      disable: true\n");
+                } else {
+                    output.push_str(&format!(
+                        "      test: {}\n",
+                        healthcheck::test_yaml(healthcheck)
+                    ));
+                    for (key, value) in [
+                        ("interval", &healthcheck.interval),
+                        ("timeout", &healthcheck.timeout),
+                        ("start_period", &healthcheck.start_period),
+                    ] {
+                        if let Some(value) = value {
+                            output.push_str(&format!("      {key}: {value}\n"));
+                        }
+                    }
+                    if let Some(retries) = healthcheck.retries {
+                        output.push_str(&format!("      retries: {retries}\n"));
+                    }
+                }
+            }
+
+            if !image.depends_on.is_empty() {
+                output.push_str("    depends_on:\n");
+                let any_healthy = image
+                    .depends_on
+                    .iter()
+                    .any(|dependency| self.service_has_healthcheck(dependency));
+                for dependency in &image.depends_on {
+                    if any_healthy {
+                        let condition = if self.service_has_healthcheck(dependency) {
+                            "service_healthy"
+                        } else {
+                            "service_started"
+                        };
+                        output.push_str(&format!(
+                            "      {}:\n        condition: {}\n",
+                            dependency, condition
+                        ));
+                    } else {
+                        output.push_str(&format!("      - {}\n", dependency));
+                    }
+                }
+            }
+
+            if !image.secrets.is_empty() {
+                output.push_str("    secrets:\n");
+                for secret in &image.secrets {
+                    output.push_str(&format!("      - {}\n", secret));
+                }
             }
         }
 
         output
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_and_rename_update_depends_on() {
+        let mut app = App::new();
+        for (name, depends_on) in [
+            ("db", vec![]),
+            ("cache", vec![]),
+            ("web", vec!["db", "cache"]),
+        ] {
+            app.images.push(ImageEntry {
+                service_name: name.to_string(),
+                depends_on: depends_on.into_iter().map(ToString::to_string).collect(),
+                ..ImageEntry::default()
+            });
+        }
+
+        app.rename_service("db", "postgres");
+        app.images[0].service_name = "postgres".to_string();
+        assert_eq!(app.images[2].depends_on, ["postgres", "cache"]);
+
+        let removed = app.remove_service(1).unwrap();
+        assert_eq!(removed.service_name, "cache");
+        assert_eq!(app.images[1].depends_on, ["postgres"]);
+        assert!(app.remove_service(5).is_none());
+    }
+
+    #[test]
+    fn host_port_check_uses_cached_ports_and_can_be_disabled() {
+        let mut app = App::new();
+        app.listening_ports = vec![80];
+        assert_eq!(app.allocate_host_port(80, None).host, 81);
+
+        app.check_host_ports = false;
+        app.refresh_listening_ports();
+        assert!(app.listening_ports.is_empty());
+        assert_eq!(app.allocate_host_port(80, None).host, 80);
+        assert!(
+            app.explain_host_port(80, 80, None)
+                .ends_with("(host port check off)")
+        );
+    }
+
+    fn ordering_app() -> App {
+        let mut app = App::new();
+        for (name, depends_on, volume) in [
+            ("web", vec!["api"], None),
+            ("api", vec!["db"], None),
+            ("db", vec![], Some("pgdata")),
+        ] {
+            app.images.push(ImageEntry {
+                service_name: name.to_string(),
+                depends_on: depends_on.into_iter().map(ToString::to_string).collect(),
+                mounts: volume
+                    .map(|volume| VolumeMount::new(volume, "/data"))
+                    .into_iter()
+                    .collect(),
+                ..ImageEntry::default()
+            });
+        }
+        app.volumes = vec![VolumeEntry::new("cache"), VolumeEntry::new("pgdata")];
+        app
+    }
+
+    fn service_names(app: &App) -> Vec<&str> {
+        app.images
+            .iter()
+            .map(|image| image.service_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn move_image_and_volume_swap_neighbours_and_switch_to_manual() {
+        let mut app = ordering_app();
+        app.list_order = ListOrder::Alphabetical;
+        assert!(!app.move_image(true));
+        assert!(app.move_image(false));
+        assert_eq!(service_names(&app), ["api", "web", "db"]);
+        assert_eq!(app.images_selected, 1);
+        assert_eq!(app.list_order, ListOrder::Manual);
+
+        app.images_selected = 2;
+        assert!(!app.move_image(false));
+
+        app.volumes_selected = 1;
+        assert!(app.move_volume(true));
+        assert_eq!(app.volumes[0].name, "pgdata");
+        assert_eq!(app.volumes_selected, 0);
+        assert!(!app.move_volume(true));
+    }
+
+    #[test]
+    fn manual_order_is_left_alone() {
+        let mut app = ordering_app();
+        app.apply_list_order();
+        assert_eq!(service_names(&app), ["web", "api", "db"]);
+        assert_eq!(app.sorted_signature, None);
+    }
+
+    #[test]
+    fn sorted_modes_reorder_once_and_keep_the_selection() {
+        let mut app = ordering_app();
+        app.images_selected = 0;
+        app.list_order = ListOrder::Alphabetical;
+        app.apply_list_order();
+        assert_eq!(service_names(&app), ["api", "db", "web"]);
+        assert_eq!(app.images_selected, 2);
+        let signature = app.sorted_signature;
+        app.apply_list_order();
+        assert_eq!(app.sorted_signature, signature);
+
+        app.list_order = ListOrder::Dependencies;
+        app.apply_list_order();
+        assert_eq!(service_names(&app), ["db", "api", "web"]);
+        assert_eq!(app.volumes[0].name, "pgdata");
+        assert_ne!(app.sorted_signature, signature);
+
+        app.images.push(ImageEntry {
+            service_name: "cache".to_string(),
+            ..ImageEntry::default()
+        });
+        app.images[0].depends_on.push("cache".to_string());
+        app.apply_list_order();
+        assert_eq!(service_names(&app), ["cache", "db", "api", "web"]);
+    }
+
+    #[test]
+    fn dependency_order_reports_cycles() {
+        let mut app = ordering_app();
+        let (ordered, cycle) = dependency_order(&app.images);
+        let names: Vec<&str> = ordered
+            .iter()
+            .map(|image| image.service_name.as_str())
+            .collect();
+        assert_eq!(names, ["db", "api", "web"]);
+        assert_eq!(cycle, None);
+
+        app.images[2].depends_on.push("api".to_string());
+        let (ordered, cycle) = dependency_order(&app.images);
+        assert_eq!(ordered.len(), 3);
+        assert_eq!(
+            cycle.as_deref(),
+            Some("depends_on cycle between api, db; keeping their list order")
+        );
+    }
+
+    #[test]
+    fn environment_override_ports_count_as_taken() {
+        let mut app = App::new();
+        app.check_host_ports = false;
+        app.images.push(ImageEntry {
+            service_name: "web".to_string(),
+            port_mapping: "3000:3000".to_string(),
+            ..ImageEntry::default()
+        });
+        app.environments[0].set_override(environments::ServiceOverride {
+            service: "web".to_string(),
+            port_mapping: Some("8080:80".to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(app.allocate_host_port(8080, None).host, 8081);
+        assert_eq!(app.allocate_host_port(8080, Some(0)).host, 8080);
+    }
+
+    #[test]
+    fn compose_yaml_quotes_environment_values() {
+        let mut app = App::new();
+        app.images.push(ImageEntry {
+            service_name: "web".to_string(),
+            namespace: "library".to_string(),
+            repo: "nginx".to_string(),
+            tag: "latest".to_string(),
+            env_vars: vec![
+                EnvVar {
+                    key: "GREETING".to_string(),
+                    value: "a: b # not a comment".to_string(),
+                },
+                EnvVar {
+                    key: "ENABLED".to_string(),
+                    value: "yes".to_string(),
+                },
+            ],
+            ..ImageEntry::default()
+        });
+        let yaml: serde_yaml_ng::Value = serde_yaml_ng::from_str(&app.compose_yaml()).unwrap();
+        let environment = &yaml["services"]["web"]["environment"];
+        assert_eq!(
+            environment["GREETING"].as_str(),
+            Some("a: b # not a comment")
+        );
+        assert_eq!(environment["ENABLED"].as_str(), Some("yes"));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::tui::{
+    app::{EnvVar, ImageEntry, VolumeMount, default_service_name},
+    dockerfile::{self, DockerfileInfo, DockerfileStage},
+    properties::KeyValue,
+};
+
+const SKIPPED_DOCKERFILE_ENV: [&str; 3] = ["PATH", "HOME", "LANG"];
+
+#[derive(Debug, Clone, Default)]
+pub struct BuildConfig {
+    pub context: String,
+    pub dockerfile: Option<String>,
+    pub target: Option<String>,
+    pub args: Vec<KeyValue>,
+    pub cache_from: Vec<String>,
+}
+
+impl BuildConfig {
+    pub fn render(&self, output: &mut String) {
+        if self.dockerfile.is_none()
+            && self.target.is_none()
+            && self.args.is_empty()
+            && self.cache_from.is_empty()
+        {
+            output.push_str(&format!("    build: {}\n", self.context));
+            return;
+        }
+
+        output.push_str("    build:\n");
+        output.push_str(&format!("      context: {}\n", self.context));
+        if let Some(dockerfile) = self.dockerfile.as_ref() {
+            output.push_str(&format!("      dockerfile: {dockerfile}\n"));
+        }
+        if let Some(target) = self.target.as_ref() {
+            output.push_str(&format!("      target: {target}\n"));
+        }
+        if !self.args.is_empty() {
+            output.push_str("      args:\n");
+            for arg in &self.args {
+                output.push_str(&format!("        {}: {}\n", arg.key, arg.value));
+            }
+        }
+        if !self.cache_from.is_empty() {
+            output.push_str("      cache_from:\n");
+            for source in &self.cache_from {
+                output.push_str(&format!("        - {source}\n"));
+            }
+        }
+    }
+
+    pub fn read_dockerfile(&self) -> std::io::Result<DockerfileInfo> {
+        dockerfile::read_dockerfile(&dockerfile::dockerfile_path(
+            &self.context,
+            self.dockerfile.as_deref(),
+        ))
+    }
+}
+
+pub fn split_image_ref(input: &str) -> Option<(String, String, String)> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let (name, tag) = match trimmed.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name, tag),
+        _ => (trimmed, "latest"),
+    };
+    let (namespace, repo) = name.split_once('/').unwrap_or(("library", name));
+    Some((namespace.to_string(), repo.to_string(), tag.to_string()))
+}
+
+pub fn service_name_for_context(context: &str) -> String {
+    std::path::Path::new(context)
+        .canonicalize()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| default_service_name(&name.to_string_lossy().to_lowercase(), 0))
+        })
+        .unwrap_or_else(|| "app".to_string())
+}
+
+pub fn select_stage<'a>(
+    info: &'a DockerfileInfo,
+    target: Option<&str>,
+) -> Result<&'a DockerfileStage, String> {
+    info.stage(target).ok_or_else(|| match target {
+        Some(target) => format!("Dockerfile has no stage named {target}"),
+        None => "Dockerfile has no FROM instruction".to_string(),
+    })
+}
+
+pub fn prefill_from_stage(image: &mut ImageEntry, stage: &DockerfileStage) -> Vec<String> {
+    let mut logs = vec![format!("Dockerfile stage based on {}", stage.base_image)];
+
+    for target in &stage.volumes {
+        let suffix = target
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|segment| !segment.is_empty())
+            .unwrap_or("data");
+        image.mounts.push(VolumeMount::new(
+            format!("{}_{}", image.service_name, suffix),
+            target.clone(),
+        ));
+    }
+
+    for env in &stage.env {
+        if SKIPPED_DOCKERFILE_ENV.contains(&env.key.as_str()) || env.value.contains('$') {
+            continue;
+        }
+        image.env_vars.push(EnvVar {
+            key: env.key.clone(),
+            value: env.value.clone(),
+        });
+    }
+
+    if !stage.volumes.is_empty() || !stage.env.is_empty() {
+        logs.push(format!(
+            "prefilled {} mounts and {} env vars from Dockerfile",
+            image.mounts.len(),
+            image.env_vars.len()
+        ));
+    }
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_image_ref_defaults_namespace_and_tag() {
+        assert_eq!(
+            split_image_ref(" nginx "),
+            Some(("library".into(), "nginx".into(), "latest".into()))
+        );
+        assert_eq!(
+            split_image_ref("acme/api:1.2"),
+            Some(("acme".into(), "api".into(), "1.2".into()))
+        );
+        assert_eq!(split_image_ref(""), None);
+    }
+
+    #[test]
+    fn service_name_for_context_is_a_valid_service_key() {
+        let dir = std::env::temp_dir().join(format!("acdc-context-Web App-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let name = service_name_for_context(&dir.to_string_lossy());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(name, format!("acdc-context-web_app-{}", std::process::id()));
+        assert_eq!(service_name_for_context("/no/such/context"), "app");
+    }
+
+    #[test]
+    fn prefill_names_volumes_after_the_service_and_skips_shell_env() {
+        let info = dockerfile::parse_dockerfile(
+            "FROM alpine\nVOLUME /var/lib/data/ /\nENV PATH=/bin MODE=prod HOME_DIR=$HOME\n",
+        );
+        let mut image = ImageEntry {
+            service_name: "api".to_string(),
+            ..ImageEntry::default()
+        };
+        prefill_from_stage(&mut image, select_stage(&info, None).unwrap());
+
+        let sources: Vec<&str> = image
+            .mounts
+            .iter()
+            .map(|mount| mount.source.as_str())
+            .collect();
+        assert_eq!(sources, ["api_data", "api_data"]);
+        assert_eq!(image.env_vars.len(), 1);
+        assert_eq!(image.env_vars[0].key, "MODE");
+        assert!(select_stage(&info, Some("build")).is_err());
+    }
+
+    #[test]
+    fn render_uses_the_short_form_for_a_bare_context() {
+        let mut output = String::new();
+        let mut config = BuildConfig {
+            context: "./api".to_string(),
+            ..BuildConfig::default()
+        };
+        config.render(&mut output);
+        assert_eq!(output, "    build: ./api\n");
+
+        config.target = Some("dev".to_string());
+        output.clear();
+        config.render(&mut output);
+        assert_eq!(
+            output,
+            "    build:\n      context: ./api\n      target: dev\n"
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use serde_yaml_ng::Value;
+
+use crate::tui::{app::yaml_quote, dockerfile};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandSpec {
+    Shell(String),
+    Exec(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandField {
+    Command,
+    Entrypoint,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandDraft {
+    pub exec: bool,
+    pub shell: String,
+    pub args: Vec<String>,
+    pub selected: usize,
+    insert_at: Option<usize>,
+}
+
+pub fn shell_quote(word: &str) -> String {
+    let safe = !word.is_empty()
+        && word
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./:=,@%+".contains(ch));
+    if safe {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+pub fn shell_join(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| shell_quote(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl CommandSpec {
+    pub fn parse(input: &str) -> Option<Self> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        if trimmed.starts_with('[')
+            && let Ok(args) = serde_yaml_ng::from_str::<Vec<String>>(trimmed)
+        {
+            return Some(Self::Exec(args));
+        }
+        Some(Self::Shell(trimmed.to_string()))
+    }
+
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Sequence(items) => Some(Self::Exec(
+                items
+                    .iter()
+                    .filter_map(|item| match item {
+                        Value::String(text) => Some(text.clone()),
+                        Value::Number(number) => Some(number.to_string()),
+                        Value::Bool(flag) => Some(flag.to_string()),
+                        _ => None,
+                    })
+                    .collect(),
+            )),
+            Value::String(text) => Self::parse_shell(text),
+            Value::Number(number) => Self::parse_shell(&number.to_string()),
+            _ => None,
+        }
+    }
+
+    fn parse_shell(input: &str) -> Option<Self> {
+        let trimmed = input.trim();
+        (!trimmed.is_empty()).then(|| Self::Shell(trimmed.to_string()))
+    }
+
+    pub fn words(&self) -> Vec<String> {
+        match self {
+            Self::Shell(text) => dockerfile::split_words(text),
+            Self::Exec(args) => args.clone(),
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            Self::Shell(text) => text.clone(),
+            Self::Exec(args) => {
+                let quoted: Vec<String> = args.iter().map(|arg| yaml_quote(arg)).collect();
+                format!("[{}]", quoted.join(", "))
+            }
+        }
+    }
+
+    pub fn render(&self, key: &str, output: &mut String) {
+        match self {
+            Self::Shell(text) => output.push_str(&format!("    {key}: {}\n", yaml_quote(text))),
+            Self::Exec(args) if args.is_empty() => output.push_str(&format!("    {key}: []\n")),
+            Self::Exec(args) => {
+                output.push_str(&format!("    {key}:\n"));
+                for arg in args {
+                    output.push_str(&format!("      - {}\n", yaml_quote(arg)));
+                }
+            }
+        }
+    }
+}
+
+impl CommandField {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Command => "command",
+            Self::Entrypoint => "entrypoint",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Command => Self::Entrypoint,
+            Self::Entrypoint => Self::Command,
+        }
+    }
+}
+
+impl CommandDraft {
+    pub fn from_spec(spec: Option<&CommandSpec>) -> Self {
+        match spec {
+            Some(CommandSpec::Shell(text)) => Self {
+                exec: false,
+                shell: text.clone(),
+                ..Self::default()
+            },
+            Some(CommandSpec::Exec(args)) => Self {
+                exec: true,
+                shell: String::new(),
+                args: args.clone(),
+                selected: args.len().saturating_sub(1),
+                insert_at: None,
+            },
+            None => Self {
+                exec: true,
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn toggle_form(&mut self) {
+        if self.exec {
+            self.shell = shell_join(&self.args);
+            self.args.clear();
+        } else {
+            self.args = dockerfile::split_words(&self.shell);
+            self.shell.clear();
+            self.selected = self.args.len().saturating_sub(1);
+        }
+        self.exec = !self.exec;
+    }
+
+    pub fn insert(&mut self, input: &str) -> usize {
+        let words = CommandSpec::parse(input)
+            .map(|spec| spec.words())
+            .unwrap_or_default();
+        let count = words.len();
+        let at = match self.insert_at.take() {
+            Some(index) => index.min(self.args.len()),
+            None if self.args.is_empty() => 0,
+            None => self.selected + 1,
+        };
+        for (offset, word) in words.into_iter().enumerate() {
+            self.args.insert(at + offset, word);
+        }
+        if count > 0 {
+            self.selected = at + count - 1;
+        }
+        count
+    }
+
+    pub fn remove_selected(&mut self) -> Option<String> {
+        if self.args.is_empty() {
+            return None;
+        }
+        let removed = self.args.remove(self.selected);
+        self.selected = self.selected.min(self.args.len().saturating_sub(1));
+        Some(removed)
+    }
+
+    pub fn take_selected(&mut self) -> Option<String> {
+        let index = self.selected;
+        let removed = self.remove_selected()?;
+        self.insert_at = Some(index);
+        Some(shell_quote(&removed))
+    }
+
+    pub fn move_selected(&mut self, up: bool) {
+        if up && self.selected > 0 {
+            self.args.swap(self.selected, self.selected - 1);
+            self.selected -= 1;
+        } else if !up && self.selected + 1 < self.args.len() {
+            self.args.swap(self.selected, self.selected + 1);
+            self.selected += 1;
+        }
+    }
+
+    pub fn select(&mut self, up: bool) {
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        } else if self.selected + 1 < self.args.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn spec(&self) -> Option<CommandSpec> {
+        if self.exec {
+            (!self.args.is_empty()).then(|| CommandSpec::Exec(self.args.clone()))
+        } else {
+            CommandSpec::parse_shell(&self.shell)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(words: &[&str]) -> Vec<String> {
+        words.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn shell_quote_leaves_safe_words_alone() {
+        assert_eq!(shell_quote("--port=8080"), "--port=8080");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn shell_join_round_trips_through_words() {
+        for words in [
+            strings(&["redis-server", "--appendonly", "yes"]),
+            strings(&["sh", "-c", "echo \"$HOME\" && exit 1"]),
+            strings(&["printf", "it's", "", "tab\there", "back\\slash"]),
+            strings(&["'", "\"", "\\"]),
+        ] {
+            let joined = shell_join(&words);
+            assert_eq!(
+                CommandSpec::Shell(joined.clone()).words(),
+                words,
+                "{joined}"
+            );
+        }
+    }
+
+    #[test]
+    fn words_split_shell_strings_and_keep_exec_args() {
+        assert_eq!(
+            CommandSpec::Shell(r#"python -m "http.server" 8000 ''"#.to_string()).words(),
+            strings(&["python", "-m", "http.server", "8000", ""])
+        );
+        assert_eq!(
+            CommandSpec::parse(r#"["npm", "run", "dev -- --host"]"#).map(|spec| spec.words()),
+            Some(strings(&["npm", "run", "dev -- --host"]))
+        );
+        assert_eq!(CommandSpec::parse("   "), None);
+    }
+}
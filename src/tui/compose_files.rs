@@ -0,0 +1,1227 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde_yaml_ng::{Mapping, Value};
+
+use crate::tui::{
+    app::{
+        EnvVar, Healthcheck, HealthcheckForm, ImageEntry, SecretEntry, VolumeEntry, VolumeMount,
+    },
+    build::{self, BuildConfig},
+    command::CommandSpec,
+    deploy::DeployProperty,
+    env_files::EnvFile,
+    environments::{Environment, ServiceOverride},
+    healthcheck,
+    properties::{KeyValue, ServiceProperty},
+    volumes::{MountOptions, MountType, SelinuxLabel},
+};
+
+pub const ROOT_FILE_CANDIDATES: [&str; 4] = [
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+const MAX_EXTENDS_DEPTH: usize = 8;
+const OVERRIDE_KEYS: [&str; 4] = ["ports", "environment", "volumes", "deploy"];
+const LEGACY_RESOURCE_KEYS: [&str; 4] = ["cpus", "mem_limit", "pids_limit", "mem_reservation"];
+const TOP_LEVEL_KEYS: [&str; 6] = [
+    "name", "version", "include", "services", "volumes", "secrets",
+];
+const SERVICE_KEYS: [&str; 12] = [
+    "image",
+    "build",
+    "ports",
+    "command",
+    "volumes",
+    "environment",
+    "env_file",
+    "depends_on",
+    "healthcheck",
+    "deploy",
+    "secrets",
+    "extends",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extends {
+    pub service: String,
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ComposeFile {
+    pub path: String,
+    pub includes: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct LoadedProject {
+    pub name: Option<String>,
+    pub root_file: String,
+    pub includes: Vec<String>,
+    pub files: Vec<ComposeFile>,
+    pub images: Vec<ImageEntry>,
+    pub extends_sources: Vec<ImageEntry>,
+    pub volumes: Vec<VolumeEntry>,
+    pub secrets: Vec<SecretEntry>,
+    pub environments: Vec<Environment>,
+    pub warnings: Vec<String>,
+    pub dropped: Vec<String>,
+}
+
+pub fn find_root_file(dir: &Path) -> Option<PathBuf> {
+    ROOT_FILE_CANDIDATES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn normalize(path: &Path) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::RootDir => parts.push(String::new()),
+            Component::ParentDir => match parts.last().map(String::as_str) {
+                Some("") => {}
+                Some(last) if last != ".." => {
+                    parts.pop();
+                }
+                _ => parts.push("..".to_string()),
+            },
+            other => parts.push(other.as_os_str().to_string_lossy().to_string()),
+        }
+    }
+    parts.join("/")
+}
+
+pub fn resolve_relative(from_file: &str, path: &str) -> String {
+    let dir = Path::new(from_file).parent().unwrap_or(Path::new(""));
+    normalize(&dir.join(path))
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Sequence(items) => items.iter().filter_map(scalar_string).collect(),
+        other => scalar_string(other).into_iter().collect(),
+    }
+}
+
+fn key_values(value: &Value) -> Vec<KeyValue> {
+    match value {
+        Value::Mapping(mapping) => mapping
+            .iter()
+            .filter_map(|(key, value)| {
+                Some(KeyValue {
+                    key: scalar_string(key)?,
+                    value: scalar_string(value).unwrap_or_default(),
+                })
+            })
+            .collect(),
+        Value::Sequence(_) => string_list(value)
+            .into_iter()
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) => KeyValue {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                },
+                None => KeyValue {
+                    key: pair,
+                    value: String::new(),
+                },
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn join_pairs(pairs: &[KeyValue], separator: char) -> String {
+    pairs
+        .iter()
+        .map(|pair| format!("{}{separator}{}", pair.key, pair.value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn get<'a>(mapping: &'a Mapping, key: &str) -> Option<&'a Value> {
+    mapping.get(Value::String(key.to_string()))
+}
+
+fn parse_port(value: &Value) -> Option<String> {
+    match value {
+        Value::Mapping(mapping) => {
+            let target = get(mapping, "target").and_then(scalar_string)?;
+            Some(match get(mapping, "published").and_then(scalar_string) {
+                Some(published) => format!("{published}:{target}"),
+                None => target,
+            })
+        }
+        other => scalar_string(other),
+    }
+}
+
+fn parse_mount(value: &Value) -> Option<VolumeMount> {
+    match value {
+        Value::Mapping(mapping) => {
+            let kind = get(mapping, "type")
+                .and_then(scalar_string)
+                .and_then(|kind| MountType::parse(&kind));
+            let source = get(mapping, "source")
+                .and_then(scalar_string)
+                .unwrap_or_default();
+            let target = get(mapping, "target").and_then(scalar_string)?;
+            if source.is_empty() && kind != Some(MountType::Tmpfs) {
+                return None;
+            }
+            let nested = |section: &str, key: &str| {
+                get(mapping, section)
+                    .and_then(Value::as_mapping)
+                    .and_then(|section| get(section, key))
+            };
+            let flag = |section: &str, key: &str| {
+                nested(section, key)
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+            };
+
+            let options = MountOptions {
+                kind: kind.filter(|kind| *kind != MountType::infer(&source)),
+                long_syntax: true,
+                read_only: get(mapping, "read_only")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                selinux: nested("bind", "selinux")
+                    .and_then(scalar_string)
+                    .and_then(|selinux| SelinuxLabel::parse(&selinux)),
+                propagation: nested("bind", "propagation").and_then(scalar_string),
+                create_host_path: flag("bind", "create_host_path"),
+                nocopy: flag("volume", "nocopy"),
+                subpath: nested("volume", "subpath").and_then(scalar_string),
+                tmpfs_size: nested("tmpfs", "size").and_then(scalar_string),
+            };
+            Some(VolumeMount {
+                source,
+                target,
+                options,
+            })
+        }
+        other => {
+            let text = scalar_string(other)?;
+            let (source, target) = text.split_once(':')?;
+            Some(VolumeMount::new(source.to_string(), target.to_string()))
+        }
+    }
+}
+
+fn parse_build(value: &Value) -> Option<BuildConfig> {
+    match value {
+        Value::Mapping(mapping) => Some(BuildConfig {
+            context: get(mapping, "context")
+                .and_then(scalar_string)
+                .unwrap_or_else(|| ".".to_string()),
+            dockerfile: get(mapping, "dockerfile").and_then(scalar_string),
+            target: get(mapping, "target").and_then(scalar_string),
+            args: get(mapping, "args").map(key_values).unwrap_or_default(),
+            cache_from: get(mapping, "cache_from")
+                .map(string_list)
+                .unwrap_or_default(),
+        }),
+        other => scalar_string(other).map(|context| BuildConfig {
+            context,
+            ..BuildConfig::default()
+        }),
+    }
+}
+
+fn parse_healthcheck(value: &Value) -> Option<Healthcheck> {
+    let mapping = value.as_mapping()?;
+    let disable = get(mapping, "disable")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let (form, test, none) = match get(mapping, "test") {
+        Some(sequence @ Value::Sequence(_)) => healthcheck::parse_test(&string_list(sequence))
+            .unwrap_or((HealthcheckForm::CmdShell, String::new(), false)),
+        Some(other) => (
+            HealthcheckForm::CmdShell,
+            scalar_string(other).unwrap_or_default(),
+            false,
+        ),
+        None => (HealthcheckForm::CmdShell, String::new(), false),
+    };
+
+    Some(Healthcheck {
+        form,
+        test,
+        interval: get(mapping, "interval").and_then(scalar_string),
+        timeout: get(mapping, "timeout").and_then(scalar_string),
+        retries: get(mapping, "retries")
+            .and_then(Value::as_u64)
+            .map(|retries| retries as u32),
+        start_period: get(mapping, "start_period").and_then(scalar_string),
+        disable: disable || none,
+    })
+}
+
+fn property_input(property: ServiceProperty, value: &Value) -> Option<String> {
+    match property {
+        ServiceProperty::Labels | ServiceProperty::Sysctls => {
+            Some(join_pairs(&key_values(value), '='))
+        }
+        ServiceProperty::ExtraHosts => Some(match value {
+            Value::Mapping(_) => join_pairs(&key_values(value), ':'),
+            other => string_list(other)
+                .iter()
+                .map(|host| host.replacen('=', ":", 1))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }),
+        ServiceProperty::Ulimits => {
+            let mapping = value.as_mapping()?;
+            let limits: Vec<String> = mapping
+                .iter()
+                .filter_map(|(name, limit)| {
+                    let name = scalar_string(name)?;
+                    match limit {
+                        Value::Mapping(range) => Some(format!(
+                            "{name}={}:{}",
+                            get(range, "soft").and_then(scalar_string)?,
+                            get(range, "hard").and_then(scalar_string)?
+                        )),
+                        other => Some(format!("{name}={}", scalar_string(other)?)),
+                    }
+                })
+                .collect();
+            Some(limits.join(", "))
+        }
+        ServiceProperty::Logging => {
+            let mapping = value.as_mapping()?;
+            let driver = get(mapping, "driver").and_then(scalar_string)?;
+            let options = get(mapping, "options").map(key_values).unwrap_or_default();
+            Some(format!("{driver} {}", join_pairs(&options, '=')))
+        }
+        ServiceProperty::Entrypoint => CommandSpec::from_value(value).map(|spec| spec.display()),
+        ServiceProperty::Restart
+        | ServiceProperty::User
+        | ServiceProperty::WorkingDir
+        | ServiceProperty::Hostname
+        | ServiceProperty::ContainerName
+        | ServiceProperty::NetworkMode
+        | ServiceProperty::StopGracePeriod => scalar_string(value),
+        ServiceProperty::Tty
+        | ServiceProperty::StdinOpen
+        | ServiceProperty::Init
+        | ServiceProperty::ReadOnly => None,
+        ServiceProperty::Profiles
+        | ServiceProperty::Dns
+        | ServiceProperty::CapAdd
+        | ServiceProperty::CapDrop
+        | ServiceProperty::SecurityOpt => Some(string_list(value).join(", ")),
+    }
+}
+
+fn parse_deploy(image: &mut ImageEntry, value: &Value, warnings: &mut Vec<String>) {
+    let Some(mapping) = value.as_mapping() else {
+        return;
+    };
+    let resources = get(mapping, "resources").and_then(Value::as_mapping);
+    let resource = |section: &str, key: &str| {
+        resources
+            .and_then(|resources| get(resources, section))
+            .and_then(Value::as_mapping)
+            .and_then(|section| get(section, key))
+            .and_then(scalar_string)
+    };
+
+    let inputs = [
+        (
+            DeployProperty::Replicas,
+            get(mapping, "replicas").and_then(scalar_string),
+        ),
+        (DeployProperty::LimitCpus, resource("limits", "cpus")),
+        (DeployProperty::LimitMemory, resource("limits", "memory")),
+        (DeployProperty::LimitPids, resource("limits", "pids")),
+        (
+            DeployProperty::ReserveCpus,
+            resource("reservations", "cpus"),
+        ),
+        (
+            DeployProperty::ReserveMemory,
+            resource("reservations", "memory"),
+        ),
+        (
+            DeployProperty::RestartPolicy,
+            get(mapping, "restart_policy").map(|value| join_pairs(&key_values(value), '=')),
+        ),
+        (
+            DeployProperty::UpdateConfig,
+            get(mapping, "update_config").map(|value| join_pairs(&key_values(value), '=')),
+        ),
+    ];
+    for (property, input) in inputs {
+        if let Some(input) = input
+            && let Err(error) = property.apply(&mut image.deploy, &input)
+        {
+            warnings.push(format!("{}: deploy {error}", image.service_name));
+        }
+    }
+}
+
+fn parse_legacy_resources(image: &mut ImageEntry, mapping: &Mapping, warnings: &mut Vec<String>) {
+    let inputs = [
+        (DeployProperty::LimitCpus, "cpus"),
+        (DeployProperty::LimitMemory, "mem_limit"),
+        (DeployProperty::LimitPids, "pids_limit"),
+        (DeployProperty::ReserveMemory, "mem_reservation"),
+    ];
+    for (property, key) in inputs {
+        if let Some(input) = get(mapping, key).and_then(scalar_string) {
+            image.deploy.legacy_limits = true;
+            if let Err(error) = property.apply(&mut image.deploy, &input) {
+                warnings.push(format!("{}: {key} {error}", image.service_name));
+            }
+        }
+    }
+}
+
+pub fn parse_service(
+    name: &str,
+    value: &Value,
+    source_file: Option<&str>,
+    warnings: &mut Vec<String>,
+    dropped: &mut Vec<String>,
+) -> ImageEntry {
+    let mut image = ImageEntry {
+        service_name: name.to_string(),
+        source_file: source_file.map(ToString::to_string),
+        ..ImageEntry::default()
+    };
+    let Some(mapping) = value.as_mapping() else {
+        return image;
+    };
+
+    if let Some((namespace, repo, tag)) = get(mapping, "image")
+        .and_then(scalar_string)
+        .and_then(|reference| build::split_image_ref(&reference))
+    {
+        image.namespace = namespace;
+        image.repo = repo;
+        image.tag = tag;
+    }
+    image.build = get(mapping, "build").and_then(parse_build);
+
+    if let Some(ports) = get(mapping, "ports") {
+        let ports: Vec<String> = match ports {
+            Value::Sequence(items) => items.iter().filter_map(parse_port).collect(),
+            other => parse_port(other).into_iter().collect(),
+        };
+        if ports.len() > 1 {
+            dropped.push(format!(
+                "{name}: drops extra ports {}",
+                ports[1..].join(", ")
+            ));
+        }
+        image.port_mapping = ports.into_iter().next().unwrap_or_default();
+    }
+    image.command = get(mapping, "command").and_then(CommandSpec::from_value);
+
+    for mount in get(mapping, "volumes")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+    {
+        match parse_mount(mount) {
+            Some(mount) => image.mounts.push(mount),
+            None => dropped.push(format!("{name}: drops anonymous or unsupported volume")),
+        }
+    }
+
+    image.env_vars = get(mapping, "environment")
+        .map(key_values)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pair| EnvVar {
+            key: pair.key,
+            value: pair.value,
+        })
+        .collect();
+    image.env_files = get(mapping, "env_file")
+        .map(parse_env_files)
+        .unwrap_or_default();
+    image.depends_on = match get(mapping, "depends_on") {
+        Some(Value::Mapping(dependencies)) => {
+            dependencies.keys().filter_map(scalar_string).collect()
+        }
+        Some(other) => string_list(other),
+        None => Vec::new(),
+    };
+    image.secrets = get(mapping, "secrets")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(|secret| match secret {
+            Value::Mapping(long) => get(long, "source").and_then(scalar_string),
+            other => scalar_string(other),
+        })
+        .collect();
+    image.healthcheck = get(mapping, "healthcheck").and_then(parse_healthcheck);
+    image.extends = match get(mapping, "extends") {
+        Some(Value::Mapping(extends)) => {
+            get(extends, "service")
+                .and_then(scalar_string)
+                .map(|service| Extends {
+                    service,
+                    file: get(extends, "file").and_then(scalar_string),
+                })
+        }
+        Some(other) => scalar_string(other).map(|service| Extends {
+            service,
+            file: None,
+        }),
+        None => None,
+    };
+
+    for property in ServiceProperty::all() {
+        let Some(value) = get(mapping, property.label()) else {
+            continue;
+        };
+        if property.is_toggle() {
+            if value.as_bool() == Some(true) {
+                property.toggle(&mut image.options);
+            }
+            continue;
+        }
+        if let Some(input) = property_input(property, value)
+            && let Err(error) = property.apply(&mut image.options, &input)
+        {
+            warnings.push(format!("{name}: {} {error}", property.label()));
+        }
+    }
+    if let Some(deploy) = get(mapping, "deploy") {
+        parse_deploy(&mut image, deploy, warnings);
+    }
+    parse_legacy_resources(&mut image, mapping, warnings);
+
+    for key in mapping.keys().filter_map(scalar_string) {
+        let known = SERVICE_KEYS.contains(&key.as_str())
+            || LEGACY_RESOURCE_KEYS.contains(&key.as_str())
+            || ServiceProperty::all()
+                .iter()
+                .any(|property| property.label() == key);
+        if !known {
+            dropped.push(format!("{name}: drops unsupported key {key}"));
+        }
+    }
+
+    image
+}
+
+fn include_paths(file: &str, value: &Value, dropped: &mut Vec<String>) -> Vec<String> {
+    let mut paths = Vec::new();
+    for entry in value.as_sequence().into_iter().flatten() {
+        match entry {
+            Value::Mapping(long) => {
+                let included = get(long, "path").map(string_list).unwrap_or_default();
+                for key in long
+                    .keys()
+                    .filter_map(scalar_string)
+                    .filter(|key| key != "path")
+                {
+                    dropped.push(format!(
+                        "{file}: drops {key} of include {}",
+                        included.join(", ")
+                    ));
+                }
+                paths.extend(included);
+            }
+            other => paths.extend(scalar_string(other)),
+        }
+    }
+    paths
+}
+
+fn read_document(path: &str) -> Result<Mapping, String> {
+    let raw = std::fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    match serde_yaml_ng::from_str::<Value>(&raw).map_err(|error| format!("{path}: {error}"))? {
+        Value::Mapping(mapping) => Ok(mapping),
+        Value::Null => Ok(Mapping::new()),
+        _ => Err(format!("{path}: expected a mapping at the top level")),
+    }
+}
+
+fn document_services(
+    document: &Mapping,
+    source_file: Option<&str>,
+    warnings: &mut Vec<String>,
+    dropped: &mut Vec<String>,
+) -> Vec<ImageEntry> {
+    get(document, "services")
+        .and_then(Value::as_mapping)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, service)| {
+            Some(parse_service(
+                &scalar_string(name)?,
+                service,
+                source_file,
+                warnings,
+                dropped,
+            ))
+        })
+        .collect()
+}
+
+fn parse_env_files(value: &Value) -> Vec<EnvFile> {
+    let items = match value {
+        Value::Sequence(items) => items.iter().collect(),
+        other => vec![other],
+    };
+    items
+        .into_iter()
+        .filter_map(|item| match item {
+            Value::Mapping(entry) => {
+                let mut file = EnvFile::new(get(entry, "path").and_then(scalar_string)?);
+                file.required = !matches!(get(entry, "required"), Some(Value::Bool(false)));
+                file.raw = get(entry, "format").and_then(scalar_string).as_deref() == Some("raw");
+                Some(file)
+            }
+            other => scalar_string(other).map(EnvFile::new),
+        })
+        .collect()
+}
+
+fn parse_volume(name: String, value: &Value) -> VolumeEntry {
+    let mut volume = VolumeEntry::new(name);
+    let Some(mapping) = value.as_mapping() else {
+        return volume;
+    };
+    volume.driver = get(mapping, "driver").and_then(scalar_string);
+    volume.driver_opts = get(mapping, "driver_opts")
+        .map(key_values)
+        .unwrap_or_default();
+    volume.labels = get(mapping, "labels").map(key_values).unwrap_or_default();
+    volume.volume_name = get(mapping, "name").and_then(scalar_string);
+    match get(mapping, "external") {
+        Some(Value::Bool(external)) => volume.external = *external,
+        Some(Value::Mapping(external)) => {
+            volume.external = true;
+            if volume.volume_name.is_none() {
+                volume.volume_name = get(external, "name").and_then(scalar_string);
+            }
+        }
+        _ => {}
+    }
+    volume
+}
+
+fn load_file(
+    project: &mut LoadedProject,
+    path: &str,
+    source_file: Option<&str>,
+) -> Result<(), String> {
+    let document = read_document(path)?;
+    let mut warnings = Vec::new();
+    let mut dropped = Vec::new();
+    for key in document
+        .keys()
+        .filter_map(scalar_string)
+        .filter(|key| !TOP_LEVEL_KEYS.contains(&key.as_str()))
+    {
+        dropped.push(format!("{path}: drops unsupported top-level key {key}"));
+    }
+    for image in document_services(&document, source_file, &mut warnings, &mut dropped) {
+        if let Some(existing) = project
+            .images
+            .iter()
+            .find(|existing| existing.service_name == image.service_name)
+        {
+            warnings.push(format!(
+                "service {} in {} is already defined in {}; keeping the first definition",
+                image.service_name,
+                image_file(&image, &project.root_file),
+                image_file(existing, &project.root_file)
+            ));
+            continue;
+        }
+        project.images.push(image);
+    }
+    project.warnings.extend(warnings);
+
+    for (name, volume) in get(&document, "volumes")
+        .and_then(Value::as_mapping)
+        .into_iter()
+        .flatten()
+    {
+        let Some(name) = scalar_string(name) else {
+            continue;
+        };
+        let volume = parse_volume(name, volume);
+        match project
+            .volumes
+            .iter_mut()
+            .find(|existing| existing.name == volume.name)
+        {
+            Some(existing) => *existing = volume,
+            None => project.volumes.push(volume),
+        }
+    }
+    for (name, secret) in get(&document, "secrets")
+        .and_then(Value::as_mapping)
+        .into_iter()
+        .flatten()
+    {
+        let Some(name) = scalar_string(name) else {
+            continue;
+        };
+        match secret
+            .as_mapping()
+            .and_then(|secret| get(secret, "file"))
+            .and_then(scalar_string)
+        {
+            Some(file) if !project.secrets.iter().any(|existing| existing.name == name) => {
+                project.secrets.push(SecretEntry {
+                    name,
+                    file,
+                    value: String::new(),
+                });
+            }
+            Some(_) => {}
+            None => project.warnings.push(format!(
+                "secret {name}: only file-backed secrets are supported"
+            )),
+        }
+    }
+
+    let includes = get(&document, "include")
+        .map(|include| include_paths(path, include, &mut dropped))
+        .unwrap_or_default();
+    project.dropped.extend(dropped);
+    match source_file {
+        None => {
+            project.name = get(&document, "name").and_then(scalar_string);
+            project.includes = includes.clone();
+        }
+        Some(file) => project.files.push(ComposeFile {
+            path: file.to_string(),
+            includes: includes.clone(),
+        }),
+    }
+
+    for include in includes {
+        let resolved = resolve_relative(path, &include);
+        if resolved == project.root_file || project.files.iter().any(|file| file.path == resolved) {
+            project
+                .warnings
+                .push(format!("{resolved} included more than once"));
+            continue;
+        }
+        load_file(project, &resolved, Some(&resolved))?;
+    }
+    Ok(())
+}
+
+fn image_file<'a>(image: &'a ImageEntry, root_file: &'a str) -> &'a str {
+    image.source_file.as_deref().unwrap_or(root_file)
+}
+
+fn load_extends_sources(project: &mut LoadedProject) {
+    let mut pending: Vec<String> = Vec::new();
+    let mut loaded: Vec<String> = vec![project.root_file.clone()];
+    loaded.extend(project.files.iter().map(|file| file.path.clone()));
+
+    loop {
+        for image in project.images.iter().chain(project.extends_sources.iter()) {
+            if let Some(file) = image
+                .extends
+                .as_ref()
+                .and_then(|extends| extends.file.as_deref())
+            {
+                let resolved = resolve_relative(image_file(image, &project.root_file), file);
+                if !loaded.contains(&resolved) && !pending.contains(&resolved) {
+                    pending.push(resolved);
+                }
+            }
+        }
+        let Some(path) = pending.pop() else {
+            break;
+        };
+        loaded.push(path.clone());
+        match read_document(&path) {
+            Ok(document) => {
+                let (mut warnings, mut dropped) = (Vec::new(), Vec::new());
+                let services =
+                    document_services(&document, Some(&path), &mut warnings, &mut dropped);
+                project.extends_sources.extend(services);
+            }
+            Err(error) => project
+                .warnings
+                .push(format!("extends source unavailable: {error}")),
+        }
+    }
+}
+
+fn parse_override(
+    file: &str,
+    name: &str,
+    value: &Value,
+    warnings: &mut Vec<String>,
+) -> ServiceOverride {
+    let mut entry = ServiceOverride {
+        service: name.to_string(),
+        ..ServiceOverride::default()
+    };
+    let Some(mapping) = value.as_mapping() else {
+        return entry;
+    };
+    let first_port = |ports: &Value| match ports {
+        Value::Sequence(items) => items.iter().find_map(parse_port).unwrap_or_default(),
+        other => parse_port(other).unwrap_or_default(),
+    };
+    entry.port_mapping = get(mapping, "ports").map(|ports| match ports {
+        Value::Tagged(tagged) if tagged.tag == "reset" => String::new(),
+        Value::Tagged(tagged) => first_port(&tagged.value),
+        other => first_port(other),
+    });
+    entry.env_vars = get(mapping, "environment")
+        .map(key_values)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pair| EnvVar {
+            key: pair.key,
+            value: pair.value,
+        })
+        .collect();
+    entry.mounts = get(mapping, "volumes")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(parse_mount)
+        .collect();
+    if let Some(deploy) = get(mapping, "deploy").and_then(Value::as_mapping) {
+        entry.replicas = get(deploy, "replicas")
+            .and_then(Value::as_u64)
+            .and_then(|replicas| u32::try_from(replicas).ok());
+        for key in deploy
+            .keys()
+            .filter_map(scalar_string)
+            .filter(|key| key != "replicas")
+        {
+            warnings.push(format!(
+                "{file}: {name} drops unsupported override deploy.{key}"
+            ));
+        }
+    }
+    for key in mapping
+        .keys()
+        .filter_map(scalar_string)
+        .filter(|key| !OVERRIDE_KEYS.contains(&key.as_str()))
+    {
+        warnings.push(format!(
+            "{file}: {name} drops unsupported override key {key}"
+        ));
+    }
+    entry
+}
+
+fn load_environments(project: &mut LoadedProject) -> Result<(), String> {
+    let dir = Path::new(&project.root_file)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map_err(|error| format!("{}: {error}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+
+    for file_name in names {
+        let Some(mut environment) = Environment::from_file_name(&file_name) else {
+            continue;
+        };
+        let path = resolve_relative(&project.root_file, &file_name);
+        if path == project.root_file || project.files.iter().any(|file| file.path == path) {
+            continue;
+        }
+        let document = read_document(&path)?;
+        let mut dropped = Vec::new();
+        for (name, service) in get(&document, "services")
+            .and_then(Value::as_mapping)
+            .into_iter()
+            .flatten()
+        {
+            let Some(name) = scalar_string(name) else {
+                continue;
+            };
+            if !project
+                .images
+                .iter()
+                .any(|image| image.service_name == name)
+            {
+                dropped.push(format!(
+                    "{path}: {name} is not a base service and will be dropped"
+                ));
+                continue;
+            }
+            environment.set_override(parse_override(&path, &name, service, &mut dropped));
+        }
+        for key in document
+            .keys()
+            .filter_map(scalar_string)
+            .filter(|key| key != "services")
+        {
+            dropped.push(format!("{path}: drops unsupported top-level key {key}"));
+        }
+        project.dropped.extend(dropped);
+        project.environments.push(environment);
+    }
+    Ok(())
+}
+
+pub fn load_project(root_file: &str) -> Result<LoadedProject, String> {
+    let mut project = LoadedProject {
+        root_file: root_file.to_string(),
+        ..LoadedProject::default()
+    };
+    load_file(&mut project, root_file, None)?;
+    load_extends_sources(&mut project);
+    load_environments(&mut project)?;
+    Ok(project)
+}
+
+fn merge_by<T: Clone>(base: &mut Vec<T>, child: &[T], same: impl Fn(&T, &T) -> bool) {
+    for item in child {
+        match base.iter_mut().find(|existing| same(existing, item)) {
+            Some(existing) => *existing = item.clone(),
+            None => base.push(item.clone()),
+        }
+    }
+}
+
+pub fn merge_service(base: &ImageEntry, child: &ImageEntry) -> ImageEntry {
+    let mut merged = base.clone();
+    merged.service_name = child.service_name.clone();
+    merged.source_file = child.source_file.clone();
+    merged.extends = None;
+
+    if child.image_ref().is_some() {
+        merged.namespace = child.namespace.clone();
+        merged.repo = child.repo.clone();
+        merged.tag = child.tag.clone();
+    }
+    if child.build.is_some() {
+        merged.build = child.build.clone();
+    }
+    if !child.port_mapping.is_empty() {
+        merged.port_mapping = child.port_mapping.clone();
+    }
+    if child.command.is_some() {
+        merged.command = child.command.clone();
+    }
+    if child.healthcheck.is_some() {
+        merged.healthcheck = child.healthcheck.clone();
+    }
+    merge_by(&mut merged.mounts, &child.mounts, |left, right| {
+        left.container_path() == right.container_path()
+    });
+    merge_by(&mut merged.env_vars, &child.env_vars, |left, right| {
+        left.key == right.key
+    });
+    merge_by(&mut merged.env_files, &child.env_files, |left, right| {
+        left.path == right.path
+    });
+    merged.depends_on = child.depends_on.clone();
+    merge_by(&mut merged.secrets, &child.secrets, |left, right| {
+        left == right
+    });
+    if merged
+        .options
+        .network_mode
+        .as_deref()
+        .is_some_and(|mode| mode.starts_with("service:"))
+    {
+        merged.options.network_mode = None;
+    }
+    merged.options.merge_from(&child.options);
+    merged.deploy.merge_from(&child.deploy);
+    merged
+}
+
+fn find_base<'a>(
+    image: &ImageEntry,
+    extends: &Extends,
+    root_file: &str,
+    images: &'a [ImageEntry],
+    sources: &'a [ImageEntry],
+) -> Option<&'a ImageEntry> {
+    let file = match extends.file.as_deref() {
+        Some(file) => resolve_relative(image_file(image, root_file), file),
+        None => image_file(image, root_file).to_string(),
+    };
+    images.iter().chain(sources.iter()).find(|candidate| {
+        candidate.service_name == extends.service && image_file(candidate, root_file) == file
+    })
+}
+
+fn resolve_service(
+    image: &ImageEntry,
+    root_file: &str,
+    images: &[ImageEntry],
+    sources: &[ImageEntry],
+    depth: usize,
+) -> ImageEntry {
+    let Some(extends) = image.extends.as_ref() else {
+        return image.clone();
+    };
+    match find_base(image, extends, root_file, images, sources) {
+        Some(base) if depth < MAX_EXTENDS_DEPTH => {
+            let base = resolve_service(base, root_file, images, sources, depth + 1);
+            merge_service(&base, image)
+        }
+        _ => image.clone(),
+    }
+}
+
+pub fn resolve_extends(
+    root_file: &str,
+    images: &[ImageEntry],
+    sources: &[ImageEntry],
+) -> Vec<ImageEntry> {
+    images
+        .iter()
+        .map(|image| resolve_service(image, root_file, images, sources, 0))
+        .collect()
+}
+
+pub fn unresolved_extends(
+    root_file: &str,
+    images: &[ImageEntry],
+    sources: &[ImageEntry],
+) -> Vec<String> {
+    images
+        .iter()
+        .filter_map(|image| {
+            let extends = image.extends.as_ref()?;
+            find_base(image, extends, root_file, images, sources)
+                .is_none()
+                .then(|| {
+                    format!(
+                        "{}: cannot resolve extends {}",
+                        image.service_name, extends.service
+                    )
+                })
+        })
+        .collect()
+}
+
+impl Extends {
+    pub fn render(&self, output: &mut String) {
+        match self.file.as_ref() {
+            Some(file) => output.push_str(&format!(
+                "    extends:\n      service: {}\n      file: {file}\n",
+                self.service
+            )),
+            None => output.push_str(&format!("    extends: {}\n", self.service)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("acdc-compose-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, contents) in files {
+            std::fs::write(dir.join(file), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn parse_mount_reads_read_only_and_selinux() {
+        let long: Value = serde_yaml_ng::from_str(
+            "type: bind\nsource: ./config\ntarget: /etc/app\nread_only: true\nbind:\n  selinux: Z\n",
+        )
+        .unwrap();
+        let mount = parse_mount(&long).unwrap();
+        assert_eq!(mount.target, "/etc/app");
+        assert!(mount.options.read_only);
+        assert_eq!(mount.options.selinux, Some(SelinuxLabel::Private));
+
+        let short = Value::String("data:/var/lib/data:ro".to_string());
+        let mount = parse_mount(&short).unwrap();
+        assert_eq!(mount.target, "/var/lib/data");
+        assert!(mount.options.read_only);
+    }
+
+    #[test]
+    fn merge_service_keeps_child_dependencies_only() {
+        let mut base = ImageEntry {
+            service_name: "base".to_string(),
+            port_mapping: "8080:80".to_string(),
+            depends_on: vec!["db".to_string()],
+            env_vars: vec![EnvVar {
+                key: "MODE".to_string(),
+                value: "base".to_string(),
+            }],
+            ..ImageEntry::default()
+        };
+        base.options.network_mode = Some("service:vpn".to_string());
+        base.options.user = Some("app".to_string());
+        let child = ImageEntry {
+            service_name: "web".to_string(),
+            depends_on: vec!["cache".to_string()],
+            env_vars: vec![EnvVar {
+                key: "MODE".to_string(),
+                value: "child".to_string(),
+            }],
+            ..ImageEntry::default()
+        };
+
+        let merged = merge_service(&base, &child);
+        assert_eq!(merged.service_name, "web");
+        assert_eq!(merged.port_mapping, "8080:80");
+        assert_eq!(merged.depends_on, ["cache"]);
+        assert_eq!(merged.env_vars[0].value, "child");
+        assert_eq!(merged.options.network_mode, None);
+        assert_eq!(merged.options.user.as_deref(), Some("app"));
+
+        base.options.network_mode = Some("host".to_string());
+        assert_eq!(
+            merge_service(&base, &child).options.network_mode.as_deref(),
+            Some("host")
+        );
+    }
+
+    #[test]
+    fn load_project_warns_about_services_redefined_in_includes() {
+        let dir = project_dir(
+            "duplicates",
+            &[
+                (
+                    "compose.yaml",
+                    "include:\n  - extra.yaml\nservices:\n  web:\n    image: nginx\n",
+                ),
+                (
+                    "extra.yaml",
+                    "services:\n  web:\n    image: httpd\n  db:\n    image: postgres\n",
+                ),
+            ],
+        );
+        let root = dir.join("compose.yaml").to_string_lossy().to_string();
+        let project = load_project(&root).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = project
+            .images
+            .iter()
+            .map(|image| image.service_name.as_str())
+            .collect();
+        assert_eq!(names, ["web", "db"]);
+        assert_eq!(project.images[0].repo, "nginx");
+        assert!(
+            project
+                .warnings
+                .iter()
+                .any(|warning| warning.starts_with("service web in ")
+                    && warning.ends_with("keeping the first definition")),
+            "{:?}",
+            project.warnings
+        );
+    }
+
+    #[test]
+    fn load_project_reports_content_it_cannot_keep() {
+        let dir = project_dir(
+            "dropped",
+            &[
+                (
+                    "compose.yaml",
+                    "include:\n  - path: extra.yaml\n    env_file: extra.env\nx-common: &common\n  restart: always\nnetworks:\n  front: {}\nservices:\n  web:\n    image: nginx\n    ports:\n      - \"80:80\"\n      - \"443:443\"\n    expose:\n      - \"9000\"\n    networks:\n      - front\n",
+                ),
+                ("extra.yaml", "configs:\n  app: {}\nservices: {}\n"),
+            ],
+        );
+        let root = dir.join("compose.yaml").to_string_lossy().to_string();
+        let extra = dir.join("extra.yaml").to_string_lossy().to_string();
+        let project = load_project(&root).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(project.images[0].port_mapping, "80:80");
+        let expected = [
+            format!("{root}: drops unsupported top-level key x-common"),
+            format!("{root}: drops unsupported top-level key networks"),
+            "web: drops extra ports 443:443".to_string(),
+            "web: drops unsupported key expose".to_string(),
+            "web: drops unsupported key networks".to_string(),
+            format!("{root}: drops env_file of include extra.yaml"),
+            format!("{extra}: drops unsupported top-level key configs"),
+        ];
+        for dropped in &expected {
+            assert!(
+                project.dropped.contains(dropped),
+                "{dropped} missing from {:?}",
+                project.dropped
+            );
+        }
+        assert_eq!(project.dropped.len(), expected.len());
+    }
+
+    #[test]
+    fn load_project_reads_environment_overrides() {
+        let dir = project_dir(
+            "environments",
+            &[
+                (
+                    "compose.yaml",
+                    "services:\n  web:\n    image: nginx\n    ports:\n      - \"80:80\"\n",
+                ),
+                (
+                    "compose.override.yaml",
+                    "services:\n  web:\n    ports: !override\n      - \"8080:80\"\n    environment:\n      GREETING: \"a: b\"\n",
+                ),
+                (
+                    "compose.prod.yaml",
+                    "services:\n  web:\n    ports: !reset []\n    deploy:\n      replicas: 3\n",
+                ),
+            ],
+        );
+        let root = dir.join("compose.yaml").to_string_lossy().to_string();
+        let project = load_project(&root).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = project
+            .environments
+            .iter()
+            .map(|environment| environment.name.as_str())
+            .collect();
+        assert_eq!(names, ["dev", "prod"]);
+        let dev = project.environments[0].override_for("web").unwrap();
+        assert_eq!(dev.port_mapping.as_deref(), Some("8080:80"));
+        assert_eq!(dev.env_vars[0].value, "a: b");
+        let prod = project.environments[1].override_for("web").unwrap();
+        assert_eq!(prod.port_mapping.as_deref(), Some(""));
+        assert_eq!(prod.replicas, Some(3));
+
+        let rendered = project.environments[0].render(&project.images);
+        assert!(rendered.contains("GREETING: \"a: b\""));
+        let reparsed: Value = serde_yaml_ng::from_str(&rendered).unwrap();
+        let mut warnings = Vec::new();
+        let service = &reparsed["services"]["web"];
+        let roundtrip = parse_override("dev", "web", service, &mut warnings);
+        assert_eq!(roundtrip.env_vars, dev.env_vars);
+        assert_eq!(roundtrip.port_mapping, dev.port_mapping);
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,397 @@
+use crate::tui::{
+    app::yaml_quote,
+    healthcheck,
+    properties::{KeyValue, merge_pairs, parse_key_values},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct ResourceSpec {
+    pub cpus: Option<String>,
+    pub memory: Option<String>,
+    pub pids: Option<u32>,
+}
+
+impl ResourceSpec {
+    fn is_empty(&self) -> bool {
+        self.cpus.is_none() && self.memory.is_none() && self.pids.is_none()
+    }
+
+    fn merge_from(&mut self, other: &ResourceSpec) {
+        if other.cpus.is_some() {
+            self.cpus = other.cpus.clone();
+        }
+        if other.memory.is_some() {
+            self.memory = other.memory.clone();
+        }
+        if other.pids.is_some() {
+            self.pids = other.pids;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeployConfig {
+    pub replicas: Option<u32>,
+    pub limits: ResourceSpec,
+    pub reservations: ResourceSpec,
+    pub restart_policy: Vec<KeyValue>,
+    pub update_config: Vec<KeyValue>,
+    pub legacy_limits: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployProperty {
+    Replicas,
+    LimitCpus,
+    LimitMemory,
+    LimitPids,
+    ReserveCpus,
+    ReserveMemory,
+    RestartPolicy,
+    UpdateConfig,
+    LegacyLimits,
+}
+
+type FieldRule = (&'static str, fn(&str) -> bool);
+
+pub fn is_valid_cpus(input: &str) -> bool {
+    input.parse::<f64>().is_ok_and(|value| value > 0.0)
+        && input.chars().all(|ch| ch.is_ascii_digit() || ch == '.')
+}
+
+pub fn parse_memory(input: &str) -> Option<(&str, Option<char>)> {
+    let split = input
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+        .unwrap_or(input.len());
+    let (amount, suffix) = input.split_at(split);
+    let mut parts = amount.split('.');
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next();
+    if whole.is_empty() || fraction.is_some_and(str::is_empty) || parts.next().is_some() {
+        return None;
+    }
+    let suffix = suffix.to_ascii_lowercase();
+    let unit = suffix
+        .chars()
+        .next()
+        .filter(|ch| matches!(ch, 'k' | 'm' | 'g' | 't'));
+    let rest = &suffix[unit.map_or(0, char::len_utf8)..];
+    let valid = match unit {
+        Some(_) => matches!(rest, "" | "b" | "i" | "ib"),
+        None => matches!(rest, "" | "b"),
+    };
+    valid.then_some((amount, unit))
+}
+
+pub fn is_valid_memory(input: &str) -> bool {
+    parse_memory(input).is_some()
+}
+
+fn validate_fields(pairs: &[KeyValue], allowed: &[FieldRule]) -> Result<(), String> {
+    for pair in pairs {
+        match allowed.iter().find(|(key, _)| *key == pair.key) {
+            Some((_, is_valid)) if is_valid(&pair.value) => {}
+            Some(_) => return Err(format!("invalid value '{}' for {}", pair.value, pair.key)),
+            None => {
+                let keys: Vec<&str> = allowed.iter().map(|(key, _)| *key).collect();
+                return Err(format!(
+                    "unknown key '{}' (use {})",
+                    pair.key,
+                    keys.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_count(input: &str) -> bool {
+    input.parse::<u32>().is_ok()
+}
+
+const RESTART_POLICY_FIELDS: [FieldRule; 4] = [
+    ("condition", |value| {
+        matches!(value, "none" | "on-failure" | "any")
+    }),
+    ("delay", healthcheck::is_valid_duration),
+    ("max_attempts", is_count),
+    ("window", healthcheck::is_valid_duration),
+];
+
+const UPDATE_CONFIG_FIELDS: [FieldRule; 5] = [
+    ("parallelism", is_count),
+    ("delay", healthcheck::is_valid_duration),
+    ("failure_action", |value| {
+        matches!(value, "continue" | "rollback" | "pause")
+    }),
+    ("monitor", healthcheck::is_valid_duration),
+    ("order", |value| {
+        matches!(value, "stop-first" | "start-first")
+    }),
+];
+
+fn optional_value(
+    input: &str,
+    is_valid: fn(&str) -> bool,
+    what: &str,
+) -> Result<Option<String>, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        Ok(None)
+    } else if is_valid(trimmed) {
+        Ok(Some(trimmed.to_string()))
+    } else {
+        Err(format!("invalid {what} '{trimmed}'"))
+    }
+}
+
+fn optional_count(input: &str, what: &str) -> Result<Option<u32>, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        Ok(None)
+    } else {
+        trimmed
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("invalid {what} '{trimmed}'"))
+    }
+}
+
+fn join_pairs(pairs: &[KeyValue]) -> String {
+    pairs
+        .iter()
+        .map(|pair| format!("{}={}", pair.key, pair.value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl DeployProperty {
+    pub fn all() -> [Self; 9] {
+        [
+            Self::Replicas,
+            Self::LimitCpus,
+            Self::LimitMemory,
+            Self::LimitPids,
+            Self::ReserveCpus,
+            Self::ReserveMemory,
+            Self::RestartPolicy,
+            Self::UpdateConfig,
+            Self::LegacyLimits,
+        ]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Replicas => "replicas",
+            Self::LimitCpus => "limits.cpus",
+            Self::LimitMemory => "limits.memory",
+            Self::LimitPids => "limits.pids",
+            Self::ReserveCpus => "reservations.cpus",
+            Self::ReserveMemory => "reservations.memory",
+            Self::RestartPolicy => "restart_policy",
+            Self::UpdateConfig => "update_config",
+            Self::LegacyLimits => "legacy mem_limit/cpus",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Replicas | Self::LimitPids => "whole number, empty clears",
+            Self::LimitCpus | Self::ReserveCpus => "decimal cpus like 0.5 or 1.5",
+            Self::LimitMemory | Self::ReserveMemory => "bytes with unit like 512m or 2g",
+            Self::RestartPolicy => {
+                "condition=none|on-failure|any, delay=5s, max_attempts=3, window=2m"
+            }
+            Self::UpdateConfig => {
+                "parallelism=1, delay=10s, failure_action=rollback, monitor=30s, order=start-first"
+            }
+            Self::LegacyLimits => "Enter toggles top-level mem_limit/cpus/pids_limit output",
+        }
+    }
+
+    pub fn is_toggle(self) -> bool {
+        matches!(self, Self::LegacyLimits)
+    }
+
+    pub fn toggle(self, deploy: &mut DeployConfig) {
+        if self.is_toggle() {
+            deploy.legacy_limits = !deploy.legacy_limits;
+        }
+    }
+
+    pub fn display(self, deploy: &DeployConfig) -> String {
+        match self {
+            Self::Replicas => deploy
+                .replicas
+                .map(|count| count.to_string())
+                .unwrap_or_default(),
+            Self::LimitCpus => deploy.limits.cpus.clone().unwrap_or_default(),
+            Self::LimitMemory => deploy.limits.memory.clone().unwrap_or_default(),
+            Self::LimitPids => deploy
+                .limits
+                .pids
+                .map(|pids| pids.to_string())
+                .unwrap_or_default(),
+            Self::ReserveCpus => deploy.reservations.cpus.clone().unwrap_or_default(),
+            Self::ReserveMemory => deploy.reservations.memory.clone().unwrap_or_default(),
+            Self::RestartPolicy => join_pairs(&deploy.restart_policy),
+            Self::UpdateConfig => join_pairs(&deploy.update_config),
+            Self::LegacyLimits => if deploy.legacy_limits { "yes" } else { "no" }.to_string(),
+        }
+    }
+
+    pub fn apply(self, deploy: &mut DeployConfig, input: &str) -> Result<(), String> {
+        match self {
+            Self::Replicas => deploy.replicas = optional_count(input, "replicas")?,
+            Self::LimitCpus => deploy.limits.cpus = optional_value(input, is_valid_cpus, "cpus")?,
+            Self::LimitMemory => {
+                deploy.limits.memory = optional_value(input, is_valid_memory, "memory")?
+            }
+            Self::LimitPids => deploy.limits.pids = optional_count(input, "pids")?,
+            Self::ReserveCpus => {
+                deploy.reservations.cpus = optional_value(input, is_valid_cpus, "cpus")?
+            }
+            Self::ReserveMemory => {
+                deploy.reservations.memory = optional_value(input, is_valid_memory, "memory")?
+            }
+            Self::RestartPolicy => {
+                let pairs = parse_key_values(input)?;
+                validate_fields(&pairs, &RESTART_POLICY_FIELDS)?;
+                deploy.restart_policy = pairs;
+            }
+            Self::UpdateConfig => {
+                let pairs = parse_key_values(input)?;
+                validate_fields(&pairs, &UPDATE_CONFIG_FIELDS)?;
+                deploy.update_config = pairs;
+            }
+            Self::LegacyLimits => self.toggle(deploy),
+        }
+        Ok(())
+    }
+}
+
+impl DeployConfig {
+    pub fn merge_from(&mut self, other: &DeployConfig) {
+        if other.replicas.is_some() {
+            self.replicas = other.replicas;
+        }
+        self.limits.merge_from(&other.limits);
+        self.reservations.merge_from(&other.reservations);
+        merge_pairs(&mut self.restart_policy, &other.restart_policy);
+        merge_pairs(&mut self.update_config, &other.update_config);
+        self.legacy_limits |= other.legacy_limits;
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(replicas) = self.replicas {
+            parts.push(format!("x{replicas}"));
+        }
+        if let Some(cpus) = self.limits.cpus.as_ref() {
+            parts.push(format!("{cpus}cpu"));
+        }
+        if let Some(memory) = self.limits.memory.as_ref() {
+            parts.push(memory.clone());
+        }
+        if parts.is_empty() {
+            "-".to_string()
+        } else {
+            parts.join("/")
+        }
+    }
+
+    pub fn render(&self, output: &mut String) {
+        let mut limits = self.limits.clone();
+        let mut reservations = self.reservations.clone();
+
+        if self.legacy_limits {
+            if let Some(cpus) = limits.cpus.take() {
+                output.push_str(&format!("    cpus: {}\n", yaml_quote(&cpus)));
+            }
+            if let Some(memory) = limits.memory.take() {
+                output.push_str(&format!("    mem_limit: {memory}\n"));
+            }
+            if let Some(pids) = limits.pids.take() {
+                output.push_str(&format!("    pids_limit: {pids}\n"));
+            }
+            if let Some(memory) = reservations.memory.take() {
+                output.push_str(&format!("    mem_reservation: {memory}\n"));
+            }
+        }
+
+        let has_resources = !limits.is_empty() || !reservations.is_empty();
+        if self.replicas.is_none()
+            && !has_resources
+            && self.restart_policy.is_empty()
+            && self.update_config.is_empty()
+        {
+            return;
+        }
+
+        output.push_str("    deploy:\n");
+        if let Some(replicas) = self.replicas {
+            output.push_str(&format!("      replicas: {replicas}\n"));
+        }
+        if has_resources {
+            output.push_str("      resources:\n");
+            for (key, spec) in [("limits", &limits), ("reservations", &reservations)] {
+                if spec.is_empty() {
+                    continue;
+                }
+                output.push_str(&format!("        {key}:\n"));
+                if let Some(cpus) = spec.cpus.as_ref() {
+                    output.push_str(&format!("          cpus: {}\n", yaml_quote(cpus)));
+                }
+                if let Some(memory) = spec.memory.as_ref() {
+                    output.push_str(&format!("          memory: {memory}\n"));
+                }
+                if let Some(pids) = spec.pids {
+                    output.push_str(&format!("          pids: {pids}\n"));
+                }
+            }
+        }
+        for (key, pairs) in [
+            ("restart_policy", &self.restart_policy),
+            ("update_config", &self.update_config),
+        ] {
+            if !pairs.is_empty() {
+                output.push_str(&format!("      {key}:\n"));
+                for pair in pairs {
+                    output.push_str(&format!("        {}: {}\n", pair.key, pair.value));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_accepts_decimals_and_binary_suffixes() {
+        for input in [
+            "512", "512m", "1.5g", "2GB", "1t", "256Ki", "512mi", "1GiB", "100b",
+        ] {
+            assert!(is_valid_memory(input), "{input}");
+        }
+        for input in ["", "m", ".5g", "1.g", "1.2.3g", "5i", "10x", "1 g", "-1m"] {
+            assert!(!is_valid_memory(input), "{input}");
+        }
+        assert_eq!(parse_memory("1.5Gi"), Some(("1.5", Some('g'))));
+    }
+
+    #[test]
+    fn deploy_memory_limit_round_trips() {
+        let mut deploy = DeployConfig::default();
+        DeployProperty::LimitMemory
+            .apply(&mut deploy, "1.5g")
+            .unwrap();
+        assert_eq!(deploy.limits.memory.as_deref(), Some("1.5g"));
+        assert!(
+            DeployProperty::LimitMemory
+                .apply(&mut deploy, "lots")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use crate::tui::{
+    app::{EnvVar, ImageEntry, VolumeMount},
+    command::CommandSpec,
+    env_files::EnvFile,
+    ports,
+    properties::{PropertySheet, ServiceProperty, SheetProperty},
+    volumes::MountType,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailSection {
+    Ports,
+    Environment,
+    EnvFiles,
+    Mounts,
+    Command,
+    DependsOn,
+    Secrets,
+    Settings,
+}
+
+fn settings(image: &ImageEntry) -> Vec<SheetProperty> {
+    [PropertySheet::Service, PropertySheet::Deploy]
+        .into_iter()
+        .flat_map(PropertySheet::properties)
+        .filter(|property| *property != SheetProperty::Service(ServiceProperty::Entrypoint))
+        .filter(|property| {
+            let value = property.display(image);
+            !value.is_empty() && value != "no"
+        })
+        .collect()
+}
+
+fn parse_env(input: &str) -> Result<EnvVar, String> {
+    let (key, value) = input.split_once('=').unwrap_or((input, ""));
+    let key = key.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err(format!("expected KEY=value, got '{input}'"));
+    }
+    Ok(EnvVar {
+        key: key.to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+fn parse_mount(input: &str, existing: Option<&VolumeMount>) -> Result<VolumeMount, String> {
+    let (source, target, kind) = match input.split_once(':') {
+        Some((source, target)) if !source.is_empty() && target.starts_with('/') => {
+            (source, target, None)
+        }
+        _ if input.starts_with('/') => ("", input, Some(MountType::Tmpfs)),
+        _ => return Err("expected source:/container/path, or /path for tmpfs".to_string()),
+    };
+    let mut mount = existing
+        .cloned()
+        .unwrap_or_else(|| VolumeMount::new(source, target));
+    mount.options.kind = match kind {
+        Some(kind) => Some(kind),
+        None if mount.source != source => mount.options.kind.map(|_| MountType::infer(source)),
+        None => mount.options.kind,
+    };
+    mount.source = source.to_string();
+    mount.set_target(target);
+    Ok(mount)
+}
+
+fn service_name(input: &str) -> Result<String, String> {
+    let name = input.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        Err(format!("invalid name '{input}'"))
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+fn upsert<T: Clone>(items: &mut Vec<T>, index: Option<usize>, item: T) {
+    match index.and_then(|index| items.get_mut(index)) {
+        Some(existing) => *existing = item,
+        None => items.push(item),
+    }
+}
+
+impl DetailSection {
+    pub fn all() -> [Self; 8] {
+        [
+            Self::Ports,
+            Self::Environment,
+            Self::EnvFiles,
+            Self::Mounts,
+            Self::Command,
+            Self::DependsOn,
+            Self::Secrets,
+            Self::Settings,
+        ]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ports => "Ports",
+            Self::Environment => "Environment",
+            Self::EnvFiles => "Env files",
+            Self::Mounts => "Mounts",
+            Self::Command => "Command",
+            Self::DependsOn => "Depends on",
+            Self::Secrets => "Secrets",
+            Self::Settings => "Settings",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Ports => "host:container, e.g. 8080:80",
+            Self::Environment => "KEY=value",
+            Self::EnvFiles => "path to a .env file",
+            Self::Mounts => "source:/container/path[:ro,z], or /path for tmpfs",
+            Self::Command => "shell string, or [\"exec\", \"form\"]",
+            Self::DependsOn => "service name",
+            Self::Secrets => "secret name",
+            Self::Settings => "value for the property (o edits all properties)",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let all = Self::all();
+        let index = all.iter().position(|section| *section == self).unwrap_or(0);
+        all[(index + 1) % all.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        let all = Self::all();
+        let index = all.iter().position(|section| *section == self).unwrap_or(0);
+        all[(index + all.len() - 1) % all.len()]
+    }
+
+    pub fn can_add(self) -> bool {
+        !matches!(self, Self::Command | Self::Settings)
+    }
+
+    pub fn can_reorder(self) -> bool {
+        matches!(
+            self,
+            Self::Environment | Self::EnvFiles | Self::Mounts | Self::DependsOn | Self::Secrets
+        )
+    }
+
+    pub fn items(self, image: &ImageEntry) -> Vec<String> {
+        match self {
+            Self::Ports if image.port_mapping.is_empty() => Vec::new(),
+            Self::Ports => vec![image.port_mapping.clone()],
+            Self::Environment => image
+                .env_vars
+                .iter()
+                .map(|env| format!("{}={}", env.key, env.value))
+                .collect(),
+            Self::EnvFiles => image.env_files.iter().map(EnvFile::describe).collect(),
+            Self::Mounts => image.mounts.iter().map(VolumeMount::describe).collect(),
+            Self::Command => vec![
+                format!(
+                    "command: {}",
+                    image
+                        .command
+                        .as_ref()
+                        .map(CommandSpec::display)
+                        .unwrap_or_default()
+                ),
+                format!(
+                    "entrypoint: {}",
+                    image
+                        .options
+                        .entrypoint
+                        .as_ref()
+                        .map(CommandSpec::display)
+                        .unwrap_or_default()
+                ),
+            ],
+            Self::DependsOn => image.depends_on.clone(),
+            Self::Secrets => image.secrets.clone(),
+            Self::Settings => settings(image)
+                .into_iter()
+                .map(|property| format!("{}: {}", property.label(), property.display(image)))
+                .collect(),
+        }
+    }
+
+    pub fn edit_text(self, image: &ImageEntry, index: usize) -> Option<String> {
+        match self {
+            Self::Mounts => image.mounts.get(index).map(|mount| {
+                if mount.source.is_empty() {
+                    mount.short_target()
+                } else {
+                    format!("{}:{}", mount.source, mount.short_target())
+                }
+            }),
+            Self::EnvFiles => image.env_files.get(index).map(|file| file.path.clone()),
+            Self::Command => match index {
+                0 => Some(
+                    image
+                        .command
+                        .as_ref()
+                        .map(CommandSpec::display)
+                        .unwrap_or_default(),
+                ),
+                _ => Some(
+                    image
+                        .options
+                        .entrypoint
+                        .as_ref()
+                        .map(CommandSpec::display)
+                        .unwrap_or_default(),
+                ),
+            },
+            Self::Settings => settings(image)
+                .get(index)
+                .map(|property| property.display(image)),
+            _ => self.items(image).get(index).cloned(),
+        }
+    }
+
+    pub fn toggles(self, image: &mut ImageEntry, index: usize) -> Option<String> {
+        if self != Self::Settings {
+            return None;
+        }
+        let property = settings(image)
+            .get(index)
+            .copied()
+            .filter(|property| property.is_toggle())?;
+        property.toggle(image);
+        Some(format!(
+            "{} = {}",
+            property.label(),
+            property.display(image)
+        ))
+    }
+
+    pub fn apply(
+        self,
+        image: &mut ImageEntry,
+        index: Option<usize>,
+        input: &str,
+        services: &[String],
+        secrets: &[String],
+    ) -> Result<String, String> {
+        let input = input.trim();
+        match self {
+            Self::Ports => {
+                ports::validate_mapping(input)?;
+                image.port_mapping = input.to_string();
+                Ok(format!("ports = {input}"))
+            }
+            Self::Environment => {
+                let env = parse_env(input)?;
+                let duplicate =
+                    image
+                        .env_vars
+                        .iter()
+                        .enumerate()
+                        .position(|(position, existing)| {
+                            existing.key == env.key && Some(position) != index
+                        });
+                let message = format!("env {}={}", env.key, env.value);
+                match duplicate {
+                    Some(position) => {
+                        image.env_vars[position] = env;
+                        if let Some(index) = index.filter(|index| *index < image.env_vars.len()) {
+                            image.env_vars.remove(index);
+                        }
+                    }
+                    None => upsert(&mut image.env_vars, index, env),
+                }
+                Ok(message)
+            }
+            Self::EnvFiles => {
+                if input.is_empty() {
+                    return Err("env file path is required".to_string());
+                }
+                let file = match index.and_then(|index| image.env_files.get(index)) {
+                    Some(existing) => EnvFile {
+                        path: input.to_string(),
+                        ..existing.clone()
+                    },
+                    None => EnvFile::new(input),
+                };
+                upsert(&mut image.env_files, index, file);
+                Ok(format!("env_file {input}"))
+            }
+            Self::Mounts => {
+                let existing = index.and_then(|index| image.mounts.get(index));
+                let mount = parse_mount(input, existing)?;
+                let message = format!("mount {}", mount.describe());
+                upsert(&mut image.mounts, index, mount);
+                Ok(message)
+            }
+            Self::Command => {
+                let spec = CommandSpec::parse(input);
+                match index {
+                    Some(1) => {
+                        image.options.entrypoint = spec;
+                        Ok(format!("entrypoint = {input}"))
+                    }
+                    _ => {
+                        image.command = spec;
+                        Ok(format!("command = {input}"))
+                    }
+                }
+            }
+            Self::DependsOn | Self::Secrets => {
+                let name = service_name(input)?;
+                if self == Self::DependsOn && name == image.service_name {
+                    return Err("a service cannot depend on itself".to_string());
+                }
+                let known = if self == Self::DependsOn {
+                    services
+                } else {
+                    secrets
+                };
+                if !known.contains(&name) {
+                    let kind = if self == Self::DependsOn {
+                        "service"
+                    } else {
+                        "secret"
+                    };
+                    return Err(format!("no {kind} named {name}"));
+                }
+                let items = if self == Self::DependsOn {
+                    &mut image.depends_on
+                } else {
+                    &mut image.secrets
+                };
+                if items
+                    .iter()
+                    .enumerate()
+                    .any(|(position, item)| *item == name && Some(position) != index)
+                {
+                    return Err(format!("{name} is already listed"));
+                }
+                upsert(items, index, name.clone());
+                Ok(format!("{} {name}", self.label().to_lowercase()))
+            }
+            Self::Settings => {
+                let property = index
+                    .and_then(|index| settings(image).get(index).copied())
+                    .ok_or_else(|| "no property selected".to_string())?;
+                property.apply(image, input)?;
+                Ok(format!("{} = {input}", property.label()))
+            }
+        }
+    }
+
+    pub fn remove(self, image: &mut ImageEntry, indices: &[usize]) -> usize {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        let properties = settings(image);
+        let mut removed = 0;
+        for index in indices.into_iter().rev() {
+            let done = match self {
+                Self::Ports => !std::mem::take(&mut image.port_mapping).is_empty(),
+                Self::Environment => remove_at(&mut image.env_vars, index),
+                Self::EnvFiles => remove_at(&mut image.env_files, index),
+                Self::Mounts => remove_at(&mut image.mounts, index),
+                Self::Command if index == 0 => image.command.take().is_some(),
+                Self::Command => image.options.entrypoint.take().is_some(),
+                Self::DependsOn => remove_at(&mut image.depends_on, index),
+                Self::Secrets => remove_at(&mut image.secrets, index),
+                Self::Settings => match properties.get(index) {
+                    Some(property) if property.is_toggle() => {
+                        property.toggle(image);
+                        true
+                    }
+                    Some(property) => property.apply(image, "").is_ok(),
+                    None => false,
+                },
+            };
+            if done {
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    pub fn move_item(self, image: &mut ImageEntry, index: usize, up: bool) -> Option<usize> {
+        let target = if up { index.checked_sub(1)? } else { index + 1 };
+        let swapped = match self {
+            Self::Environment => swap(&mut image.env_vars, index, target),
+            Self::EnvFiles => swap(&mut image.env_files, index, target),
+            Self::Mounts => swap(&mut image.mounts, index, target),
+            Self::DependsOn => swap(&mut image.depends_on, index, target),
+            Self::Secrets => swap(&mut image.secrets, index, target),
+            _ => false,
+        };
+        swapped.then_some(target)
+    }
+}
+
+fn remove_at<T>(items: &mut Vec<T>, index: usize) -> bool {
+    if index < items.len() {
+        items.remove(index);
+        true
+    } else {
+        false
+    }
+}
+
+fn swap<T>(items: &mut [T], left: usize, right: usize) -> bool {
+    if left < items.len() && right < items.len() {
+        items.swap(left, right);
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn ports_reject_invalid_mappings() {
+        let mut image = ImageEntry::default();
+        for input in ["8080", "0:80", "70000:80", "web:80", "8080:80/icmp"] {
+            assert!(
+                DetailSection::Ports
+                    .apply(&mut image, None, input, &[], &[])
+                    .is_err(),
+                "{input}"
+            );
+        }
+        assert!(image.port_mapping.is_empty());
+        for input in ["8080:80", "127.0.0.1:5432:5432", "53:53/udp"] {
+            DetailSection::Ports
+                .apply(&mut image, None, input, &[], &[])
+                .unwrap();
+            assert_eq!(image.port_mapping, input);
+        }
+    }
+
+    #[test]
+    fn depends_on_and_secrets_must_exist() {
+        let mut image = ImageEntry {
+            service_name: "web".to_string(),
+            ..ImageEntry::default()
+        };
+        let services = names(&["web", "db"]);
+        let secrets = names(&["db_password"]);
+        let depends = DetailSection::DependsOn;
+        assert!(
+            depends
+                .apply(&mut image, None, "cache", &services, &secrets)
+                .is_err()
+        );
+        assert!(
+            depends
+                .apply(&mut image, None, "web", &services, &secrets)
+                .is_err()
+        );
+        depends
+            .apply(&mut image, None, "db", &services, &secrets)
+            .unwrap();
+        assert_eq!(image.depends_on, names(&["db"]));
+
+        let section = DetailSection::Secrets;
+        assert!(
+            section
+                .apply(&mut image, None, "api_key", &services, &secrets)
+                .is_err()
+        );
+        section
+            .apply(&mut image, None, "db_password", &services, &secrets)
+            .unwrap();
+        assert_eq!(image.secrets, names(&["db_password"]));
+    }
+
+    #[test]
+    fn editing_long_syntax_mount_keeps_its_type() {
+        let mut mount = VolumeMount::new("./data", "/data");
+        mount.options.long_syntax = true;
+        mount.options.kind = Some(MountType::Bind);
+        mount.options.create_host_path = true;
+        let mut image = ImageEntry {
+            mounts: vec![mount],
+            ..ImageEntry::default()
+        };
+
+        DetailSection::Mounts
+            .apply(&mut image, Some(0), "./data:/srv/data", &[], &[])
+            .unwrap();
+        let edited = &image.mounts[0];
+        assert_eq!(edited.target, "/srv/data");
+        assert_eq!(edited.options.kind, Some(MountType::Bind));
+        assert!(edited.options.long_syntax && edited.options.create_host_path);
+
+        DetailSection::Mounts
+            .apply(&mut image, Some(0), "cache:/srv/data", &[], &[])
+            .unwrap();
+        assert_eq!(image.mounts[0].options.kind, Some(MountType::Volume));
+
+        DetailSection::Mounts
+            .apply(&mut image, Some(0), "/scratch", &[], &[])
+            .unwrap();
+        assert_eq!(image.mounts[0].options.kind, Some(MountType::Tmpfs));
+    }
+}
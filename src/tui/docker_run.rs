@@ -0,0 +1,468 @@
+use std::path::Path;
+
+use crate::tui::{
+    app::{
+        App, EnvVar, Healthcheck, HealthcheckForm, ImageEntry, VolumeEntry, VolumeMount,
+        default_service_name,
+    },
+    build,
+    command::CommandSpec,
+    deploy::DeployProperty,
+    dockerfile, env_files, healthcheck,
+    properties::ServiceProperty,
+    volumes::{self, MountOptions, MountType},
+};
+
+const BOOLEAN_FLAGS: [&str; 18] = [
+    "-d",
+    "--detach",
+    "--rm",
+    "-i",
+    "--interactive",
+    "-t",
+    "--tty",
+    "--init",
+    "--read-only",
+    "--privileged",
+    "-P",
+    "--publish-all",
+    "--no-healthcheck",
+    "--oom-kill-disable",
+    "-q",
+    "--quiet",
+    "--sig-proxy",
+    "--disable-content-trust",
+];
+const SHORT_SWITCHES: [char; 5] = ['d', 'i', 't', 'P', 'q'];
+const SHORT_VALUE_FLAGS: [char; 8] = ['p', 'e', 'v', 'w', 'u', 'h', 'l', 'm'];
+
+#[derive(Debug, Clone, Default)]
+pub struct RunImport {
+    pub entry: ImageEntry,
+    pub volumes: Vec<VolumeEntry>,
+    pub unmapped: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+fn strip_prefix_words(words: &mut Vec<String>) {
+    let skip = match words
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["docker" | "podman", "container", "run", ..] => 3,
+        ["docker" | "podman", "run", ..] => 2,
+        ["run", ..] => 1,
+        _ => 0,
+    };
+    words.drain(..skip);
+}
+
+fn split_flag(word: &str) -> (String, Option<String>) {
+    match word.split_once('=') {
+        Some((name, value)) => (name.to_string(), Some(value.to_string())),
+        None => (word.to_string(), None),
+    }
+}
+
+fn expand_short_flags(word: &str) -> Vec<(String, Option<String>)> {
+    if word.starts_with("--") {
+        return vec![split_flag(word)];
+    }
+    let mut flags = Vec::new();
+    for (index, ch) in word.char_indices().skip(1) {
+        if SHORT_VALUE_FLAGS.contains(&ch) {
+            let rest = &word[index + ch.len_utf8()..];
+            let value = rest.strip_prefix('=').unwrap_or(rest);
+            flags.push((
+                format!("-{ch}"),
+                (!rest.is_empty()).then(|| value.to_string()),
+            ));
+            return flags;
+        }
+        if !SHORT_SWITCHES.contains(&ch) {
+            return vec![split_flag(word)];
+        }
+        flags.push((format!("-{ch}"), None));
+    }
+    flags
+}
+
+fn push_volume(import: &mut RunImport, source: &str) {
+    if !volumes::is_path_source(source)
+        && !import.volumes.iter().any(|volume| volume.name == source)
+    {
+        import.volumes.push(VolumeEntry::new(source));
+    }
+}
+
+fn parse_mount_flag(import: &mut RunImport, value: &str) -> Result<(), String> {
+    let mut source = String::new();
+    let mut target = None;
+    let mut read_only = false;
+    let mut kind = MountType::Volume;
+    let mut options = MountOptions::default();
+    for field in value.split(',') {
+        let (key, field_value) = field.split_once('=').unwrap_or((field, ""));
+        match key {
+            "type" => {
+                kind = MountType::parse(field_value).ok_or_else(|| format!("--mount {value}"))?
+            }
+            "source" | "src" => source = field_value.to_string(),
+            "target" | "destination" | "dst" => target = Some(field_value.to_string()),
+            "readonly" | "ro" => read_only = field_value != "false",
+            "bind-propagation" => options.propagation = Some(field_value.to_string()),
+            "volume-nocopy" => options.nocopy = field_value != "false",
+            "volume-subpath" => options.subpath = Some(field_value.to_string()),
+            "tmpfs-size" => options.tmpfs_size = Some(field_value.to_string()),
+            _ => import.unmapped.push(format!("--mount option {field}")),
+        }
+    }
+    let Some(target) = target else {
+        return Err(format!("--mount {value}"));
+    };
+    if source.is_empty() && kind != MountType::Tmpfs {
+        return Err(format!("--mount {value}"));
+    }
+    if kind == MountType::Volume {
+        push_volume(import, &source);
+    }
+
+    options.read_only = read_only;
+    options.kind = (kind != MountType::infer(&source)).then_some(kind);
+    options.long_syntax = true;
+    import.entry.mounts.push(VolumeMount {
+        source,
+        target,
+        options,
+    });
+    Ok(())
+}
+
+fn parse_tmpfs_flag(import: &mut RunImport, value: &str) {
+    let (target, flags) = value.split_once(':').unwrap_or((value, ""));
+    let mut mount = VolumeMount::tmpfs(target);
+    for flag in flags.split(',').filter(|flag| !flag.is_empty()) {
+        match flag.split_once('=') {
+            Some(("size", size)) => mount.options.tmpfs_size = Some(size.to_string()),
+            _ => import.unmapped.push(format!("--tmpfs option {flag}")),
+        }
+    }
+    import.entry.mounts.push(mount);
+}
+
+fn apply_property(import: &mut RunImport, property: ServiceProperty, flag: &str, value: &str) {
+    let mut input = value.to_string();
+    let existing = property.display(&import.entry.options);
+    if matches!(
+        property,
+        ServiceProperty::Labels
+            | ServiceProperty::ExtraHosts
+            | ServiceProperty::Dns
+            | ServiceProperty::CapAdd
+            | ServiceProperty::CapDrop
+            | ServiceProperty::SecurityOpt
+            | ServiceProperty::Ulimits
+            | ServiceProperty::Sysctls
+    ) && !existing.is_empty()
+    {
+        input = format!("{existing}, {value}");
+    }
+    if let Err(error) = property.apply(&mut import.entry.options, &input) {
+        import.unmapped.push(format!("{flag} {value} ({error})"));
+    }
+}
+
+fn apply_deploy(import: &mut RunImport, property: DeployProperty, flag: &str, value: &str) {
+    if let Err(error) = property.apply(&mut import.entry.deploy, value) {
+        import.unmapped.push(format!("{flag} {value} ({error})"));
+    }
+}
+
+fn healthcheck_mut(import: &mut RunImport) -> &mut Healthcheck {
+    import.entry.healthcheck.get_or_insert_with(|| Healthcheck {
+        form: HealthcheckForm::CmdShell,
+        test: String::new(),
+        interval: None,
+        timeout: None,
+        retries: None,
+        start_period: None,
+        disable: false,
+    })
+}
+
+fn apply_flag(import: &mut RunImport, flag: &str, value: &str) {
+    match flag {
+        "-p" | "--publish" => {
+            if import.entry.port_mapping.is_empty() {
+                import.entry.port_mapping = value.to_string();
+            } else {
+                import.unmapped.push(format!(
+                    "{flag} {value} (only one port mapping per service)"
+                ));
+            }
+        }
+        "-v" | "--volume" => match value.split_once(':') {
+            Some((source, target)) => {
+                push_volume(import, source);
+                import
+                    .entry
+                    .mounts
+                    .push(VolumeMount::new(source.to_string(), target.to_string()));
+            }
+            None => import
+                .unmapped
+                .push(format!("{flag} {value} (anonymous volume)")),
+        },
+        "--tmpfs" => parse_tmpfs_flag(import, value),
+        "--mount" => {
+            if let Err(flag) = parse_mount_flag(import, value) {
+                import.unmapped.push(flag);
+            }
+        }
+        "-e" | "--env" => {
+            let (key, value) = value.split_once('=').unwrap_or((value, ""));
+            import.entry.env_vars.push(EnvVar {
+                key: key.to_string(),
+                value: value.to_string(),
+            });
+        }
+        "--env-file" => {
+            let path = Path::new(value);
+            if path.is_file() {
+                let vars = env_files::read_env_file(path, false);
+                import
+                    .notes
+                    .push(format!("imported {} vars from {value}", vars.len()));
+                import.entry.env_vars.extend(vars);
+            } else {
+                import
+                    .unmapped
+                    .push(format!("{flag} {value} (file not found)"));
+            }
+        }
+        "--name" => import.entry.service_name = default_service_name(value, 0),
+        "--restart" => apply_property(import, ServiceProperty::Restart, flag, value),
+        "--network" | "--net" => {
+            if ServiceProperty::NetworkMode
+                .apply(&mut import.entry.options, value)
+                .is_err()
+            {
+                import.unmapped.push(format!(
+                    "{flag} {value} (define the network under networks: manually)"
+                ));
+            }
+        }
+        "--entrypoint" => {
+            import.entry.options.entrypoint = Some(CommandSpec::Exec(vec![value.to_string()]))
+        }
+        "-u" | "--user" => apply_property(import, ServiceProperty::User, flag, value),
+        "-w" | "--workdir" => apply_property(import, ServiceProperty::WorkingDir, flag, value),
+        "-h" | "--hostname" => apply_property(import, ServiceProperty::Hostname, flag, value),
+        "-l" | "--label" => apply_property(import, ServiceProperty::Labels, flag, value),
+        "--add-host" => apply_property(import, ServiceProperty::ExtraHosts, flag, value),
+        "--dns" => apply_property(import, ServiceProperty::Dns, flag, value),
+        "--cap-add" => apply_property(import, ServiceProperty::CapAdd, flag, value),
+        "--cap-drop" => apply_property(import, ServiceProperty::CapDrop, flag, value),
+        "--security-opt" => apply_property(import, ServiceProperty::SecurityOpt, flag, value),
+        "--ulimit" => apply_property(import, ServiceProperty::Ulimits, flag, value),
+        "--sysctl" => apply_property(import, ServiceProperty::Sysctls, flag, value),
+        "--stop-timeout" => apply_property(
+            import,
+            ServiceProperty::StopGracePeriod,
+            flag,
+            &format!("{value}s"),
+        ),
+        "--log-driver" => apply_property(import, ServiceProperty::Logging, flag, value),
+        "-m" | "--memory" => apply_deploy(import, DeployProperty::LimitMemory, flag, value),
+        "--memory-reservation" => apply_deploy(import, DeployProperty::ReserveMemory, flag, value),
+        "--cpus" => apply_deploy(import, DeployProperty::LimitCpus, flag, value),
+        "--pids-limit" => apply_deploy(import, DeployProperty::LimitPids, flag, value),
+        "--health-cmd" => healthcheck_mut(import).test = value.to_string(),
+        "--health-interval" | "--health-timeout" | "--health-start-period" => {
+            if !healthcheck::is_valid_duration(value) {
+                import
+                    .unmapped
+                    .push(format!("{flag} {value} (invalid duration)"));
+                return;
+            }
+            let healthcheck = healthcheck_mut(import);
+            let slot = match flag {
+                "--health-interval" => &mut healthcheck.interval,
+                "--health-timeout" => &mut healthcheck.timeout,
+                _ => &mut healthcheck.start_period,
+            };
+            *slot = Some(value.to_string());
+        }
+        "--health-retries" => match value.parse() {
+            Ok(retries) => healthcheck_mut(import).retries = Some(retries),
+            Err(_) => import.unmapped.push(format!("{flag} {value}")),
+        },
+        _ => import.unmapped.push(format!("{flag} {value}")),
+    }
+}
+
+fn apply_switch(import: &mut RunImport, flag: &str) {
+    let options = &mut import.entry.options;
+    match flag {
+        "-d" | "--detach" => {}
+        "-i" | "--interactive" if !options.stdin_open => ServiceProperty::StdinOpen.toggle(options),
+        "-t" | "--tty" if !options.tty => ServiceProperty::Tty.toggle(options),
+        "--init" if !options.init => ServiceProperty::Init.toggle(options),
+        "--read-only" if !options.read_only => ServiceProperty::ReadOnly.toggle(options),
+        "-i" | "--interactive" | "-t" | "--tty" | "--init" | "--read-only" => {}
+        "--no-healthcheck" => healthcheck_mut(import).disable = true,
+        other => import.unmapped.push(other.to_string()),
+    }
+}
+
+pub fn parse_docker_run(input: &str) -> Result<RunImport, String> {
+    let joined = input.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut words = dockerfile::split_words(&joined);
+    strip_prefix_words(&mut words);
+
+    let mut import = RunImport::default();
+    let mut index = 0;
+    let mut image = None;
+    while index < words.len() {
+        let word = words[index].clone();
+        index += 1;
+        if word == "--" {
+            continue;
+        }
+        if !word.starts_with('-') || word == "-" {
+            image = Some(word);
+            break;
+        }
+
+        for (flag, value) in expand_short_flags(&word) {
+            if let Some(value) = value {
+                apply_flag(&mut import, &flag, &value);
+            } else if BOOLEAN_FLAGS.contains(&flag.as_str()) {
+                apply_switch(&mut import, &flag);
+            } else if let Some(value) = words.get(index).cloned() {
+                index += 1;
+                apply_flag(&mut import, &flag, &value);
+            } else {
+                import.unmapped.push(format!("{flag} (missing value)"));
+            }
+        }
+    }
+
+    let image = image.ok_or_else(|| "no image found in docker run command".to_string())?;
+    let (namespace, repo, tag) =
+        build::split_image_ref(&image).ok_or_else(|| format!("invalid image '{image}'"))?;
+    import.entry.namespace = namespace;
+    import.entry.repo = repo;
+    import.entry.tag = tag;
+    if import.entry.service_name.is_empty() {
+        import.entry.service_name = default_service_name(&import.entry.repo, 0);
+    }
+
+    let command = &words[index..];
+    if !command.is_empty() {
+        import.entry.command = Some(CommandSpec::Exec(command.to_vec()));
+    }
+    if import
+        .entry
+        .healthcheck
+        .as_ref()
+        .is_some_and(|healthcheck| !healthcheck.disable && healthcheck.test.is_empty())
+    {
+        import
+            .unmapped
+            .push("--health-* without --health-cmd".to_string());
+        import.entry.healthcheck = None;
+    }
+    Ok(import)
+}
+
+pub fn apply_import(app: &mut App, import: RunImport) -> Vec<String> {
+    let mut entry = import.entry;
+    let requested = entry.service_name.clone();
+    entry.service_name = app.unique_service_name(&requested);
+
+    let mut logs = vec![format!(
+        "imported {} ({})",
+        entry.service_name,
+        entry.describe()
+    )];
+    if entry.service_name != requested {
+        logs.push(format!(
+            "renamed {requested} to {} to keep names unique",
+            entry.service_name
+        ));
+    }
+    for volume in import.volumes {
+        if !app
+            .volumes
+            .iter()
+            .any(|existing| existing.name == volume.name)
+        {
+            logs.push(format!("added volume {}", volume.name));
+            app.volumes.push(volume);
+        }
+    }
+    logs.extend(import.notes);
+    for flag in import.unmapped {
+        logs.push(format!("not mapped: {flag}"));
+    }
+
+    app.images.push(entry);
+    app.images_selected = app.images.len() - 1;
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(import: &RunImport) -> Vec<(&str, &str)> {
+        import
+            .entry
+            .env_vars
+            .iter()
+            .map(|var| (var.key.as_str(), var.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn attached_short_flag_values_are_split() {
+        let import =
+            parse_docker_run("docker run -p8080:80 -eFOO=bar -e=BAZ=qux -wdata nginx").unwrap();
+        assert_eq!(import.entry.port_mapping, "8080:80");
+        assert_eq!(env(&import), vec![("FOO", "bar"), ("BAZ", "qux")]);
+        assert_eq!(import.entry.options.working_dir.as_deref(), Some("data"));
+        assert!(import.unmapped.is_empty(), "{:?}", import.unmapped);
+        assert_eq!(import.entry.repo, "nginx");
+    }
+
+    #[test]
+    fn grouped_switches_end_in_value_flag() {
+        let import =
+            parse_docker_run("docker run -itp 8080:80 -dit -de FOO=1 redis:7 redis-server")
+                .unwrap();
+        assert!(import.entry.options.stdin_open);
+        assert!(import.entry.options.tty);
+        assert_eq!(import.entry.port_mapping, "8080:80");
+        assert_eq!(env(&import), vec![("FOO", "1")]);
+        assert_eq!(import.entry.tag, "7");
+        assert_eq!(
+            import.entry.command,
+            Some(CommandSpec::Exec(vec!["redis-server".to_string()]))
+        );
+    }
+
+    #[test]
+    fn separate_values_and_long_flags_still_parse() {
+        let import =
+            parse_docker_run("docker run --name web -p 80:80 --env=A=b -v data:/data nginx")
+                .unwrap();
+        assert_eq!(import.entry.service_name, "web");
+        assert_eq!(import.entry.port_mapping, "80:80");
+        assert_eq!(env(&import), vec![("A", "b")]);
+        assert_eq!(import.entry.mounts.len(), 1);
+        assert!(import.unmapped.is_empty(), "{:?}", import.unmapped);
+    }
+}
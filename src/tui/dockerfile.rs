@@ -0,0 +1,265 @@
+use std::path::{Path, PathBuf};
+
+use crate::tui::app::EnvVar;
+
+#[derive(Debug, Clone, Default)]
+pub struct DockerfileStage {
+    pub base_image: String,
+    pub name: Option<String>,
+    pub exposed_ports: Vec<u16>,
+    pub volumes: Vec<String>,
+    pub env: Vec<EnvVar>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DockerfileInfo {
+    pub stages: Vec<DockerfileStage>,
+}
+
+impl DockerfileInfo {
+    pub fn stage(&self, target: Option<&str>) -> Option<&DockerfileStage> {
+        match target {
+            Some(target) => self
+                .stages
+                .iter()
+                .find(|stage| stage.name.as_deref() == Some(target)),
+            None => self.stages.last(),
+        }
+    }
+}
+
+pub fn dockerfile_path(context: &str, dockerfile: Option<&str>) -> PathBuf {
+    Path::new(context).join(dockerfile.unwrap_or("Dockerfile"))
+}
+
+pub fn read_dockerfile(path: &Path) -> std::io::Result<DockerfileInfo> {
+    std::fs::read_to_string(path).map(|contents| parse_dockerfile(&contents))
+}
+
+pub fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some(open), ch) if ch == open => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            (Some(_), ch) => current.push(ch),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                    in_word = true;
+                }
+            }
+            (None, ch) if ch.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, ch) => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+fn logical_lines(contents: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pending = String::new();
+
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+        match trimmed.strip_suffix('\\') {
+            Some(continued) => {
+                pending.push_str(continued);
+                pending.push(' ');
+            }
+            None => {
+                pending.push_str(trimmed);
+                if !pending.trim().is_empty() {
+                    lines.push(std::mem::take(&mut pending));
+                }
+                pending.clear();
+            }
+        }
+    }
+    if !pending.trim().is_empty() {
+        lines.push(pending);
+    }
+    lines
+}
+
+fn json_or_words(arguments: &str) -> Vec<String> {
+    let trimmed = arguments.trim();
+    match trimmed
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        Some(items) => items
+            .split(',')
+            .map(|item| item.trim().trim_matches('"').to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        None => split_words(trimmed),
+    }
+}
+
+fn parse_env(arguments: &str) -> Vec<EnvVar> {
+    let words = split_words(arguments);
+    if words.first().is_some_and(|word| !word.contains('=')) {
+        let (key, value) = arguments
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((arguments, ""));
+        return vec![EnvVar {
+            key: key.to_string(),
+            value: value.trim().to_string(),
+        }];
+    }
+
+    words
+        .iter()
+        .filter_map(|word| word.split_once('='))
+        .map(|(key, value)| EnvVar {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+        .collect()
+}
+
+pub fn parse_dockerfile(contents: &str) -> DockerfileInfo {
+    let mut info = DockerfileInfo::default();
+
+    for line in logical_lines(contents) {
+        let (instruction, arguments) = line
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((line.trim(), ""));
+        let instruction = instruction.to_ascii_uppercase();
+
+        if instruction == "FROM" {
+            let words: Vec<&str> = arguments
+                .split_whitespace()
+                .filter(|word| !word.starts_with("--"))
+                .collect();
+            let name = match words.as_slice() {
+                [_, keyword, name, ..] if keyword.eq_ignore_ascii_case("as") => {
+                    Some(name.to_string())
+                }
+                _ => None,
+            };
+            let base_image = words.first().map(ToString::to_string).unwrap_or_default();
+            let inherited = info
+                .stages
+                .iter()
+                .find(|stage| stage.name.as_deref() == Some(base_image.as_str()))
+                .cloned();
+            info.stages.push(DockerfileStage {
+                base_image,
+                name,
+                ..inherited.unwrap_or_default()
+            });
+            continue;
+        }
+
+        let Some(stage) = info.stages.last_mut() else {
+            continue;
+        };
+        match instruction.as_str() {
+            "EXPOSE" => {
+                for port in arguments.split_whitespace() {
+                    let number = port.split('/').next().unwrap_or(port);
+                    if let Ok(port) = number.parse::<u16>()
+                        && !stage.exposed_ports.contains(&port)
+                    {
+                        stage.exposed_ports.push(port);
+                    }
+                }
+            }
+            "VOLUME" => {
+                for volume in json_or_words(arguments) {
+                    if !stage.volumes.contains(&volume) {
+                        stage.volumes.push(volume);
+                    }
+                }
+            }
+            "ENV" => {
+                for env in parse_env(arguments) {
+                    stage.env.retain(|existing| existing.key != env.key);
+                    stage.env.push(env);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_words_handles_quotes_and_escapes() {
+        assert_eq!(
+            split_words(r#"a "b c" 'd "e"' f\ g "h\"i" """#),
+            ["a", "b c", "d \"e\"", "f g", "h\"i", ""]
+        );
+    }
+
+    #[test]
+    fn stages_inherit_from_named_parents() {
+        let info = parse_dockerfile(concat!(
+            "# base image\n",
+            "FROM node:20 AS base\n",
+            "ENV NODE_ENV=production \\\n",
+            "    PORT=3000\n",
+            "EXPOSE 3000 9229/tcp\n",
+            "\n",
+            "FROM base AS dev\n",
+            "ENV NODE_ENV development\n",
+            "VOLUME [\"/app/node_modules\", \"/cache\"]\n",
+            "EXPOSE 3000\n",
+            "\n",
+            "FROM --platform=linux/amd64 nginx\n",
+        ));
+
+        assert_eq!(info.stages.len(), 3);
+        let dev = info.stage(Some("dev")).unwrap();
+        assert_eq!(dev.base_image, "base");
+        assert_eq!(dev.exposed_ports, [3000, 9229]);
+        assert_eq!(dev.volumes, ["/app/node_modules", "/cache"]);
+        let env: Vec<(&str, &str)> = dev
+            .env
+            .iter()
+            .map(|env| (env.key.as_str(), env.value.as_str()))
+            .collect();
+        assert_eq!(env, [("PORT", "3000"), ("NODE_ENV", "development")]);
+
+        let last = info.stage(None).unwrap();
+        assert_eq!(last.base_image, "nginx");
+        assert!(last.exposed_ports.is_empty());
+        assert!(info.stage(Some("missing")).is_none());
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::api::{self, DockerEngine, LocalImageConfig};
+
+pub const LOCAL_SUGGESTION_LIMIT: usize = 8;
+
+enum PullEvent {
+    Progress(String),
+    Finished(Result<(), String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectPurpose {
+    SuggestPorts,
+    OfferPull,
+    SuggestHealthcheck(usize),
+}
+
+pub enum EngineReply {
+    LocalImages(Result<Vec<String>, String>),
+    Inspected {
+        reference: String,
+        purpose: InspectPurpose,
+        result: Result<Option<LocalImageConfig>, String>,
+    },
+}
+
+pub struct EngineQueries {
+    sender: Sender<EngineReply>,
+    replies: Receiver<EngineReply>,
+}
+
+pub struct PullTask {
+    pub reference: String,
+    pub finished: bool,
+    events: Receiver<PullEvent>,
+}
+
+fn block_on<T>(
+    action: impl AsyncFnOnce(&DockerEngine) -> Result<T, api::ApiError>,
+) -> Result<T, String> {
+    let engine = DockerEngine::from_env()?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|error| format!("runtime error: {error}"))?;
+    runtime
+        .block_on(action(&engine))
+        .map_err(|error| format!("{} ({})", error, engine.endpoint().describe()))
+}
+
+pub fn matching_images(local_images: &[String], query: &str) -> Vec<String> {
+    if local_images.is_empty() {
+        return Vec::new();
+    }
+    api::filter_tags(local_images, query.trim(), LOCAL_SUGGESTION_LIMIT)
+}
+
+impl EngineQueries {
+    pub fn new() -> Self {
+        let (sender, replies) = mpsc::channel();
+        Self { sender, replies }
+    }
+
+    pub fn list_images(&self) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = block_on(async |engine| engine.list_images().await);
+            let _ = sender.send(EngineReply::LocalImages(result));
+        });
+    }
+
+    pub fn inspect(&self, reference: &str, purpose: InspectPurpose) {
+        let sender = self.sender.clone();
+        let reference = reference.to_string();
+        thread::spawn(move || {
+            let result = block_on(async |engine| engine.inspect_image(&reference).await);
+            let _ = sender.send(EngineReply::Inspected {
+                reference,
+                purpose,
+                result,
+            });
+        });
+    }
+
+    pub fn poll(&self) -> Vec<EngineReply> {
+        self.replies.try_iter().collect()
+    }
+}
+
+impl Default for EngineQueries {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn spawn_pull(reference: &str) -> PullTask {
+    let (sender, events) = mpsc::channel();
+    let owned = reference.to_string();
+    thread::spawn(move || {
+        let progress = sender.clone();
+        let result = block_on(async |engine| {
+            engine
+                .pull_image(&owned, |line| {
+                    let _ = progress.send(PullEvent::Progress(line.to_string()));
+                })
+                .await
+        });
+        let _ = sender.send(PullEvent::Finished(result));
+    });
+    PullTask {
+        reference: reference.to_string(),
+        finished: false,
+        events,
+    }
+}
+
+impl PullTask {
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut logs = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                PullEvent::Progress(line) => logs.push(format!("pull {}: {line}", self.reference)),
+                PullEvent::Finished(Ok(())) => {
+                    self.finished = true;
+                    logs.push(format!("pulled {}", self.reference));
+                }
+                PullEvent::Finished(Err(error)) => {
+                    self.finished = true;
+                    logs.push(format!("pull failed: {error}"));
+                }
+            }
+        }
+        logs
+    }
+}
@@ -0,0 +1,436 @@
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::tui::{
+    app::{EnvVar, ImageEntry, yaml_quote},
+    paths,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvFile {
+    pub path: String,
+    pub required: bool,
+    pub raw: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvFileMode {
+    Import,
+    Reference,
+}
+
+impl EnvFileMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Import => "import variables into environment:",
+            Self::Reference => "reference with env_file:",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Import => Self::Reference,
+            Self::Reference => Self::Import,
+        }
+    }
+}
+
+impl EnvFile {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            required: true,
+            raw: false,
+        }
+    }
+
+    fn is_short(&self) -> bool {
+        self.required && !self.raw
+    }
+
+    pub fn describe(&self) -> String {
+        let mut flags = Vec::new();
+        if !self.required {
+            flags.push("optional");
+        }
+        if self.raw {
+            flags.push("raw");
+        }
+        if flags.is_empty() {
+            self.path.clone()
+        } else {
+            format!("{} ({})", self.path, flags.join(", "))
+        }
+    }
+
+    pub fn read(&self, base: &Path) -> Vec<EnvVar> {
+        read_env_file(&paths::resolve(base, &self.path), self.raw)
+    }
+}
+
+fn unescape_double_quoted(value: &str) -> String {
+    let mut output = String::new();
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => output.push('\n'),
+                Some('t') => output.push('\t'),
+                Some('r') => output.push('\r'),
+                Some(escaped @ ('"' | '\\' | '$')) => output.push(escaped),
+                Some(other) => {
+                    output.push('\\');
+                    output.push(other);
+                }
+                None => output.push('\\'),
+            },
+            other => output.push(other),
+        }
+    }
+    output
+}
+
+fn env_value(value: &str) -> String {
+    let value = value.trim();
+    if let Some(quoted) = value.strip_prefix('"') {
+        return unescape_double_quoted(quoted);
+    }
+    if let Some(quoted) = value.strip_prefix('\'') {
+        return quoted
+            .split_once('\'')
+            .map_or(quoted, |(inner, _)| inner)
+            .to_string();
+    }
+    let comment = value
+        .char_indices()
+        .find(|(index, ch)| *ch == '#' && value[..*index].ends_with(char::is_whitespace))
+        .map_or(value.len(), |(index, _)| index);
+    value[..comment].trim_end().to_string()
+}
+
+pub fn read_env_file(path: &Path, raw: bool) -> Vec<EnvVar> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim_start)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.trim_start_matches("export ").split_once('='))
+        .map(|(key, value)| EnvVar {
+            key: key.trim().to_string(),
+            value: if raw {
+                value.to_string()
+            } else {
+                env_value(value)
+            },
+        })
+        .collect()
+}
+
+pub fn render(files: &[EnvFile], output: &mut String) {
+    if files.is_empty() {
+        return;
+    }
+    if files.iter().all(EnvFile::is_short) {
+        if let [file] = files {
+            output.push_str(&format!("    env_file: {}\n", yaml_quote(&file.path)));
+            return;
+        }
+        output.push_str("    env_file:\n");
+        for file in files {
+            output.push_str(&format!("      - {}\n", yaml_quote(&file.path)));
+        }
+        return;
+    }
+    output.push_str("    env_file:\n");
+    for file in files {
+        output.push_str(&format!("      - path: {}\n", yaml_quote(&file.path)));
+        if !file.required {
+            output.push_str("        required: false\n");
+        }
+        if file.raw {
+            output.push_str("        format: raw\n");
+        }
+    }
+}
+
+pub fn duplicate_keys(vars: &[EnvVar]) -> Vec<String> {
+    let mut duplicates: Vec<String> = Vec::new();
+    for (index, var) in vars.iter().enumerate() {
+        if vars[..index].iter().any(|earlier| earlier.key == var.key)
+            && !duplicates.contains(&var.key)
+        {
+            duplicates.push(var.key.clone());
+        }
+    }
+    duplicates
+}
+
+fn duplicates_count(vars: &[EnvVar]) -> usize {
+    vars.iter()
+        .enumerate()
+        .filter(|(index, var)| vars[..*index].iter().any(|earlier| earlier.key == var.key))
+        .count()
+}
+
+#[derive(Debug)]
+struct CachedKeys {
+    path: String,
+    modified: Option<SystemTime>,
+    keys: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct EnvFileKeys {
+    files: Vec<CachedKeys>,
+}
+
+impl EnvFileKeys {
+    pub fn refresh(&mut self, base: &Path, images: &[ImageEntry]) {
+        let files: Vec<&EnvFile> = images.iter().flat_map(|image| &image.env_files).collect();
+        self.files
+            .retain(|cached| files.iter().any(|file| file.path == cached.path));
+        for file in files {
+            let modified = std::fs::metadata(paths::resolve(base, &file.path))
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            match self
+                .files
+                .iter_mut()
+                .find(|cached| cached.path == file.path)
+            {
+                Some(cached) if cached.modified == modified => {}
+                Some(cached) => {
+                    cached.modified = modified;
+                    cached.keys = file.read(base).into_iter().map(|var| var.key).collect();
+                }
+                None => self.files.push(CachedKeys {
+                    path: file.path.clone(),
+                    modified,
+                    keys: file.read(base).into_iter().map(|var| var.key).collect(),
+                }),
+            }
+        }
+    }
+
+    pub fn var_count(&self, image: &ImageEntry) -> usize {
+        let mut keys: Vec<&String> = image
+            .env_files
+            .iter()
+            .filter_map(|file| self.files.iter().find(|cached| cached.path == file.path))
+            .flat_map(|cached| &cached.keys)
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys.len()
+    }
+}
+
+pub fn import(image: &mut ImageEntry, vars: Vec<EnvVar>, source: &str) -> Vec<String> {
+    let mut logs = Vec::new();
+    let duplicates = duplicate_keys(&vars);
+    if !duplicates.is_empty() {
+        logs.push(format!(
+            "{source}: duplicate keys {} (last value wins)",
+            duplicates.join(", ")
+        ));
+    }
+
+    let mut unique: Vec<EnvVar> = Vec::new();
+    for var in vars.into_iter().filter(|var| !var.key.is_empty()) {
+        match unique.iter_mut().find(|existing| existing.key == var.key) {
+            Some(existing) => existing.value = var.value,
+            None => unique.push(var),
+        }
+    }
+
+    let mut added = 0;
+    let mut conflicts = Vec::new();
+    for var in unique {
+        match image
+            .env_vars
+            .iter()
+            .find(|existing| existing.key == var.key)
+        {
+            Some(existing) if existing.value == var.value => {}
+            Some(_) => conflicts.push(var.key),
+            None => {
+                image.env_vars.push(var);
+                added += 1;
+            }
+        }
+    }
+    logs.push(format!(
+        "imported {added} variables from {source} into {}",
+        image.service_name
+    ));
+    if !conflicts.is_empty() {
+        logs.push(format!(
+            "{}: kept existing values for conflicting keys {}",
+            image.service_name,
+            conflicts.join(", ")
+        ));
+    }
+    logs
+}
+
+pub fn reference(image: &mut ImageEntry, file: EnvFile, base: &Path) -> Vec<String> {
+    let mut logs = Vec::new();
+    if image
+        .env_files
+        .iter()
+        .any(|existing| existing.path == file.path)
+    {
+        logs.push(format!(
+            "{} already uses env_file {}",
+            image.service_name, file.path
+        ));
+        return logs;
+    }
+
+    let vars = file.read(base);
+    if vars.is_empty() && !paths::exists(base, &file.path) {
+        logs.push(format!(
+            "warning: {} does not exist{}",
+            file.path,
+            if file.required {
+                " and is required"
+            } else {
+                ""
+            }
+        ));
+    }
+    let duplicates = duplicate_keys(&vars);
+    if !duplicates.is_empty() {
+        logs.push(format!(
+            "{}: duplicate keys {} (last value wins)",
+            file.path,
+            duplicates.join(", ")
+        ));
+    }
+    let overridden: Vec<&str> = vars
+        .iter()
+        .filter(|var| {
+            image
+                .env_vars
+                .iter()
+                .any(|inline| inline.key == var.key && inline.value != var.value)
+        })
+        .map(|var| var.key.as_str())
+        .collect();
+    if !overridden.is_empty() {
+        logs.push(format!(
+            "{}: environment: overrides {} from {}",
+            image.service_name,
+            overridden.join(", "),
+            file.path
+        ));
+    }
+    let shadowed: Vec<String> = image
+        .env_files
+        .iter()
+        .flat_map(|existing| existing.read(base))
+        .filter(|earlier| {
+            vars.iter()
+                .any(|var| var.key == earlier.key && var.value != earlier.value)
+        })
+        .map(|earlier| earlier.key)
+        .collect();
+    if !shadowed.is_empty() {
+        logs.push(format!(
+            "{}: later env_file {} overrides {}",
+            image.service_name,
+            file.path,
+            shadowed.join(", ")
+        ));
+    }
+
+    logs.push(format!(
+        "{} now reads {} variables from env_file {}",
+        image.service_name,
+        vars.len() - duplicates_count(&vars),
+        file.describe()
+    ));
+    image.env_files.push(file);
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_env_file_follows_dotenv_syntax() {
+        let path = std::env::temp_dir().join(format!("acdc-dotenv-{}.env", std::process::id()));
+        std::fs::write(
+            &path,
+            "# comment\nexport PLAIN=value # trailing\nHASH=a#b\nDOUBLE=\"line\\nnext \\\"q\\\"\" # note\nSINGLE='it''s'\nEMPTY=\n",
+        )
+        .unwrap();
+        let parsed: Vec<(String, String)> = read_env_file(&path, false)
+            .into_iter()
+            .map(|var| (var.key, var.value))
+            .collect();
+        let raw = read_env_file(&path, true);
+        std::fs::remove_file(&path).unwrap();
+
+        let expected = [
+            ("PLAIN", "value"),
+            ("HASH", "a#b"),
+            ("DOUBLE", "line\nnext \"q\""),
+            ("SINGLE", "it"),
+            ("EMPTY", ""),
+        ];
+        assert_eq!(
+            parsed,
+            expected.map(|(key, value)| (key.to_string(), value.to_string()))
+        );
+        assert_eq!(raw[0].value, "value # trailing");
+        assert_eq!(raw[3].value, "'it''s'");
+    }
+
+    #[test]
+    fn render_uses_long_syntax_only_when_needed() {
+        let mut output = String::new();
+        render(&[EnvFile::new(".env")], &mut output);
+        assert_eq!(output, "    env_file: \".env\"\n");
+
+        let mut optional = EnvFile::new("local.env");
+        optional.required = false;
+        let mut output = String::new();
+        render(&[EnvFile::new(".env"), optional], &mut output);
+        assert_eq!(
+            output,
+            "    env_file:\n      - path: \".env\"\n      - path: \"local.env\"\n        required: false\n"
+        );
+    }
+
+    #[test]
+    fn var_count_rereads_files_that_changed_on_disk() {
+        let base = std::env::temp_dir().join(format!("acdc-env-files-{}", std::process::id()));
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::write(base.join("a.env"), "ONE=1\nTWO=2\n").unwrap();
+        std::fs::write(base.join("b.env"), "TWO=3\nTHREE=3\n").unwrap();
+        let image = ImageEntry {
+            env_files: vec![EnvFile::new("a.env"), EnvFile::new("b.env")],
+            ..ImageEntry::default()
+        };
+        let mut keys = EnvFileKeys::default();
+        keys.refresh(&base, std::slice::from_ref(&image));
+        assert_eq!(keys.var_count(&image), 3);
+
+        std::fs::write(base.join("a.env"), "ONE=1\nFOUR=4\n").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(base.join("a.env"))
+            .unwrap()
+            .set_modified(SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        keys.refresh(&base, std::slice::from_ref(&image));
+        assert_eq!(keys.var_count(&image), 4);
+
+        std::fs::remove_dir_all(&base).unwrap();
+        keys.refresh(&base, std::slice::from_ref(&image));
+        assert_eq!(keys.var_count(&image), 0);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::tui::app::{EnvVar, ImageEntry, VolumeMount, yaml_quote};
+
+pub const BASE_COMPOSE_FILE: &str = "compose.yaml";
+pub const DEFAULT_ENVIRONMENTS: [&str; 2] = ["dev", "prod"];
+
+#[derive(Debug, Clone, Default)]
+pub struct ServiceOverride {
+    pub service: String,
+    pub port_mapping: Option<String>,
+    pub env_vars: Vec<EnvVar>,
+    pub mounts: Vec<VolumeMount>,
+    pub replicas: Option<u32>,
+}
+
+impl ServiceOverride {
+    pub fn is_empty(&self) -> bool {
+        self.port_mapping.is_none()
+            && self.env_vars.is_empty()
+            && self.mounts.is_empty()
+            && self.replicas.is_none()
+    }
+
+    fn apply(&self, image: &mut ImageEntry) {
+        if let Some(port_mapping) = self.port_mapping.as_ref() {
+            image.port_mapping = port_mapping.clone();
+        }
+        for env in &self.env_vars {
+            match image
+                .env_vars
+                .iter_mut()
+                .find(|existing| existing.key == env.key)
+            {
+                Some(existing) => existing.value = env.value.clone(),
+                None => image.env_vars.push(env.clone()),
+            }
+        }
+        for mount in &self.mounts {
+            match image
+                .mounts
+                .iter_mut()
+                .find(|existing| existing.container_path() == mount.container_path())
+            {
+                Some(existing) => existing.source = mount.source.clone(),
+                None => image.mounts.push(mount.clone()),
+            }
+        }
+        if let Some(replicas) = self.replicas {
+            image.deploy.replicas = Some(replicas);
+        }
+    }
+
+    fn render(&self, output: &mut String) {
+        output.push_str(&format!("  {}:\n", self.service));
+        if let Some(port_mapping) = self.port_mapping.as_ref() {
+            if port_mapping.is_empty() {
+                output.push_str("    ports: !reset []\n");
+            } else {
+                output.push_str(&format!(
+                    "    ports: !override\n      - \"{port_mapping}\"\n"
+                ));
+            }
+        }
+        if !self.mounts.is_empty() {
+            output.push_str("    volumes:\n");
+            for mount in &self.mounts {
+                mount.render(output);
+            }
+        }
+        if !self.env_vars.is_empty() {
+            output.push_str("    environment:\n");
+            for env in &self.env_vars {
+                output.push_str(&format!("      {}: {}\n", env.key, yaml_quote(&env.value)));
+            }
+        }
+        if let Some(replicas) = self.replicas {
+            output.push_str(&format!("    deploy:\n      replicas: {replicas}\n"));
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub name: String,
+    pub overrides: Vec<ServiceOverride>,
+}
+
+impl Environment {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            overrides: Vec::new(),
+        }
+    }
+
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let name = match file_name.strip_prefix("compose.")?.strip_suffix(".yaml")? {
+            "override" => "dev",
+            "dev" => return None,
+            name => name,
+        };
+        is_valid_environment_name(name).then(|| Self::new(name))
+    }
+
+    pub fn file_name(&self) -> String {
+        if self.name == "dev" {
+            "compose.override.yaml".to_string()
+        } else {
+            format!("compose.{}.yaml", self.name)
+        }
+    }
+
+    pub fn override_for(&self, service: &str) -> Option<&ServiceOverride> {
+        self.overrides.iter().find(|entry| entry.service == service)
+    }
+
+    pub fn set_override(&mut self, entry: ServiceOverride) {
+        self.overrides
+            .retain(|existing| existing.service != entry.service);
+        if !entry.is_empty() {
+            self.overrides.push(entry);
+        }
+    }
+
+    pub fn rename_service(&mut self, from: &str, to: &str) {
+        for entry in self
+            .overrides
+            .iter_mut()
+            .filter(|entry| entry.service == from)
+        {
+            entry.service = to.to_string();
+        }
+    }
+
+    pub fn remove_service(&mut self, service: &str) {
+        self.overrides.retain(|entry| entry.service != service);
+    }
+
+    pub fn has_overrides(&self) -> bool {
+        self.overrides.iter().any(|entry| !entry.is_empty())
+    }
+
+    pub fn merged_images(&self, images: &[ImageEntry]) -> Vec<ImageEntry> {
+        images
+            .iter()
+            .map(|image| {
+                let mut merged = image.clone();
+                if let Some(entry) = self.override_for(&image.service_name) {
+                    entry.apply(&mut merged);
+                }
+                merged
+            })
+            .collect()
+    }
+
+    pub fn render(&self, images: &[ImageEntry]) -> String {
+        let mut output = String::from("services:\n");
+        for image in images {
+            if let Some(entry) = self
+                .override_for(&image.service_name)
+                .filter(|entry| !entry.is_empty())
+            {
+                entry.render(&mut output);
+            }
+        }
+        output
+    }
+
+    pub fn compose_command(&self, base_file: &str) -> String {
+        format!(
+            "docker compose -f {base_file} -f {} up -d",
+            self.file_name()
+        )
+    }
+}
+
+pub fn is_valid_environment_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
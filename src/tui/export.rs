@@ -0,0 +1,1436 @@
+use std::path::Path;
+
+use crate::tui::{
+    app::{self, App, EnvVar, HealthcheckForm, ImageEntry, VolumeEntry, VolumeMount},
+    command::{shell_join, shell_quote},
+    deploy, env_files, healthcheck,
+    properties::RestartPolicy,
+    secrets,
+    volumes::MountType,
+};
+
+const PVC_SIZE: &str = "1Gi";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    DockerRun,
+    Kubernetes,
+    Quadlet,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportFile {
+    pub path: String,
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Export {
+    pub files: Vec<ExportFile>,
+    pub warnings: Vec<String>,
+}
+
+impl ExportFormat {
+    pub fn all() -> [Self; 3] {
+        [Self::DockerRun, Self::Kubernetes, Self::Quadlet]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::DockerRun => "docker-run",
+            Self::Kubernetes => "kubernetes",
+            Self::Quadlet => "quadlet",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::DockerRun => "shell script of docker volume create / docker run commands",
+            Self::Kubernetes => "Deployment, Service and PersistentVolumeClaim manifests in ./k8s",
+            Self::Quadlet => "Podman Quadlet .container, .volume and .network units in ./quadlet",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "docker-run" | "docker" | "sh" => Some(Self::DockerRun),
+            "kubernetes" | "k8s" => Some(Self::Kubernetes),
+            "quadlet" | "podman" => Some(Self::Quadlet),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::DockerRun => Self::Kubernetes,
+            Self::Kubernetes => Self::Quadlet,
+            Self::Quadlet => Self::DockerRun,
+        }
+    }
+}
+
+impl Export {
+    pub fn write(&self, base: &Path) -> std::io::Result<()> {
+        for file in &self.files {
+            let path = base.join(&file.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, &file.contents)?;
+            #[cfg(unix)]
+            if file.path.ends_with(".sh") {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn resource_name(name: &str) -> String {
+    let lowered: String = name
+        .to_ascii_lowercase()
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' })
+        .collect();
+    let trimmed = lowered.trim_matches('-');
+    if trimmed.is_empty() {
+        "app".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn json_quote(word: &str) -> String {
+    format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn duration_seconds(input: &str) -> Option<u64> {
+    let mut rest = input.trim();
+    let mut total = 0;
+    while !rest.is_empty() {
+        let digits = rest.chars().take_while(|ch| ch.is_ascii_digit()).count();
+        let amount: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .chars()
+            .take_while(|ch| ch.is_ascii_alphabetic())
+            .count();
+        total += match &rest[..unit_len] {
+            "h" => amount * 3600,
+            "m" => amount * 60,
+            "s" => amount,
+            "ms" | "us" | "ns" => 0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+    }
+    Some(total)
+}
+
+fn kubernetes_memory(input: &str) -> String {
+    match deploy::parse_memory(input) {
+        Some((amount, Some(unit))) => format!("{amount}{}i", unit.to_ascii_uppercase()),
+        Some((amount, None)) => amount.to_string(),
+        None => input.to_string(),
+    }
+}
+
+fn interpolation_vars(app: &App) -> Vec<EnvVar> {
+    let mut vars = env_files::read_env_file(&app.project_dir().join(".env"), false);
+    for pending in &app.dotenv {
+        match vars.iter_mut().find(|var| var.key == pending.key) {
+            Some(existing) => existing.value = pending.value.clone(),
+            None => vars.push(pending.clone()),
+        }
+    }
+    vars
+}
+
+fn interpolate(value: &str, vars: &[EnvVar]) -> Result<String, String> {
+    let lookup = |name: &str| {
+        vars.iter()
+            .find(|var| var.key == name)
+            .map(|var| var.value.as_str())
+    };
+    let mut output = String::new();
+    let mut rest = value;
+    while let Some(index) = rest.find('$') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            output.push('$');
+            rest = after;
+        } else if let Some(braced) = rest.strip_prefix('{') {
+            let end = braced.find('}').ok_or_else(|| value.to_string())?;
+            let expression = &braced[..end];
+            rest = &braced[end + 1..];
+            let name_len = expression
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                .unwrap_or(expression.len());
+            let (name, modifier) = expression.split_at(name_len);
+            let (colon, modifier) = match modifier.strip_prefix(':') {
+                Some(modifier) => (true, modifier),
+                None => (false, modifier),
+            };
+            let set = lookup(name).filter(|found| !colon || !found.is_empty());
+            let mut operator = modifier.chars();
+            let resolved = match operator.next() {
+                None if !colon => set.ok_or_else(|| name.to_string())?,
+                Some('-') => set.unwrap_or(operator.as_str()),
+                Some('+') => set.map_or("", |_| operator.as_str()),
+                Some('?') => set.ok_or_else(|| name.to_string())?,
+                _ => return Err(name.to_string()),
+            };
+            output.push_str(resolved);
+        } else {
+            let name_len = rest
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                .unwrap_or(rest.len());
+            if name_len == 0 {
+                output.push('$');
+                continue;
+            }
+            output.push_str(lookup(&rest[..name_len]).ok_or_else(|| rest[..name_len].to_string())?);
+            rest = &rest[name_len..];
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn resolved_env(image: &ImageEntry, vars: &[EnvVar], warnings: &mut Vec<String>) -> Vec<EnvVar> {
+    image
+        .env_vars
+        .iter()
+        .map(|env| {
+            if !env.value.contains('$') {
+                return env.clone();
+            }
+            match interpolate(&env.value, vars) {
+                Ok(value) => {
+                    if secrets::is_secret_key(&env.key) {
+                        warnings.push(format!(
+                            "{}: {} is resolved from .env and exported as a literal value",
+                            image.service_name, env.key
+                        ));
+                    }
+                    EnvVar {
+                        key: env.key.clone(),
+                        value,
+                    }
+                }
+                Err(variable) => {
+                    warnings.push(format!(
+                        "{}: {} references {variable}, which is not set in .env; exported as written",
+                        image.service_name, env.key
+                    ));
+                    env.clone()
+                }
+            }
+        })
+        .collect()
+}
+
+fn container_name(project: &str, image: &ImageEntry) -> String {
+    image
+        .options
+        .container_name
+        .clone()
+        .unwrap_or_else(|| format!("{project}-{}", resource_name(&image.service_name)))
+}
+
+fn shared_network(project: &str, images: &[ImageEntry], mode: &str) -> String {
+    match mode.strip_prefix("service:") {
+        Some(service) => {
+            let name = images
+                .iter()
+                .find(|image| image.service_name == service)
+                .map(|image| container_name(project, image))
+                .unwrap_or_else(|| format!("{project}-{}", resource_name(service)));
+            format!("container:{name}")
+        }
+        None => mode.to_string(),
+    }
+}
+
+fn volume_name<'a>(app: &'a App, source: &'a str) -> &'a str {
+    app.volumes
+        .iter()
+        .find(|volume| volume.name == source)
+        .and_then(|volume| volume.volume_name.as_deref())
+        .unwrap_or(source)
+}
+
+fn docker_mount_args(app: &App, mount: &VolumeMount) -> [String; 2] {
+    let options = &mount.options;
+    let kind = mount.kind();
+    let source = if mount.is_named_volume() {
+        volume_name(app, &mount.source)
+    } else {
+        mount.source.as_str()
+    };
+    if kind == MountType::Tmpfs && !mount.options.read_only {
+        let path = mount.container_path();
+        let value = match options.tmpfs_size.as_ref() {
+            Some(size) => format!("{path}:size={size}"),
+            None => path.to_string(),
+        };
+        return ["--tmpfs".to_string(), value];
+    }
+    if !mount.requires_long_syntax() {
+        return [
+            "-v".to_string(),
+            format!("{source}:{}", mount.short_target()),
+        ];
+    }
+
+    let mut fields = vec![format!("type={}", kind.label())];
+    if !source.is_empty() && kind != MountType::Tmpfs {
+        fields.push(format!("source={source}"));
+    }
+    fields.push(format!("target={}", mount.container_path()));
+    if mount.options.read_only {
+        fields.push("readonly".to_string());
+    }
+    if let Some(propagation) = options.propagation.as_ref() {
+        fields.push(format!("bind-propagation={propagation}"));
+    }
+    if options.nocopy {
+        fields.push("volume-nocopy".to_string());
+    }
+    if let Some(subpath) = options.subpath.as_ref() {
+        fields.push(format!("volume-subpath={subpath}"));
+    }
+    if let Some(size) = options.tmpfs_size.as_ref() {
+        fields.push(format!("tmpfs-size={size}"));
+    }
+    ["--mount".to_string(), fields.join(",")]
+}
+
+fn split_port(mapping: &str) -> Option<(String, String)> {
+    if mapping.trim().is_empty() {
+        return None;
+    }
+    let mapping = mapping.split('/').next().unwrap_or(mapping);
+    let mut parts: Vec<&str> = mapping.split(':').collect();
+    let container = parts.pop()?.to_string();
+    let host = parts
+        .pop()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| container.clone());
+    Some((host, container))
+}
+
+fn continued_command(args: &[String], head: usize, tail: &[String]) -> String {
+    let quote = |words: &[String]| {
+        words
+            .iter()
+            .map(|word| shell_quote(word))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut lines = vec![quote(&args[..head.min(args.len())])];
+    for word in args.iter().skip(head) {
+        let continues = lines.len() > 1 && !word.starts_with('-');
+        match lines.last_mut() {
+            Some(line) if continues => {
+                line.push(' ');
+                line.push_str(&shell_quote(word));
+            }
+            _ => lines.push(shell_quote(word)),
+        }
+    }
+    lines.push(quote(tail));
+    lines.join(" \\\n  ")
+}
+
+fn image_name(project: &str, image: &ImageEntry) -> String {
+    image
+        .image_ref()
+        .unwrap_or_else(|| format!("{project}-{}:latest", resource_name(&image.service_name)))
+}
+
+fn common_warnings(image: &ImageEntry, format: ExportFormat, warnings: &mut Vec<String>) {
+    let name = &image.service_name;
+    if !image.options.profiles.is_empty() {
+        warnings.push(format!(
+            "{name}: profiles are ignored, the service is always exported"
+        ));
+    }
+    if !image.deploy.update_config.is_empty() || !image.deploy.restart_policy.is_empty() {
+        warnings.push(format!(
+            "{name}: deploy.update_config and deploy.restart_policy are not exported"
+        ));
+    }
+    if format != ExportFormat::Kubernetes
+        && image.deploy.replicas.is_some_and(|replicas| replicas > 1)
+    {
+        warnings.push(format!(
+            "{name}: replicas are not supported, a single container is exported"
+        ));
+    }
+    if format == ExportFormat::Kubernetes {
+        for file in &image.env_files {
+            warnings.push(format!(
+                "{name}: env_file {} is not exported, create a ConfigMap with kubectl create configmap --from-env-file={}",
+                file.path, file.path
+            ));
+        }
+    }
+    for env in &image.env_vars {
+        if secrets::is_secret_key(&env.key)
+            && !env.value.is_empty()
+            && !secrets::is_interpolated(&env.value)
+        {
+            warnings.push(format!(
+                "{name}: {} is exported as a literal value",
+                env.key
+            ));
+        }
+    }
+}
+
+fn export_docker_run(app: &App, project: &str, images: &[ImageEntry]) -> Export {
+    let mut warnings = Vec::new();
+    let vars = interpolation_vars(app);
+    let network = format!("{project}_default");
+    let mut script = format!(
+        "#!/bin/sh\n# Generated by acdc from {}\nset -e\n\ndocker network create {network} 2>/dev/null || true\n",
+        app.compose_file
+    );
+    for volume in &app.volumes {
+        let name = volume.volume_name.as_deref().unwrap_or(&volume.name);
+        if volume.external {
+            warnings.push(format!("volume {name} is external and must already exist"));
+            continue;
+        }
+        let mut args = vec![
+            "docker".to_string(),
+            "volume".to_string(),
+            "create".to_string(),
+        ];
+        if let Some(driver) = volume.driver.as_ref() {
+            args.extend(["--driver".to_string(), driver.clone()]);
+        }
+        for opt in &volume.driver_opts {
+            args.extend(["--opt".to_string(), format!("{}={}", opt.key, opt.value)]);
+        }
+        for label in &volume.labels {
+            args.extend([
+                "--label".to_string(),
+                format!("{}={}", label.key, label.value),
+            ]);
+        }
+        script.push_str(&format!(
+            "{}\n",
+            continued_command(&args, 3, &[name.to_string()])
+        ));
+    }
+
+    let (ordered, cycle) = app::dependency_order(images);
+    warnings.extend(cycle);
+    for image in ordered {
+        common_warnings(image, ExportFormat::DockerRun, &mut warnings);
+        let name = &image.service_name;
+        let reference = image_name(project, image);
+        script.push('\n');
+
+        if let Some(build) = image.build.as_ref() {
+            let mut args = vec![
+                "docker".to_string(),
+                "build".to_string(),
+                "-t".to_string(),
+                reference.clone(),
+            ];
+            if let Some(dockerfile) = build.dockerfile.as_ref() {
+                let path = Path::new(&build.context).join(dockerfile);
+                args.extend(["-f".to_string(), path.to_string_lossy().to_string()]);
+            }
+            if let Some(target) = build.target.as_ref() {
+                args.extend(["--target".to_string(), target.clone()]);
+            }
+            for arg in &build.args {
+                args.extend([
+                    "--build-arg".to_string(),
+                    format!("{}={}", arg.key, arg.value),
+                ]);
+            }
+            script.push_str(&format!(
+                "{}\n",
+                continued_command(&args, 4, std::slice::from_ref(&build.context))
+            ));
+        }
+
+        let mut args: Vec<String> = vec!["docker", "run", "-d", "--name"]
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+        args.push(container_name(project, image));
+        match image.options.network_mode.as_ref() {
+            Some(mode) => args.extend([
+                "--network".to_string(),
+                shared_network(project, images, mode),
+            ]),
+            None => args.extend([
+                "--network".to_string(),
+                network.clone(),
+                "--network-alias".to_string(),
+                name.clone(),
+            ]),
+        }
+        if !image.port_mapping.is_empty() {
+            args.extend(["-p".to_string(), image.port_mapping.clone()]);
+        }
+        for mount in &image.mounts {
+            args.extend(docker_mount_args(app, mount));
+        }
+        for secret in &image.secrets {
+            match app.secrets.iter().find(|entry| &entry.name == secret) {
+                Some(entry) => args.extend([
+                    "-v".to_string(),
+                    format!("{}:/run/secrets/{secret}:ro", entry.file),
+                ]),
+                None => warnings.push(format!(
+                    "{name}: secret {secret} has no file and is skipped"
+                )),
+            }
+        }
+        for file in &image.env_files {
+            if !file.required && !Path::new(&file.path).is_file() {
+                warnings.push(format!(
+                    "{name}: optional env_file {} is missing and skipped",
+                    file.path
+                ));
+                continue;
+            }
+            args.extend(["--env-file".to_string(), file.path.clone()]);
+        }
+        for env in resolved_env(image, &vars, &mut warnings) {
+            args.extend(["-e".to_string(), format!("{}={}", env.key, env.value)]);
+        }
+
+        let options = &image.options;
+        if let Some(restart) = options.restart.as_ref() {
+            args.extend([
+                "--restart".to_string(),
+                restart.as_compose().trim_matches('"').to_string(),
+            ]);
+        }
+        let mut tail = vec![reference];
+        if let Some(entrypoint) = options.entrypoint.as_ref() {
+            let mut words = entrypoint.words().into_iter();
+            if let Some(program) = words.next() {
+                args.extend(["--entrypoint".to_string(), program]);
+            }
+            tail.extend(words);
+        }
+        for (flag, value) in [
+            ("--user", &options.user),
+            ("--workdir", &options.working_dir),
+            ("--hostname", &options.hostname),
+            ("--cpus", &image.deploy.limits.cpus),
+            ("--memory", &image.deploy.limits.memory),
+            ("--memory-reservation", &image.deploy.reservations.memory),
+        ] {
+            if let Some(value) = value {
+                args.extend([flag.to_string(), value.clone()]);
+            }
+        }
+        if let Some(pids) = image.deploy.limits.pids {
+            args.extend(["--pids-limit".to_string(), pids.to_string()]);
+        }
+        if image.deploy.reservations.cpus.is_some() {
+            warnings.push(format!("{name}: cpu reservations have no docker run flag"));
+        }
+        if let Some(period) = options.stop_grace_period.as_ref() {
+            match duration_seconds(period) {
+                Some(seconds) => args.extend(["--stop-timeout".to_string(), seconds.to_string()]),
+                None => warnings.push(format!(
+                    "{name}: stop_grace_period {period} is not exported"
+                )),
+            }
+        }
+        for (flag, enabled) in [
+            ("-t", options.tty),
+            ("-i", options.stdin_open),
+            ("--init", options.init),
+            ("--read-only", options.read_only),
+        ] {
+            if enabled {
+                args.push(flag.to_string());
+            }
+        }
+        for (flag, items) in [
+            ("--add-host", &options.extra_hosts),
+            ("--dns", &options.dns),
+            ("--cap-add", &options.cap_add),
+            ("--cap-drop", &options.cap_drop),
+            ("--security-opt", &options.security_opt),
+        ] {
+            for item in items {
+                args.extend([flag.to_string(), item.clone()]);
+            }
+        }
+        for (flag, pairs) in [("--label", &options.labels), ("--sysctl", &options.sysctls)] {
+            for pair in pairs {
+                args.extend([flag.to_string(), format!("{}={}", pair.key, pair.value)]);
+            }
+        }
+        for ulimit in &options.ulimits {
+            let value = match ulimit.hard {
+                Some(hard) => format!("{}={}:{hard}", ulimit.name, ulimit.soft),
+                None => format!("{}={}", ulimit.name, ulimit.soft),
+            };
+            args.extend(["--ulimit".to_string(), value]);
+        }
+        if let Some(logging) = options.logging.as_ref() {
+            args.extend(["--log-driver".to_string(), logging.driver.clone()]);
+            for pair in &logging.options {
+                args.extend([
+                    "--log-opt".to_string(),
+                    format!("{}={}", pair.key, pair.value),
+                ]);
+            }
+        }
+        if let Some(healthcheck) = image.healthcheck.as_ref() {
+            if healthcheck.disable {
+                args.push("--no-healthcheck".to_string());
+            } else {
+                args.extend(["--health-cmd".to_string(), healthcheck.test.clone()]);
+                for (flag, value) in [
+                    ("--health-interval", &healthcheck.interval),
+                    ("--health-timeout", &healthcheck.timeout),
+                    ("--health-start-period", &healthcheck.start_period),
+                ] {
+                    if let Some(value) = value {
+                        args.extend([flag.to_string(), value.clone()]);
+                    }
+                }
+                if let Some(retries) = healthcheck.retries {
+                    args.extend(["--health-retries".to_string(), retries.to_string()]);
+                }
+            }
+        }
+        if !image.depends_on.is_empty() {
+            warnings.push(format!(
+                "{name}: depends_on only orders the script, health conditions are not awaited"
+            ));
+        }
+
+        if let Some(command) = image.command.as_ref() {
+            tail.extend(command.words());
+        }
+        script.push_str(&format!("{}\n", continued_command(&args, 3, &tail)));
+    }
+
+    Export {
+        files: vec![ExportFile {
+            path: "run.sh".to_string(),
+            contents: script,
+        }],
+        warnings,
+    }
+}
+
+fn yaml_list(items: &[String]) -> String {
+    let quoted: Vec<String> = items.iter().map(|item| json_quote(item)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+fn kubernetes_probe(image: &ImageEntry, output: &mut String) {
+    let Some(healthcheck) = image
+        .healthcheck
+        .as_ref()
+        .filter(|healthcheck| !healthcheck.disable)
+    else {
+        return;
+    };
+    let command = match healthcheck.form {
+        HealthcheckForm::Cmd => healthcheck::test_args(healthcheck),
+        HealthcheckForm::CmdShell => {
+            vec!["sh".to_string(), "-c".to_string(), healthcheck.test.clone()]
+        }
+    };
+    output.push_str(&format!(
+        "          readinessProbe:\n            exec:\n              command: {}\n",
+        yaml_list(&command)
+    ));
+    for (key, value) in [
+        ("periodSeconds", &healthcheck.interval),
+        ("timeoutSeconds", &healthcheck.timeout),
+        ("initialDelaySeconds", &healthcheck.start_period),
+    ] {
+        if let Some(seconds) = value.as_deref().and_then(duration_seconds) {
+            output.push_str(&format!("            {key}: {}\n", seconds.max(1)));
+        }
+    }
+    if let Some(retries) = healthcheck.retries {
+        output.push_str(&format!("            failureThreshold: {retries}\n"));
+    }
+}
+
+fn kubernetes_deployment(
+    app: &App,
+    project: &str,
+    image: &ImageEntry,
+    vars: &[EnvVar],
+    warnings: &mut Vec<String>,
+) -> String {
+    let name = resource_name(&image.service_name);
+    let options = &image.options;
+    let mut output = format!(
+        "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: {name}\n  labels:\n    app.kubernetes.io/name: {name}\n    app.kubernetes.io/part-of: {project}\n"
+    );
+    if !options.labels.is_empty() {
+        output.push_str("  annotations:\n");
+        for pair in &options.labels {
+            output.push_str(&format!(
+                "    {}: {}\n",
+                json_quote(&pair.key),
+                json_quote(&pair.value)
+            ));
+        }
+    }
+    output.push_str(&format!(
+        "spec:\n  replicas: {}\n  selector:\n    matchLabels:\n      app.kubernetes.io/name: {name}\n  template:\n    metadata:\n      labels:\n        app.kubernetes.io/name: {name}\n    spec:\n",
+        image.deploy.replicas.unwrap_or(1)
+    ));
+    if let Some(hostname) = options.hostname.as_ref() {
+        output.push_str(&format!("      hostname: {hostname}\n"));
+    }
+    if !options.extra_hosts.is_empty() {
+        output.push_str("      hostAliases:\n");
+        for host in &options.extra_hosts {
+            if let Some((hostname, ip)) = host.split_once(':') {
+                output.push_str(&format!(
+                    "        - ip: {}\n          hostnames: [{}]\n",
+                    json_quote(ip),
+                    json_quote(hostname)
+                ));
+            }
+        }
+    }
+    if let Some(period) = options
+        .stop_grace_period
+        .as_deref()
+        .and_then(duration_seconds)
+    {
+        output.push_str(&format!("      terminationGracePeriodSeconds: {period}\n"));
+    }
+    if matches!(
+        options.restart,
+        Some(RestartPolicy::No) | Some(RestartPolicy::OnFailure(_))
+    ) {
+        warnings.push(format!(
+            "{}: Deployments always restart, restart policy ignored",
+            image.service_name
+        ));
+    }
+
+    output.push_str(&format!(
+        "      containers:\n        - name: {name}\n          image: {}\n",
+        image_name(project, image)
+    ));
+    if let Some(entrypoint) = options.entrypoint.as_ref() {
+        output.push_str(&format!(
+            "          command: {}\n",
+            yaml_list(&entrypoint.words())
+        ));
+    }
+    if let Some(command) = image.command.as_ref() {
+        output.push_str(&format!(
+            "          args: {}\n",
+            yaml_list(&command.words())
+        ));
+    }
+    if let Some(working_dir) = options.working_dir.as_ref() {
+        output.push_str(&format!(
+            "          workingDir: {}\n",
+            json_quote(working_dir)
+        ));
+    }
+    if options.tty {
+        output.push_str("          tty: true\n");
+    }
+    if options.stdin_open {
+        output.push_str("          stdin: true\n");
+    }
+    if let Some((_, container)) = split_port(&image.port_mapping) {
+        output.push_str(&format!(
+            "          ports:\n            - containerPort: {container}\n"
+        ));
+    }
+    if !image.env_vars.is_empty() {
+        output.push_str("          env:\n");
+        for env in resolved_env(image, vars, warnings) {
+            output.push_str(&format!(
+                "            - name: {}\n              value: {}\n",
+                env.key,
+                json_quote(&env.value)
+            ));
+        }
+    }
+
+    let limits = &image.deploy.limits;
+    let reservations = &image.deploy.reservations;
+    let mut resources = String::new();
+    for (key, spec) in [("limits", limits), ("requests", reservations)] {
+        let mut lines = String::new();
+        if let Some(cpus) = spec.cpus.as_ref() {
+            lines.push_str(&format!("              cpu: {}\n", json_quote(cpus)));
+        }
+        if let Some(memory) = spec.memory.as_ref() {
+            lines.push_str(&format!(
+                "              memory: {}\n",
+                kubernetes_memory(memory)
+            ));
+        }
+        if !lines.is_empty() {
+            resources.push_str(&format!("            {key}:\n{lines}"));
+        }
+    }
+    if !resources.is_empty() {
+        output.push_str(&format!("          resources:\n{resources}"));
+    }
+    if limits.pids.is_some() {
+        warnings.push(format!(
+            "{}: pids limits are set per node in Kubernetes",
+            image.service_name
+        ));
+    }
+
+    let run_as_user = options
+        .user
+        .as_deref()
+        .and_then(|user| user.split(':').next())
+        .and_then(|user| user.parse::<u32>().ok());
+    if options.user.is_some() && run_as_user.is_none() {
+        warnings.push(format!(
+            "{}: only numeric users map to runAsUser",
+            image.service_name
+        ));
+    }
+    if run_as_user.is_some()
+        || options.read_only
+        || !options.cap_add.is_empty()
+        || !options.cap_drop.is_empty()
+    {
+        output.push_str("          securityContext:\n");
+        if let Some(user) = run_as_user {
+            output.push_str(&format!("            runAsUser: {user}\n"));
+        }
+        if options.read_only {
+            output.push_str("            readOnlyRootFilesystem: true\n");
+        }
+        if !options.cap_add.is_empty() || !options.cap_drop.is_empty() {
+            output.push_str("            capabilities:\n");
+            for (key, items) in [("add", &options.cap_add), ("drop", &options.cap_drop)] {
+                if !items.is_empty() {
+                    output.push_str(&format!("              {key}: {}\n", yaml_list(items)));
+                }
+            }
+        }
+    }
+    kubernetes_probe(image, &mut output);
+
+    let mut volumes = String::new();
+    let mut mounts = String::new();
+    for (index, mount) in image.mounts.iter().enumerate() {
+        let path = mount.container_path();
+        let volume_name = if mount.kind() == MountType::Tmpfs {
+            let volume_name = format!("{name}-tmpfs-{index}");
+            volumes.push_str(&format!(
+                "        - name: {volume_name}\n          emptyDir:\n            medium: Memory\n"
+            ));
+            if let Some(size) = mount.options.tmpfs_size.as_ref() {
+                volumes.push_str(&format!(
+                    "            sizeLimit: {}\n",
+                    kubernetes_memory(size)
+                ));
+            }
+            volume_name
+        } else if mount.is_named_volume() {
+            let claim = resource_name(&mount.source);
+            volumes.push_str(&format!(
+                "        - name: {claim}\n          persistentVolumeClaim:\n            claimName: {claim}\n"
+            ));
+            claim
+        } else {
+            let volume_name = format!("{name}-bind-{index}");
+            warnings.push(format!(
+                "{}: bind mount {} becomes a hostPath volume",
+                image.service_name, mount.source
+            ));
+            volumes.push_str(&format!(
+                "        - name: {volume_name}\n          hostPath:\n            path: {}\n",
+                json_quote(&mount.source)
+            ));
+            volume_name
+        };
+        mounts.push_str(&format!(
+            "            - name: {volume_name}\n              mountPath: {path}\n"
+        ));
+        if let Some(subpath) = mount.options.subpath.as_ref() {
+            mounts.push_str(&format!("              subPath: {subpath}\n"));
+        }
+        if mount.options.read_only {
+            mounts.push_str("              readOnly: true\n");
+        }
+    }
+    for secret in &image.secrets {
+        let secret_name = resource_name(secret);
+        warnings.push(format!(
+            "{}: create Secret {secret_name} with kubectl create secret generic {secret_name} --from-file={}",
+            image.service_name,
+            app.secrets
+                .iter()
+                .find(|entry| &entry.name == secret)
+                .map(|entry| entry.file.as_str())
+                .unwrap_or("<file>")
+        ));
+        volumes.push_str(&format!(
+            "        - name: secret-{secret_name}\n          secret:\n            secretName: {secret_name}\n"
+        ));
+        mounts.push_str(&format!(
+            "            - name: secret-{secret_name}\n              mountPath: /run/secrets/{secret}\n              subPath: {secret}\n              readOnly: true\n"
+        ));
+    }
+    if !mounts.is_empty() {
+        output.push_str(&format!("          volumeMounts:\n{mounts}"));
+    }
+    if !volumes.is_empty() {
+        output.push_str(&format!("      volumes:\n{volumes}"));
+    }
+    output
+}
+
+fn export_kubernetes(app: &App, project: &str, images: &[ImageEntry]) -> Export {
+    let mut export = Export::default();
+    let vars = interpolation_vars(app);
+    for image in images {
+        let name = resource_name(&image.service_name);
+        common_warnings(image, ExportFormat::Kubernetes, &mut export.warnings);
+        if image.build.is_some() {
+            export.warnings.push(format!(
+                "{}: build and push {} to a registry the cluster can pull from",
+                image.service_name,
+                image_name(project, image)
+            ));
+        }
+        if !image.depends_on.is_empty() {
+            export.warnings.push(format!(
+                "{}: depends_on has no Kubernetes equivalent",
+                image.service_name
+            ));
+        }
+        if image.options.network_mode.is_some() {
+            export.warnings.push(format!(
+                "{}: network_mode is not exported",
+                image.service_name
+            ));
+        }
+        let deployment = kubernetes_deployment(app, project, image, &vars, &mut export.warnings);
+        export.files.push(ExportFile {
+            path: format!("k8s/{name}-deployment.yaml"),
+            contents: deployment,
+        });
+
+        if let Some((host, container)) = split_port(&image.port_mapping) {
+            export.files.push(ExportFile {
+                path: format!("k8s/{name}-service.yaml"),
+                contents: format!(
+                    "apiVersion: v1\nkind: Service\nmetadata:\n  name: {name}\n  labels:\n    app.kubernetes.io/part-of: {project}\nspec:\n  selector:\n    app.kubernetes.io/name: {name}\n  ports:\n    - port: {host}\n      targetPort: {container}\n"
+                ),
+            });
+        }
+    }
+
+    for volume in &app.volumes {
+        let name = resource_name(&volume.name);
+        if volume.external {
+            export.warnings.push(format!(
+                "volume {} is external, create PersistentVolumeClaim {name} yourself",
+                volume.name
+            ));
+            continue;
+        }
+        export.files.push(ExportFile {
+            path: format!("k8s/{name}-persistentvolumeclaim.yaml"),
+            contents: format!(
+                "apiVersion: v1\nkind: PersistentVolumeClaim\nmetadata:\n  name: {name}\n  labels:\n    app.kubernetes.io/part-of: {project}\nspec:\n  accessModes:\n    - ReadWriteOnce\n  resources:\n    requests:\n      storage: {PVC_SIZE}\n"
+            ),
+        });
+    }
+    if !app.volumes.is_empty() {
+        export.warnings.push(format!(
+            "PersistentVolumeClaims request {PVC_SIZE}, adjust as needed"
+        ));
+    }
+    export
+}
+
+fn qualified_image(reference: &str) -> String {
+    let first = reference.split('/').next().unwrap_or_default();
+    if reference.contains('/')
+        && (first.contains('.') || first.contains(':') || first == "localhost")
+    {
+        reference.to_string()
+    } else if reference.contains('/') {
+        format!("docker.io/{reference}")
+    } else {
+        format!("docker.io/library/{reference}")
+    }
+}
+
+fn quadlet_container(
+    app: &App,
+    project: &str,
+    images: &[ImageEntry],
+    image: &ImageEntry,
+    vars: &[EnvVar],
+    warnings: &mut Vec<String>,
+) -> String {
+    let name = &image.service_name;
+    let options = &image.options;
+    let mut output = format!("[Unit]\nDescription={name} ({project})\n");
+    for dependency in &image.depends_on {
+        output.push_str(&format!(
+            "Requires={dependency}.service\nAfter={dependency}.service\n"
+        ));
+    }
+
+    let reference = match image.image_ref() {
+        Some(reference) => qualified_image(&reference),
+        None => {
+            warnings.push(format!(
+                "{name}: build the image first, Quadlet cannot build it"
+            ));
+            format!("localhost/{}", image_name(project, image))
+        }
+    };
+    output.push_str(&format!(
+        "\n[Container]\nContainerName={}\nImage={reference}\n",
+        container_name(project, image)
+    ));
+    match options.network_mode.as_deref() {
+        Some("host") => output.push_str("Network=host\n"),
+        Some("none") => output.push_str("Network=none\n"),
+        Some(mode) if mode.starts_with("service:") || mode.starts_with("container:") => {
+            output.push_str(&format!(
+                "Network={}\n",
+                shared_network(project, images, mode)
+            ));
+        }
+        Some(mode) => warnings.push(format!("{name}: network_mode {mode} is not exported")),
+        None => output.push_str(&format!("Network={project}.network\nNetworkAlias={name}\n")),
+    }
+    if !image.port_mapping.is_empty() {
+        output.push_str(&format!("PublishPort={}\n", image.port_mapping));
+    }
+    for mount in &image.mounts {
+        if mount.kind() == MountType::Tmpfs {
+            let mut value = mount.container_path().to_string();
+            if let Some(size) = mount.options.tmpfs_size.as_ref() {
+                value.push_str(&format!(":size={size}"));
+            }
+            output.push_str(&format!("Tmpfs={value}\n"));
+        } else if mount.is_named_volume() {
+            match app
+                .volumes
+                .iter()
+                .find(|volume| volume.name == mount.source)
+            {
+                Some(volume) if volume.external => output.push_str(&format!(
+                    "Volume={}:{}\n",
+                    volume_name(app, &mount.source),
+                    mount.short_target()
+                )),
+                _ => output.push_str(&format!(
+                    "Volume={}.volume:{}\n",
+                    mount.source,
+                    mount.short_target()
+                )),
+            }
+        } else {
+            warnings.push(format!(
+                "{name}: bind mount {} is resolved relative to the unit file",
+                mount.source
+            ));
+            output.push_str(&format!(
+                "Volume={}:{}\n",
+                mount.source,
+                mount.short_target()
+            ));
+        }
+    }
+    for secret in &image.secrets {
+        warnings.push(format!(
+            "{name}: create podman secret {secret} before starting"
+        ));
+        output.push_str(&format!("Secret={secret}\n"));
+    }
+    for file in &image.env_files {
+        output.push_str(&format!("EnvironmentFile={}\n", file.path));
+    }
+    for env in resolved_env(image, vars, warnings) {
+        output.push_str(&format!(
+            "Environment={}\n",
+            shell_quote(&format!("{}={}", env.key, env.value))
+        ));
+    }
+    if let Some(entrypoint) = options.entrypoint.as_ref() {
+        output.push_str(&format!("Entrypoint={}\n", yaml_list(&entrypoint.words())));
+    }
+    for (key, value) in [
+        ("User", &options.user),
+        ("WorkingDir", &options.working_dir),
+        ("HostName", &options.hostname),
+        (
+            "StopTimeout",
+            &options
+                .stop_grace_period
+                .as_deref()
+                .and_then(duration_seconds)
+                .map(|seconds| seconds.to_string()),
+        ),
+        (
+            "PidsLimit",
+            &image.deploy.limits.pids.map(|pids| pids.to_string()),
+        ),
+    ] {
+        if let Some(value) = value {
+            output.push_str(&format!("{key}={value}\n"));
+        }
+    }
+    if options.read_only {
+        output.push_str("ReadOnly=true\n");
+    }
+    if options.init {
+        output.push_str("RunInit=true\n");
+    }
+    for (key, items) in [
+        ("AddHost", &options.extra_hosts),
+        ("DNS", &options.dns),
+        ("AddCapability", &options.cap_add),
+        ("DropCapability", &options.cap_drop),
+    ] {
+        for item in items {
+            output.push_str(&format!("{key}={item}\n"));
+        }
+    }
+    for (key, pairs) in [("Label", &options.labels), ("Sysctl", &options.sysctls)] {
+        for pair in pairs {
+            output.push_str(&format!(
+                "{key}={}\n",
+                shell_quote(&format!("{}={}", pair.key, pair.value))
+            ));
+        }
+    }
+    for ulimit in &options.ulimits {
+        match ulimit.hard {
+            Some(hard) => {
+                output.push_str(&format!("Ulimit={}={}:{hard}\n", ulimit.name, ulimit.soft))
+            }
+            None => output.push_str(&format!("Ulimit={}={}\n", ulimit.name, ulimit.soft)),
+        }
+    }
+    if let Some(logging) = options.logging.as_ref() {
+        output.push_str(&format!("LogDriver={}\n", logging.driver));
+    }
+    if let Some(healthcheck) = image.healthcheck.as_ref() {
+        if healthcheck.disable {
+            output.push_str("HealthCmd=none\n");
+        } else {
+            output.push_str(&format!("HealthCmd={}\n", healthcheck.test));
+            for (key, value) in [
+                ("HealthInterval", &healthcheck.interval),
+                ("HealthTimeout", &healthcheck.timeout),
+                ("HealthStartPeriod", &healthcheck.start_period),
+            ] {
+                if let Some(value) = value {
+                    output.push_str(&format!("{key}={value}\n"));
+                }
+            }
+            if let Some(retries) = healthcheck.retries {
+                output.push_str(&format!("HealthRetries={retries}\n"));
+            }
+        }
+    }
+    let limits = &image.deploy.limits;
+    let mut podman_args = Vec::new();
+    if let Some(cpus) = limits.cpus.as_ref() {
+        podman_args.push(format!("--cpus={cpus}"));
+    }
+    if let Some(memory) = limits.memory.as_ref() {
+        podman_args.push(format!("--memory={memory}"));
+    }
+    if options.tty {
+        podman_args.push("--tty".to_string());
+    }
+    if options.stdin_open {
+        podman_args.push("--interactive".to_string());
+    }
+    if !podman_args.is_empty() {
+        output.push_str(&format!("PodmanArgs={}\n", podman_args.join(" ")));
+    }
+    if let Some(command) = image.command.as_ref() {
+        output.push_str(&format!("Exec={}\n", shell_join(&command.words())));
+    }
+    if image.deploy.reservations.cpus.is_some() || image.deploy.reservations.memory.is_some() {
+        warnings.push(format!("{name}: resource reservations are not exported"));
+    }
+    let restart = match options.restart.as_ref() {
+        Some(RestartPolicy::No) => "no",
+        Some(RestartPolicy::OnFailure(_)) => "on-failure",
+        Some(RestartPolicy::Always) | Some(RestartPolicy::UnlessStopped) => "always",
+        None => "no",
+    };
+    output.push_str(&format!(
+        "\n[Service]\nRestart={restart}\n\n[Install]\nWantedBy=default.target\n"
+    ));
+    output
+}
+
+fn quadlet_volume(volume: &VolumeEntry) -> String {
+    let mut output = format!(
+        "[Volume]\nVolumeName={}\n",
+        volume.volume_name.as_deref().unwrap_or(&volume.name)
+    );
+    if let Some(driver) = volume.driver.as_ref() {
+        output.push_str(&format!("Driver={driver}\n"));
+    }
+    for opt in &volume.driver_opts {
+        match opt.key.as_str() {
+            "type" => output.push_str(&format!("Type={}\n", opt.value)),
+            "device" => output.push_str(&format!("Device={}\n", opt.value)),
+            "o" => output.push_str(&format!("Options={}\n", opt.value)),
+            _ => output.push_str(&format!("PodmanArgs=--opt {}={}\n", opt.key, opt.value)),
+        }
+    }
+    for label in &volume.labels {
+        output.push_str(&format!("Label={}={}\n", label.key, label.value));
+    }
+    output
+}
+
+fn export_quadlet(app: &App, project: &str, images: &[ImageEntry]) -> Export {
+    let mut export = Export::default();
+    let vars = interpolation_vars(app);
+    export.files.push(ExportFile {
+        path: format!("quadlet/{project}.network"),
+        contents: format!("[Network]\nNetworkName={project}_default\n"),
+    });
+    for volume in app.volumes.iter().filter(|volume| !volume.external) {
+        export.files.push(ExportFile {
+            path: format!("quadlet/{}.volume", volume.name),
+            contents: quadlet_volume(volume),
+        });
+    }
+    for image in images {
+        common_warnings(image, ExportFormat::Quadlet, &mut export.warnings);
+        let contents = quadlet_container(app, project, images, image, &vars, &mut export.warnings);
+        export.files.push(ExportFile {
+            path: format!("quadlet/{}.container", image.service_name),
+            contents,
+        });
+    }
+    export.warnings.push(
+        "copy the units to ~/.config/containers/systemd/ and run systemctl --user daemon-reload"
+            .to_string(),
+    );
+    export
+}
+
+pub fn export(app: &App, format: ExportFormat) -> Export {
+    let project = resource_name(app.compose_name.as_deref().unwrap_or(&app.project_name));
+    let images = app.effective_images();
+    match format {
+        ExportFormat::DockerRun => export_docker_run(app, &project, &images),
+        ExportFormat::Kubernetes => export_kubernetes(app, &project, &images),
+        ExportFormat::Quadlet => export_quadlet(app, &project, &images),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::{app::Healthcheck, build::BuildConfig};
+
+    fn service(name: &str) -> ImageEntry {
+        ImageEntry {
+            service_name: name.to_string(),
+            namespace: "library".to_string(),
+            repo: name.to_string(),
+            tag: "latest".to_string(),
+            ..ImageEntry::default()
+        }
+    }
+
+    fn project(name: &str, dotenv: &str, images: Vec<ImageEntry>) -> (App, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("acdc-export-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".env"), dotenv).unwrap();
+        let mut app = App::new();
+        app.project_name = "shop".to_string();
+        app.compose_file = dir.join("compose.yaml").to_string_lossy().to_string();
+        app.images = images;
+        (app, dir)
+    }
+
+    fn file<'a>(export: &'a Export, path: &str) -> &'a str {
+        &export
+            .files
+            .iter()
+            .find(|file| file.path == path)
+            .unwrap()
+            .contents
+    }
+
+    #[test]
+    fn duration_seconds_sums_units() {
+        assert_eq!(duration_seconds("1m30s"), Some(90));
+        assert_eq!(duration_seconds("2h"), Some(7200));
+        assert_eq!(duration_seconds("500ms"), Some(0));
+        assert_eq!(duration_seconds("10x"), None);
+    }
+
+    #[test]
+    fn kubernetes_memory_uses_binary_suffixes() {
+        assert_eq!(kubernetes_memory("512m"), "512Mi");
+        assert_eq!(kubernetes_memory("1.5GB"), "1.5Gi");
+        assert_eq!(kubernetes_memory("2t"), "2Ti");
+        assert_eq!(kubernetes_memory("256Ki"), "256Ki");
+        assert_eq!(kubernetes_memory("1048576"), "1048576");
+    }
+
+    #[test]
+    fn interpolate_follows_compose_modifiers() {
+        let vars = [EnvVar {
+            key: "USER".to_string(),
+            value: "app".to_string(),
+        }];
+        assert_eq!(
+            interpolate("${USER}:$USER", &vars).as_deref(),
+            Ok("app:app")
+        );
+        assert_eq!(
+            interpolate("${MISSING:-guest}", &vars).as_deref(),
+            Ok("guest")
+        );
+        assert_eq!(interpolate("${USER:+set}", &vars).as_deref(), Ok("set"));
+        assert_eq!(interpolate("cost $$5", &vars).as_deref(), Ok("cost $5"));
+        assert_eq!(interpolate("${MISSING}", &vars), Err("MISSING".to_string()));
+        assert_eq!(
+            interpolate("${MISSING:?required}", &vars),
+            Err("MISSING".to_string())
+        );
+    }
+
+    #[test]
+    fn docker_run_resolves_build_files_networks_and_env() {
+        let mut db = service("db");
+        db.options.container_name = Some("shop-database".to_string());
+        let mut sidecar = service("sidecar");
+        sidecar.options.network_mode = Some("service:db".to_string());
+        let mut web = service("web");
+        web.build = Some(BuildConfig {
+            context: "./web".to_string(),
+            dockerfile: Some("Dockerfile.prod".to_string()),
+            target: None,
+            args: Vec::new(),
+            cache_from: Vec::new(),
+        });
+        web.env_vars = vec![
+            EnvVar {
+                key: "DB_PASSWORD".to_string(),
+                value: "${WEB_DB_PASSWORD}".to_string(),
+            },
+            EnvVar {
+                key: "API_URL".to_string(),
+                value: "${API_URL}".to_string(),
+            },
+        ];
+        let (app, dir) = project(
+            "docker-run",
+            "WEB_DB_PASSWORD=s3cret\n",
+            vec![db, sidecar, web],
+        );
+        let export = export(&app, ExportFormat::DockerRun);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let script = file(&export, "run.sh");
+        assert!(script.contains("-f ./web/Dockerfile.prod"), "{script}");
+        assert!(
+            script.contains("--network container:shop-database"),
+            "{script}"
+        );
+        assert!(script.contains("-e DB_PASSWORD=s3cret"), "{script}");
+        assert!(
+            export
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("API_URL references API_URL"))
+        );
+        assert!(
+            export
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("DB_PASSWORD is resolved from .env"))
+        );
+    }
+
+    #[test]
+    fn quadlet_joins_service_networks() {
+        let mut sidecar = service("sidecar");
+        sidecar.options.network_mode = Some("service:db".to_string());
+        let (app, dir) = project("quadlet", "", vec![service("db"), sidecar]);
+        let export = export(&app, ExportFormat::Quadlet);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(file(&export, "quadlet/sidecar.container").contains("Network=container:shop-db\n"));
+    }
+
+    #[test]
+    fn quadlet_volumes_keep_mount_flags() {
+        let mut web = service("web");
+        web.mounts = vec![
+            VolumeMount::new("./config", "/etc/nginx:ro,z"),
+            VolumeMount::new("cache", "/var/cache/nginx:ro"),
+        ];
+        let (app, dir) = project("quadlet-flags", "", vec![web]);
+        let export = export(&app, ExportFormat::Quadlet);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let unit = file(&export, "quadlet/web.container");
+        assert!(unit.contains("Volume=./config:/etc/nginx:ro,z\n"), "{unit}");
+        assert!(
+            unit.contains("Volume=cache.volume:/var/cache/nginx:ro\n"),
+            "{unit}"
+        );
+    }
+
+    #[test]
+    fn kubernetes_probe_keeps_quoted_arguments() {
+        let mut db = service("db");
+        db.healthcheck = Some(Healthcheck {
+            form: HealthcheckForm::Cmd,
+            test: "pg_isready -U 'app user'".to_string(),
+            interval: Some("10s".to_string()),
+            timeout: None,
+            retries: Some(3),
+            start_period: None,
+            disable: false,
+        });
+        let (app, dir) = project("kubernetes", "", vec![db]);
+        let export = export(&app, ExportFormat::Kubernetes);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let deployment = file(&export, "k8s/db-deployment.yaml");
+        assert!(
+            deployment.contains(r#"command: ["pg_isready", "-U", "app user"]"#),
+            "{deployment}"
+        );
+        assert!(deployment.contains("periodSeconds: 10"));
+    }
+}
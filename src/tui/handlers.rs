@@ -1,88 +1,468 @@
-use crossterm::event::KeyCode;
+use std::path::Path;
+
+use crossterm::event::{KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
 
 use crate::api;
 use crate::tui::{
     app::{
-        App, ConfigureField, EnvInputField, EnvVar, FocusArea, ImageEntry, ModalState,
-        MountExistingField, MountInputField, VolumeEntry, VolumeMount,
+        App, BuildField, ConfigureField, EnvFileField, EnvInputField, EnvVar, FocusArea,
+        Healthcheck, HealthcheckField, ImageEntry, ModalState, MountExistingField, MountInputField,
+        OverrideField, SecretEntry, SecretStorage, VolumeEntry, VolumeMount, default_service_name,
     },
+    build::{self, BuildConfig},
+    command::{CommandDraft, CommandField},
+    detail::DetailSection,
+    docker_run,
+    engine::{self, EngineReply, InspectPurpose},
+    env_files::{self, EnvFile, EnvFileMode},
+    environments::{self, Environment, ServiceOverride},
+    export, healthcheck, knowledge,
+    paths::{self, DirBrowser, PathAssist},
+    ports,
+    properties::{self, PropertySheet},
+    scan, secrets,
     tab::{Tab, TabCommand},
+    templates, ui,
+    volumes::{self, MountProperty, VolumeProperty},
 };
 
+const LOG_PAGE: usize = 10;
+
 pub enum LoopControl {
     Continue,
     Exit,
 }
 
-fn default_service_name(repo: &str, current_len: usize) -> String {
-    let base: String = repo
-        .chars()
-        .map(|ch| {
-            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
-                ch
-            } else {
-                '_'
-            }
+fn split_port_mapping(mapping: &str) -> (String, String) {
+    let trimmed = mapping.trim();
+    if let Some((host, container)) = trimmed.split_once(':') {
+        (host.trim().to_string(), container.trim().to_string())
+    } else {
+        (trimmed.to_string(), String::from("80"))
+    }
+}
+
+fn default_mount_target(image: Option<&ImageEntry>) -> String {
+    image
+        .and_then(|image| {
+            knowledge::lookup(&image.namespace, &image.repo).and_then(|entry| {
+                entry
+                    .data_dirs
+                    .iter()
+                    .find(|dir| !image.mounts.iter().any(|mount| &mount.target == *dir))
+                    .cloned()
+            })
         })
-        .collect();
+        .unwrap_or_else(|| "/data".to_string())
+}
 
-    if base.is_empty() {
-        format!("service_{}", current_len + 1)
-    } else {
-        base
+fn suggested_env(image: &ImageEntry) -> (String, String) {
+    let Some(entry) = knowledge::lookup(&image.namespace, &image.repo) else {
+        return (String::new(), String::new());
+    };
+    let is_set = |key: &str| image.env_vars.iter().any(|env| env.key == key);
+
+    if let Some(key) = entry.required_env.iter().find(|key| !is_set(key)) {
+        return (key.clone(), String::new());
+    }
+    entry
+        .recommended_env_pairs()
+        .find(|(key, _)| !is_set(key))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .unwrap_or_default()
+}
+
+fn default_volume_name(app: &App) -> String {
+    format!("volume_{}", app.volumes.len() + 1)
+}
+
+fn suggested_secret_key(image: &ImageEntry) -> String {
+    let literal_secret = image
+        .env_vars
+        .iter()
+        .find(|env| secrets::is_secret_key(&env.key) && !secrets::is_interpolated(&env.value))
+        .map(|env| env.key.clone());
+
+    literal_secret
+        .or_else(|| {
+            knowledge::lookup(&image.namespace, &image.repo).and_then(|entry| {
+                entry
+                    .required_env
+                    .iter()
+                    .find(|key| {
+                        secrets::is_secret_key(key)
+                            && !image.env_vars.iter().any(|env| &env.key == *key)
+                    })
+                    .cloned()
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn upsert_env(env_vars: &mut Vec<EnvVar>, key: &str, value: String) {
+    match env_vars.iter_mut().find(|env| env.key == key) {
+        Some(existing) => existing.value = value,
+        None => env_vars.push(EnvVar {
+            key: key.to_string(),
+            value,
+        }),
+    }
+}
+
+fn is_build_context_input(input: &str) -> bool {
+    input.starts_with('.') || input.starts_with('/') || input.starts_with('~')
+}
+
+fn edit_build_modal(
+    existing_index: Option<usize>,
+    image: Option<&ImageEntry>,
+    context: &str,
+) -> ModalState {
+    let build = image
+        .and_then(|image| image.build.clone())
+        .unwrap_or_else(|| BuildConfig {
+            context: context.to_string(),
+            ..BuildConfig::default()
+        });
+    ModalState::EditBuild {
+        existing_index,
+        context_input: build.context,
+        dockerfile_input: build.dockerfile.unwrap_or_default(),
+        target_input: build.target.unwrap_or_default(),
+        args_input: build
+            .args
+            .iter()
+            .map(|arg| format!("{}={}", arg.key, arg.value))
+            .collect::<Vec<_>>()
+            .join(", "),
+        cache_from_input: build.cache_from.join(", "),
+        image_input: image.and_then(ImageEntry::image_ref).unwrap_or_default(),
+        service_name_input: image
+            .map(|image| image.service_name.clone())
+            .unwrap_or_default(),
+        active_field: BuildField::Context,
+        context_assist: PathAssist::default(),
     }
 }
 
-fn preferred_container_port(ports: &[u16]) -> Option<u16> {
-    let preferred = [80, 443, 8080, 3000, 5000, 5432, 3306, 6379];
-    for candidate in preferred {
-        if ports.contains(&candidate) {
-            return Some(candidate);
+fn handle_path_assist(
+    assist: &mut PathAssist,
+    input: &mut String,
+    base: &Path,
+    key_code: KeyCode,
+) -> bool {
+    if let Some(browser) = assist.browser.as_mut() {
+        match key_code {
+            KeyCode::Up | KeyCode::Char('k') => browser.select(true),
+            KeyCode::Down | KeyCode::Char('j') => browser.select(false),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => browser.enter(),
+            KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => browser.parent(),
+            KeyCode::Char(' ') => {
+                *input = browser.selected_path(base);
+                assist.browser = None;
+            }
+            KeyCode::Tab => assist.browser = None,
+            _ => {}
+        }
+        return true;
+    }
+
+    match key_code {
+        KeyCode::Down => {
+            assist.candidates.clear();
+            assist.browser = Some(DirBrowser::open(base, input));
+            true
+        }
+        KeyCode::Tab => {
+            let completion = paths::complete(base, input);
+            let progressed =
+                completion.input != *input || completion.candidates != assist.candidates;
+            *input = completion.input;
+            assist.candidates = completion.candidates;
+            progressed
+        }
+        _ => {
+            assist.candidates.clear();
+            false
         }
     }
-    ports.first().copied()
 }
 
-fn suggested_port_mapping(app: &App, suggested_container_port: Option<u16>) -> String {
-    let fallback = app.next_port_mapping();
-    let host = fallback
-        .split(':')
-        .next()
-        .unwrap_or("8000")
-        .to_string();
+fn suggested_healthcheck(app: &App, index: usize, logs: &mut Vec<String>) -> Option<Healthcheck> {
+    let image = &app.images[index];
+    if let Some(existing) = image.healthcheck.clone() {
+        return Some(existing);
+    }
+    if let Some(reference) = image.image_ref() {
+        app.engine
+            .inspect(&reference, InspectPurpose::SuggestHealthcheck(index));
+    }
 
-    if let Some(container) = suggested_container_port {
-        format!("{host}:{container}")
-    } else {
-        fallback
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let from_registry = match runtime {
+        Ok(runtime) => match runtime.block_on(api::fetch_docker_hub_healthcheck(
+            &image.namespace,
+            &image.repo,
+            &image.tag,
+        )) {
+            Ok(config) => config.as_ref().and_then(healthcheck::from_image_config),
+            Err(error) => {
+                logs.push(format!("image healthcheck unavailable: {error}"));
+                None
+            }
+        },
+        Err(error) => {
+            logs.push(format!("runtime error for healthcheck: {error}"));
+            None
+        }
+    };
+
+    if from_registry.is_some() {
+        logs.push("healthcheck pre-filled from image config".to_string());
+        return from_registry;
+    }
+
+    let known = knowledge::lookup(&image.namespace, &image.repo)
+        .and_then(|entry| entry.healthcheck.as_deref())
+        .map(healthcheck::from_shell);
+    if known.is_some() {
+        logs.push("healthcheck pre-filled from known image defaults".to_string());
     }
+    known
 }
 
-fn split_port_mapping(mapping: &str) -> (String, String) {
-    let trimmed = mapping.trim();
-    if let Some((host, container)) = trimmed.split_once(':') {
-        (host.trim().to_string(), container.trim().to_string())
-    } else {
-        (trimmed.to_string(), String::from("80"))
+fn registry_exposed_ports(
+    namespace: &str,
+    repo: &str,
+    tag: &str,
+    logs: &mut Vec<String>,
+) -> Vec<u16> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    match runtime {
+        Ok(runtime) => {
+            match runtime.block_on(api::list_docker_hub_exposed_ports(namespace, repo, tag)) {
+                Ok(ports) => ports,
+                Err(error) => {
+                    logs.push(format!("port suggestions unavailable: {error}"));
+                    Vec::new()
+                }
+            }
+        }
+        Err(error) => {
+            logs.push(format!("runtime error for port suggestion: {error}"));
+            Vec::new()
+        }
     }
 }
 
-fn default_mount_target() -> String {
-    "/data".to_string()
+fn configure_new_image_modal(
+    app: &mut App,
+    namespace: &str,
+    repo: &str,
+    tag: &str,
+    logs: &mut Vec<String>,
+) -> ModalState {
+    app.refresh_listening_ports();
+    let image = ImageEntry {
+        namespace: namespace.to_string(),
+        repo: repo.to_string(),
+        tag: tag.to_string(),
+        ..ImageEntry::default()
+    };
+    if let Some(reference) = image.image_ref() {
+        app.engine.inspect(&reference, InspectPurpose::SuggestPorts);
+    }
+    let suggested_ports = registry_exposed_ports(namespace, repo, tag, logs);
+    let suggested_container_port =
+        knowledge::preferred_container_port(knowledge::lookup(namespace, repo), &suggested_ports);
+    let suggested_mapping = app.suggested_port_mapping(suggested_container_port);
+    let (host_port_input, container_port_input) = split_port_mapping(&suggested_mapping);
+    if let Some(port) = suggested_container_port {
+        logs.push(format!("suggested container port {port}"));
+    }
+
+    ModalState::ConfigureImagePorts {
+        existing_index: None,
+        namespace: namespace.to_string(),
+        repo: repo.to_string(),
+        tag: tag.to_string(),
+        host_port_input,
+        container_port_input,
+        service_name_input: default_service_name(repo, app.images.len()),
+        active_field: ConfigureField::HostPort,
+        host_port_typed: false,
+        container_port_typed: false,
+        service_name_typed: false,
+    }
 }
 
-fn default_volume_name(app: &App) -> String {
-    format!("volume_{}", app.volumes.len() + 1)
+fn edit_healthcheck_modal(image_index: usize, current: Healthcheck) -> ModalState {
+    ModalState::EditHealthcheck {
+        image_index,
+        form: current.form,
+        test_input: current.test,
+        interval_input: current.interval.unwrap_or_default(),
+        timeout_input: current.timeout.unwrap_or_default(),
+        retries_input: current
+            .retries
+            .map(|retries| retries.to_string())
+            .unwrap_or_default(),
+        start_period_input: current.start_period.unwrap_or_default(),
+        disable: current.disable,
+        active_field: HealthcheckField::Test,
+        edited: false,
+    }
+}
+
+pub fn handle_engine_reply(app: &mut App, reply: EngineReply) {
+    match reply {
+        EngineReply::LocalImages(Err(error)) => {
+            app.push_log(format!("docker engine unavailable: {error}"));
+        }
+        EngineReply::LocalImages(Ok(images)) => {
+            if let Some(ModalState::AddImageType { local_images, .. }) = app.modal.as_mut() {
+                if !images.is_empty() {
+                    *local_images = images;
+                    let count = local_images.len();
+                    app.push_log(format!("{count} local images available"));
+                }
+            }
+        }
+        EngineReply::Inspected {
+            reference,
+            purpose: InspectPurpose::OfferPull,
+            result: Ok(None),
+        } => {
+            if app.modal.is_none() {
+                app.modal = Some(ModalState::ConfirmPullImage { reference });
+            } else {
+                app.push_log(format!("{reference} is not available locally"));
+            }
+        }
+        EngineReply::Inspected {
+            reference,
+            purpose: InspectPurpose::SuggestPorts,
+            result: Ok(Some(config)),
+        } => {
+            let Some(ModalState::ConfigureImagePorts {
+                existing_index: None,
+                namespace,
+                repo,
+                tag,
+                host_port_typed: false,
+                container_port_typed: false,
+                ..
+            }) = app.modal.as_ref()
+            else {
+                return;
+            };
+            let image = ImageEntry {
+                namespace: namespace.clone(),
+                repo: repo.clone(),
+                tag: tag.clone(),
+                ..ImageEntry::default()
+            };
+            if image.image_ref().as_deref() != Some(reference.as_str())
+                || config.exposed_ports.is_empty()
+            {
+                return;
+            }
+            let suggested_container_port = knowledge::preferred_container_port(
+                knowledge::lookup(&image.namespace, &image.repo),
+                &config.exposed_ports,
+            );
+            let suggested_mapping = app.suggested_port_mapping(suggested_container_port);
+            if let Some(ModalState::ConfigureImagePorts {
+                host_port_input,
+                container_port_input,
+                ..
+            }) = app.modal.as_mut()
+            {
+                (*host_port_input, *container_port_input) = split_port_mapping(&suggested_mapping);
+            }
+            app.push_log("ports read from local image");
+            if !config.volumes.is_empty() {
+                app.push_log(format!(
+                    "image declares volumes {}",
+                    config.volumes.join(", ")
+                ));
+            }
+        }
+        EngineReply::Inspected {
+            reference,
+            purpose: InspectPurpose::SuggestHealthcheck(index),
+            result: Ok(Some(config)),
+        } => {
+            let matches_modal = matches!(
+                app.modal,
+                Some(ModalState::EditHealthcheck {
+                    image_index,
+                    edited: false,
+                    ..
+                }) if image_index == index
+            );
+            let same_image = app
+                .images
+                .get(index)
+                .and_then(ImageEntry::image_ref)
+                .is_some_and(|current| current == reference);
+            if let Some(local) = config
+                .healthcheck
+                .as_ref()
+                .and_then(healthcheck::from_image_config)
+                .filter(|_| matches_modal && same_image)
+            {
+                app.modal = Some(edit_healthcheck_modal(index, local));
+                app.push_log("healthcheck pre-filled from local image");
+            }
+        }
+        EngineReply::Inspected { .. } => {}
+    }
+}
+
+fn open_log_viewer(app: &mut App, service: Option<&str>) {
+    let files = app.compose_run_files();
+    match app.runner.open_logs(&files, service) {
+        Ok(viewer) => {
+            app.push_log(format!("{} (Esc closes)", viewer.title));
+            app.log_viewer = Some(viewer);
+        }
+        Err(error) => app.push_log(format!("logs unavailable: {error}")),
+    }
 }
 
-fn is_shell_command_wrapper(input: &str) -> bool {
-    input.starts_with("sh -c \"") && input.ends_with('"')
+fn handle_log_viewer_key(app: &mut App, key_code: KeyCode) -> LoopControl {
+    let Some(viewer) = app.log_viewer.as_mut() else {
+        return LoopControl::Continue;
+    };
+    match key_code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.log_viewer = None;
+            app.push_log("log viewer closed");
+        }
+        KeyCode::Up | KeyCode::Char('k') => viewer.scroll_up(1),
+        KeyCode::Down | KeyCode::Char('j') => viewer.scroll_down(1),
+        KeyCode::PageUp => viewer.scroll_up(LOG_PAGE),
+        KeyCode::PageDown => viewer.scroll_down(LOG_PAGE),
+        KeyCode::Char('g') | KeyCode::Home => viewer.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End => viewer.follow(),
+        _ => {}
+    }
+    LoopControl::Continue
 }
 
 pub fn handle_key(app: &mut App, key_code: KeyCode) -> LoopControl {
     if app.modal.is_some() {
         return handle_modal_key(app, key_code);
     }
+    if app.log_viewer.is_some() {
+        return handle_log_viewer_key(app, key_code);
+    }
 
     match key_code {
         KeyCode::Char('q') | KeyCode::Esc => LoopControl::Exit,
@@ -132,20 +512,50 @@ pub fn handle_key(app: &mut App, key_code: KeyCode) -> LoopControl {
             }
             LoopControl::Continue
         }
+        KeyCode::Enter
+            if matches!(app.focus, FocusArea::Main)
+                && matches!(app.active_tab, Tab::Images)
+                && !app.images.is_empty() =>
+        {
+            app.modal = Some(ModalState::ServiceDetail {
+                image_index: app.images_selected.min(app.images.len() - 1),
+                section: DetailSection::Ports,
+                selected: 0,
+                marked: Vec::new(),
+                input: None,
+                adding: false,
+            });
+            app.push_log(
+                "service detail: Tab switches section, Enter edits, a adds, Space marks, d deletes",
+            );
+            LoopControl::Continue
+        }
         KeyCode::Char(ch) => {
             if let Some(command) = app.active_tab.command_for_key(ch) {
                 match command {
                     TabCommand::NewImage => {
+                        app.engine.list_images();
                         app.modal = Some(ModalState::AddImageType {
                             input: String::new(),
+                            local_images: Vec::new(),
+                            selected: None,
                         });
                         app.push_log("add image: enter image term");
                         return LoopControl::Continue;
                     }
+                    TabCommand::ImportRun => {
+                        app.modal = Some(ModalState::ImportRun {
+                            input: String::new(),
+                            report: None,
+                        });
+                        app.push_log("import: paste a docker run command");
+                        return LoopControl::Continue;
+                    }
                     TabCommand::EditImage => {
                         if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
                             let index = app.images_selected.min(app.images.len() - 1);
                             if let Some(image) = app.images.get(index).cloned() {
+                                app.refresh_listening_ports();
                                 let (host_port_input, container_port_input) =
                                     split_port_mapping(&image.port_mapping);
                                 app.modal = Some(ModalState::ConfigureImagePorts {
@@ -166,39 +576,84 @@ pub fn handle_key(app: &mut App, key_code: KeyCode) -> LoopControl {
                             }
                         }
                     }
+                    TabCommand::ScanProject => {
+                        let root = std::env::current_dir().unwrap_or_else(|_| ".".into());
+                        let scan = scan::scan_project(&root);
+                        app.push_log(format!("scan: {} proposals", scan.proposals.len()));
+                        app.modal = Some(ModalState::ReviewScan {
+                            proposals: scan.proposals,
+                            notes: scan.notes,
+                            selected: 0,
+                        });
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::EditBuild => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            let modal = edit_build_modal(Some(index), app.images.get(index), ".");
+                            app.modal = Some(modal);
+                            app.push_log("build: set context, Dockerfile, target and args");
+                            return LoopControl::Continue;
+                        }
+                    }
                     TabCommand::SetImageCommand => {
                         if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
                             let index = app.images_selected.min(app.images.len() - 1);
-                            let current = app
-                                .images
-                                .get(index)
-                                .and_then(|image| image.command.clone())
-                                .unwrap_or_else(|| "sh -c \"\"".to_string());
+                            let image = &app.images[index];
                             app.modal = Some(ModalState::SetImageCommand {
                                 image_index: index,
-                                input: current,
+                                field: CommandField::Command,
+                                command: CommandDraft::from_spec(image.command.as_ref()),
+                                entrypoint: CommandDraft::from_spec(
+                                    image.options.entrypoint.as_ref(),
+                                ),
+                                input: String::new(),
                             });
-                            app.push_log("set command: enter container command");
+                            app.push_log("command: add args, Tab switches shell/exec form");
                             return LoopControl::Continue;
                         }
                     }
                     TabCommand::AddImageEnv => {
                         if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
                             let index = app.images_selected.min(app.images.len() - 1);
+                            let (key_input, value_input) = suggested_env(&app.images[index]);
                             app.modal = Some(ModalState::AddImageEnv {
                                 image_index: index,
-                                key_input: String::new(),
-                                value_input: String::new(),
+                                key_input,
+                                value_input,
                                 active_field: EnvInputField::Key,
                             });
                             app.push_log("add env: enter variable and value");
                             return LoopControl::Continue;
                         }
                     }
+                    TabCommand::ImportEnvFile => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            let path_input = if app.project_dir().join(".env").is_file() {
+                                "./.env".to_string()
+                            } else {
+                                "./".to_string()
+                            };
+                            app.modal = Some(ModalState::ImportEnvFile {
+                                image_index: index,
+                                path_input,
+                                mode: EnvFileMode::Import,
+                                required: true,
+                                raw: false,
+                                active_field: EnvFileField::Path,
+                                path_assist: PathAssist::default(),
+                            });
+                            app.push_log("env file: pick a .env file to import or reference");
+                            return LoopControl::Continue;
+                        }
+                    }
                     TabCommand::RemoveImageEnv => {
                         if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
                             let index = app.images_selected.min(app.images.len() - 1);
-                            if app.images[index].env_vars.is_empty() {
+                            if app.images[index].env_vars.is_empty()
+                                && app.images[index].env_files.is_empty()
+                            {
                                 app.push_log("selected image has no env vars");
                             } else {
                                 app.modal = Some(ModalState::RemoveImageEnv {
@@ -210,6 +665,86 @@ pub fn handle_key(app: &mut App, key_code: KeyCode) -> LoopControl {
                             }
                         }
                     }
+                    TabCommand::GenerateSecret => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            app.modal = Some(ModalState::GenerateSecret {
+                                image_index: index,
+                                key_input: suggested_secret_key(&app.images[index]),
+                                storage: SecretStorage::DotEnv,
+                            });
+                            app.push_log("generate secret: pick variable and storage");
+                            return LoopControl::Continue;
+                        }
+                    }
+                    TabCommand::EditServiceProperties => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            app.modal = Some(ModalState::ServiceProperties {
+                                image_index: index,
+                                sheet: PropertySheet::Service,
+                                selected: 0,
+                                input: None,
+                            });
+                            app.push_log("service properties: pick a property to edit");
+                            return LoopControl::Continue;
+                        }
+                    }
+                    TabCommand::EditMounts => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            app.modal = Some(ModalState::MountProperties {
+                                image_index: index,
+                                mount_index: 0,
+                                selected: 0,
+                                input: None,
+                            });
+                            app.push_log("mount options: Tab cycles mounts, t adds a tmpfs mount");
+                            return LoopControl::Continue;
+                        }
+                    }
+                    TabCommand::EditDeploy => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            app.modal = Some(ModalState::ServiceProperties {
+                                image_index: index,
+                                sheet: PropertySheet::Deploy,
+                                selected: 0,
+                                input: None,
+                            });
+                            app.push_log("deploy: set resource limits and replicas");
+                            return LoopControl::Continue;
+                        }
+                    }
+                    TabCommand::EditHealthcheck => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            let mut logs = Vec::new();
+                            let current = suggested_healthcheck(app, index, &mut logs);
+                            for line in logs {
+                                app.push_log(line);
+                            }
+                            let current = current.unwrap_or_else(|| healthcheck::from_shell(""));
+                            app.modal = Some(edit_healthcheck_modal(index, current));
+                            app.push_log("healthcheck: edit test and timings");
+                            return LoopControl::Continue;
+                        }
+                    }
+                    TabCommand::DuplicateService => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            app.refresh_listening_ports();
+                            app.modal = Some(ModalState::DuplicateService {
+                                image_index: index,
+                                name_input: app.duplicate_name(index),
+                                clone_volumes: false,
+                            });
+                            app.push_log(
+                                "duplicate service: set a name, Tab picks share/clone volumes",
+                            );
+                            return LoopControl::Continue;
+                        }
+                    }
                     TabCommand::DeleteImage => {
                         if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
                             let index = app.images_selected.min(app.images.len() - 1);
@@ -251,19 +786,204 @@ pub fn handle_key(app: &mut App, key_code: KeyCode) -> LoopControl {
                         app.push_log("add volume: enter a name");
                         return LoopControl::Continue;
                     }
+                    TabCommand::EditVolume => {
+                        if matches!(app.focus, FocusArea::Main) && !app.volumes.is_empty() {
+                            let index = app.volumes_selected.min(app.volumes.len() - 1);
+                            app.modal = Some(ModalState::VolumeProperties {
+                                volume_index: index,
+                                selected: 0,
+                                input: None,
+                            });
+                            app.push_log("edit volume: pick a property to edit");
+                            return LoopControl::Continue;
+                        }
+                    }
                     TabCommand::DeleteVolume => {
                         if matches!(app.focus, FocusArea::Main) && !app.volumes.is_empty() {
                             let index = app.volumes_selected.min(app.volumes.len() - 1);
-                            let removed = app.volumes.remove(index);
-                            if app.volumes.is_empty() {
-                                app.volumes_selected = 0;
-                            } else if app.volumes_selected >= app.volumes.len() {
-                                app.volumes_selected = app.volumes.len() - 1;
+                            let usage = app.volume_usage(&app.volumes[index].name);
+                            app.modal = Some(ModalState::DeleteVolume {
+                                volume_index: index,
+                                selected_option: 0,
+                            });
+                            app.push_log(format!(
+                                "delete volume {}: {}",
+                                app.volumes[index].name,
+                                volumes::usage_summary(&usage)
+                            ));
+                            return LoopControl::Continue;
+                        }
+                    }
+                    TabCommand::ApplyTemplate => {
+                        let (templates, warnings) = templates::load_templates();
+                        for warning in warnings {
+                            app.push_log(warning);
+                        }
+                        if templates.is_empty() {
+                            app.push_log("no stack templates available");
+                        } else {
+                            app.modal = Some(ModalState::SelectTemplate {
+                                templates,
+                                selected: 0,
+                            });
+                            app.push_log("apply template: pick a stack");
+                        }
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::SwitchEnvironment => {
+                        app.cycle_environment();
+                        let line = match app.active_environment() {
+                            Some(environment) => format!(
+                                "environment: {} ({} + {})",
+                                environment.name,
+                                app.compose_file,
+                                environment.file_name()
+                            ),
+                            None => format!("environment: base ({})", app.compose_file),
+                        };
+                        app.push_log(line);
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::ComposeUp | TabCommand::ComposeDown => {
+                        let files = app.compose_run_files();
+                        if !Path::new(&app.compose_file).exists() {
+                            app.push_log(format!(
+                                "{} not written yet (press p first)",
+                                app.compose_file
+                            ));
+                            return LoopControl::Continue;
+                        }
+                        if command == TabCommand::ComposeDown {
+                            app.modal = Some(ModalState::ConfirmComposeDown);
+                            return LoopControl::Continue;
+                        }
+                        app.runner.up(&files);
+                        let line =
+                            format!("running {}", app.runner.command_line(&files, &["up", "-d"]));
+                        app.push_log(line);
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::ProjectLogs => {
+                        open_log_viewer(app, None);
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::ExportProject => {
+                        app.modal = Some(ModalState::Export {
+                            format: export::ExportFormat::DockerRun,
+                            written: None,
+                        });
+                        app.push_log("export: Tab to pick a format, Enter to write");
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::ToggleHostPortCheck => {
+                        app.check_host_ports = !app.check_host_ports;
+                        app.push_log(if app.check_host_ports {
+                            "host port check on: listening ports are avoided"
+                        } else {
+                            "host port check off: only compose services are checked"
+                        });
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::RestartService | TabCommand::ServiceLogs => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            let service = app.images[index].service_name.clone();
+                            if command == TabCommand::ServiceLogs {
+                                open_log_viewer(app, Some(&service));
+                            } else {
+                                let files = app.compose_run_files();
+                                app.runner.restart(&files, &service);
+                                app.push_log(format!("restarting {service}"));
                             }
-                            app.push_log(format!("deleted volume {}", removed.name));
                             return LoopControl::Continue;
                         }
                     }
+                    TabCommand::AddEnvironment => {
+                        app.modal = Some(ModalState::AddEnvironment {
+                            input: String::new(),
+                        });
+                        app.push_log("add environment: enter a name");
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::EditOverride => {
+                        if matches!(app.focus, FocusArea::Main) && !app.images.is_empty() {
+                            let Some(environment_index) = app.active_environment else {
+                                app.push_log("switch to an environment first (e in Project tab)");
+                                return LoopControl::Continue;
+                            };
+                            let index = app.images_selected.min(app.images.len() - 1);
+                            let existing = app
+                                .images
+                                .get(index)
+                                .and_then(|image| {
+                                    app.environments.get(environment_index).and_then(
+                                        |environment| environment.override_for(&image.service_name),
+                                    )
+                                })
+                                .cloned()
+                                .unwrap_or_default();
+                            app.modal = Some(ModalState::EditOverride {
+                                image_index: index,
+                                environment_index,
+                                port_input: match existing.port_mapping {
+                                    Some(mapping) if mapping.is_empty() => "none".to_string(),
+                                    Some(mapping) => mapping,
+                                    None => String::new(),
+                                },
+                                env_input: existing
+                                    .env_vars
+                                    .iter()
+                                    .map(|env| format!("{}={}", env.key, env.value))
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                                mounts_input: existing
+                                    .mounts
+                                    .iter()
+                                    .filter(|mount| !mount.uses_long_syntax())
+                                    .map(|mount| {
+                                        format!("{}:{}", mount.source, mount.short_target())
+                                    })
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                                replicas_input: existing
+                                    .replicas
+                                    .map(|replicas| replicas.to_string())
+                                    .unwrap_or_default(),
+                                active_field: OverrideField::Ports,
+                            });
+                            app.push_log(format!(
+                                "override: editing for {}",
+                                app.environment_label()
+                            ));
+                            return LoopControl::Continue;
+                        }
+                    }
+                    TabCommand::MoveUp | TabCommand::MoveDown => {
+                        if matches!(app.focus, FocusArea::Main) {
+                            let up = command == TabCommand::MoveUp;
+                            let moved = if matches!(app.active_tab, Tab::Volume) {
+                                app.move_volume(up)
+                            } else {
+                                app.move_image(up)
+                            };
+                            if moved {
+                                app.push_log(format!(
+                                    "moved {}; order is now manual",
+                                    if up { "up" } else { "down" }
+                                ));
+                            }
+                        }
+                        return LoopControl::Continue;
+                    }
+                    TabCommand::CycleOrder => {
+                        app.list_order = app.list_order.next();
+                        app.apply_list_order();
+                        app.push_log(format!(
+                            "services and volumes: {} order",
+                            app.list_order.label()
+                        ));
+                        return LoopControl::Continue;
+                    }
                     TabCommand::RenameProject | TabCommand::EditEnv => {}
                 }
             }
@@ -284,465 +1004,799 @@ pub fn handle_key(app: &mut App, key_code: KeyCode) -> LoopControl {
     }
 }
 
-fn handle_modal_key(app: &mut App, key_code: KeyCode) -> LoopControl {
-    let mut next_modal = app.modal.take();
-    let mut modal_transition: Option<ModalState> = None;
-    let mut close_modal = false;
-    let mut should_exit_after_modal = false;
-    let mut deferred_logs: Vec<String> = Vec::new();
+const PREVIEW_SCROLL_STEP: u16 = 3;
 
-    match key_code {
-        KeyCode::Esc => {
-            close_modal = true;
-            deferred_logs.push("modal canceled".to_string());
+fn scrolls_as_list(modal: &ModalState) -> bool {
+    match modal {
+        ModalState::SelectImageTag { .. }
+        | ModalState::SelectTemplate { .. }
+        | ModalState::ReviewScan { .. }
+        | ModalState::DeleteVolume { .. }
+        | ModalState::RemoveImageMount { .. }
+        | ModalState::RemoveImageEnv { .. } => true,
+        ModalState::ServiceProperties { input, .. }
+        | ModalState::VolumeProperties { input, .. }
+        | ModalState::MountProperties { input, .. }
+        | ModalState::ServiceDetail { input, .. } => input.is_none(),
+        _ => false,
+    }
+}
+
+fn handle_modal_mouse(app: &mut App, mouse: MouseEvent, popup: Rect) -> LoopControl {
+    let Some(modal) = app.modal.as_ref() else {
+        return LoopControl::Continue;
+    };
+    let position = Position::new(mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::ScrollUp if scrolls_as_list(modal) => handle_modal_key(app, KeyCode::Up),
+        MouseEventKind::ScrollDown if scrolls_as_list(modal) => {
+            handle_modal_key(app, KeyCode::Down)
         }
-        _ => match next_modal.as_mut() {
-            Some(modal) => match modal {
-                ModalState::AddImageType { input } => match key_code {
-                    KeyCode::Char(ch) => input.push(ch),
-                    KeyCode::Backspace => {
-                        input.pop();
-                    }
-                    KeyCode::Enter => {
-                        let image_term = input.trim().to_string();
-                        if image_term.is_empty() {
-                            app.push_log("type an image name to continue");
-                        } else {
-                            let runtime = tokio::runtime::Builder::new_current_thread()
-                                .enable_all()
-                                .build();
-
-                            match runtime {
-                                Ok(runtime) => {
-                                    match runtime.block_on(api::resolve_docker_hub_repository(
-                                        &image_term,
-                                    )) {
-                                        Ok(Some(resolved)) => match runtime.block_on(
-                                            api::list_docker_hub_tags(
-                                                &resolved.namespace,
-                                                &resolved.repo,
-                                            ),
-                                        ) {
-                                            Ok(all_tags) => {
-                                                let filtered_tags = api::filter_tags(&all_tags, "", 30);
-                                                modal_transition = Some(ModalState::SelectImageTag {
-                                                    image_term,
-                                                    namespace: resolved.namespace,
-                                                    repo: resolved.repo,
-                                                    all_tags,
-                                                    query: String::new(),
-                                                    filtered_tags,
-                                                    selected: 0,
-                                                });
-                                                deferred_logs.push(
-                                                    "image repo resolved; pick a tag".to_string(),
-                                                );
-                                            }
-                                            Err(error) => {
-                                                deferred_logs
-                                                    .push(format!("tag fetch failed: {error}"));
-                                            }
-                                        },
-                                        Ok(None) => {
-                                            deferred_logs.push("no repo match found".to_string());
-                                        }
-                                        Err(error) => {
-                                            deferred_logs.push(format!("repo search failed: {error}"));
-                                        }
-                                    }
-                                }
-                                Err(error) => {
-                                    deferred_logs.push(format!("runtime error: {error}"));
-                                }
-                            }
-                        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some((_, _, key)) = ui::modal_button_areas(popup, modal)
+                .into_iter()
+                .find(|(area, _, _)| area.contains(position))
+            {
+                return handle_modal_key(app, KeyCode::Char(key));
+            }
+            if let Some(ModalState::SelectImageTag {
+                filtered_tags,
+                selected,
+                ..
+            }) = app.modal.as_mut()
+                && let Some(index) = ui::list_row(
+                    ui::tag_picker_sections(popup)[1],
+                    mouse.row,
+                    filtered_tags.len(),
+                    *selected,
+                )
+            {
+                if index == *selected {
+                    return handle_modal_key(app, KeyCode::Enter);
+                }
+                *selected = index;
+            }
+            LoopControl::Continue
+        }
+        _ => LoopControl::Continue,
+    }
+}
+
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent, area: Rect) -> LoopControl {
+    let layout = ui::screen_layout(area, app);
+    if app.modal.is_some() {
+        return handle_modal_mouse(app, mouse, layout.popup);
+    }
+    let position = Position::new(mouse.column, mouse.row);
+
+    if let Some(viewer) = app.log_viewer.as_mut() {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => viewer.scroll_up(1),
+            MouseEventKind::ScrollDown => viewer.scroll_down(1),
+            _ => {}
+        }
+        return LoopControl::Continue;
+    }
+
+    let in_list =
+        layout.list.contains(position) && matches!(app.active_tab, Tab::Images | Tab::Volume);
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(index) = layout.sidebar.iter().position(|tab| tab.contains(position)) {
+                let tab = Tab::all()[index];
+                if tab != app.active_tab {
+                    app.active_tab = tab;
+                    app.preview_scroll = 0;
+                }
+                app.focus = FocusArea::Sidebar;
+            } else if layout.right[0].contains(position) {
+                app.focus = FocusArea::Main;
+                if in_list && matches!(app.active_tab, Tab::Images) {
+                    if let Some(index) = ui::list_row(
+                        layout.list,
+                        mouse.row,
+                        app.images.len(),
+                        app.images_selected,
+                    ) {
+                        app.images_selected = index;
                     }
-                    _ => {}
-                },
-                ModalState::SelectImageTag {
-                    image_term,
-                    namespace,
-                    repo,
-                    all_tags,
-                    query,
-                    filtered_tags,
-                    selected,
-                } => {
-                    let mut next_step: Option<ModalState> = None;
+                } else if in_list
+                    && let Some(index) = ui::list_row(
+                        layout.list,
+                        mouse.row,
+                        app.volumes.len(),
+                        app.volumes_selected,
+                    )
+                {
+                    app.volumes_selected = index;
+                }
+            }
+        }
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            let up = mouse.kind == MouseEventKind::ScrollUp;
+            if layout.sidebar.iter().any(|tab| tab.contains(position)) {
+                app.active_tab = if up {
+                    app.active_tab.previous()
+                } else {
+                    app.active_tab.next()
+                };
+                app.preview_scroll = 0;
+            } else if in_list {
+                let (selected, total) = if matches!(app.active_tab, Tab::Images) {
+                    (&mut app.images_selected, app.images.len())
+                } else {
+                    (&mut app.volumes_selected, app.volumes.len())
+                };
+                *selected = if up {
+                    selected.saturating_sub(1)
+                } else {
+                    (*selected + 1).min(total.saturating_sub(1))
+                };
+            } else if layout.right[0].contains(position) && matches!(app.active_tab, Tab::Project) {
+                let max = app.compose_preview().lines().count().saturating_sub(1) as u16;
+                app.preview_scroll = if up {
+                    app.preview_scroll.saturating_sub(PREVIEW_SCROLL_STEP)
+                } else {
+                    (app.preview_scroll + PREVIEW_SCROLL_STEP).min(max)
+                };
+            }
+        }
+        _ => {}
+    }
+    LoopControl::Continue
+}
 
-                    match key_code {
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            if !filtered_tags.is_empty() {
-                                *selected = (*selected + 1).min(filtered_tags.len() - 1);
-                            }
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if !filtered_tags.is_empty() && *selected > 0 {
-                                *selected -= 1;
-                            }
-                        }
-                        KeyCode::Enter => {
-                            if let Some(tag) = filtered_tags.get(*selected).cloned() {
-                                let namespace_value = namespace.clone();
-                                let repo_value = repo.clone();
-                                let runtime = tokio::runtime::Builder::new_current_thread()
-                                    .enable_all()
-                                    .build();
-                                let suggested_ports = match runtime {
-                                    Ok(runtime) => match runtime.block_on(
-                                        api::list_docker_hub_exposed_ports(
-                                            &namespace_value,
-                                            &repo_value,
-                                            &tag,
+fn handle_modal_key(app: &mut App, key_code: KeyCode) -> LoopControl {
+    let mut next_modal = app.modal.take();
+    let mut modal_transition: Option<ModalState> = None;
+    let mut close_modal = false;
+    let mut should_exit_after_modal = false;
+    let mut deferred_logs: Vec<String> = Vec::new();
+
+    match (key_code, next_modal.as_mut()) {
+        (KeyCode::Esc, _) => {
+            close_modal = true;
+            deferred_logs.push("modal canceled".to_string());
+        }
+        (_, Some(modal)) => match modal {
+            ModalState::AddImageType {
+                input,
+                local_images,
+                selected,
+            } => match key_code {
+                KeyCode::Char(ch) => {
+                    input.push(ch);
+                    *selected = None;
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    *selected = None;
+                }
+                KeyCode::Down => {
+                    let matches = engine::matching_images(local_images, input);
+                    if !matches.is_empty() {
+                        *selected =
+                            Some(selected.map_or(0, |index| (index + 1).min(matches.len() - 1)));
+                    }
+                }
+                KeyCode::Up => {
+                    *selected = selected.and_then(|index| index.checked_sub(1));
+                }
+                KeyCode::Enter if selected.is_some() => {
+                    let matches = engine::matching_images(local_images, input);
+                    if let Some(reference) = selected.and_then(|index| matches.get(index))
+                        && let Some((namespace, repo, tag)) = build::split_image_ref(reference)
+                    {
+                        modal_transition = Some(configure_new_image_modal(
+                            app,
+                            &namespace,
+                            &repo,
+                            &tag,
+                            &mut deferred_logs,
+                        ));
+                        deferred_logs.push(format!("using local image {reference}"));
+                    }
+                }
+                KeyCode::Tab => {
+                    modal_transition = Some(edit_build_modal(None, None, "."));
+                    deferred_logs.push("build service: set context and Dockerfile".to_string());
+                }
+                KeyCode::Enter => {
+                    let image_term = input.trim().to_string();
+                    if image_term.is_empty() {
+                        app.push_log("type an image name to continue");
+                    } else if is_build_context_input(&image_term) {
+                        modal_transition = Some(edit_build_modal(None, None, &image_term));
+                        deferred_logs.push("build service: set context and Dockerfile".to_string());
+                    } else {
+                        let runtime = tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build();
+
+                        match runtime {
+                            Ok(runtime) => {
+                                match runtime
+                                    .block_on(api::resolve_docker_hub_repository(&image_term))
+                                {
+                                    Ok(Some(resolved)) => match runtime.block_on(
+                                        api::list_docker_hub_tags(
+                                            &resolved.namespace,
+                                            &resolved.repo,
                                         ),
                                     ) {
-                                        Ok(ports) => ports,
+                                        Ok(all_tags) => {
+                                            let filtered_tags = api::filter_tags(&all_tags, "", 30);
+                                            modal_transition = Some(ModalState::SelectImageTag {
+                                                image_term,
+                                                namespace: resolved.namespace,
+                                                repo: resolved.repo,
+                                                all_tags,
+                                                query: String::new(),
+                                                filtered_tags,
+                                                selected: 0,
+                                            });
+                                            deferred_logs.push(
+                                                "image repo resolved; pick a tag".to_string(),
+                                            );
+                                        }
                                         Err(error) => {
-                                            deferred_logs.push(format!(
-                                                "port suggestions unavailable: {error}"
-                                            ));
-                                            Vec::new()
+                                            deferred_logs
+                                                .push(format!("tag fetch failed: {error}"));
                                         }
                                     },
+                                    Ok(None) => {
+                                        deferred_logs.push("no repo match found".to_string());
+                                    }
                                     Err(error) => {
-                                        deferred_logs.push(format!(
-                                            "runtime error for port suggestion: {error}"
-                                        ));
-                                        Vec::new()
+                                        deferred_logs.push(format!("repo search failed: {error}"));
                                     }
-                                };
-                                let suggested_container_port =
-                                    preferred_container_port(&suggested_ports);
-                                let suggested_mapping =
-                                    suggested_port_mapping(&app, suggested_container_port);
-                                let (host_port_input, container_port_input) =
-                                    split_port_mapping(&suggested_mapping);
-                                next_step = Some(ModalState::ConfigureImagePorts {
-                                    existing_index: None,
-                                    namespace: namespace_value,
-                                    repo: repo_value,
-                                    tag: tag.clone(),
-                                    host_port_input,
-                                    container_port_input,
-                                    service_name_input: default_service_name(repo, app.images.len()),
-                                    active_field: ConfigureField::HostPort,
-                                    host_port_typed: false,
-                                    container_port_typed: false,
-                                    service_name_typed: false,
-                                });
-                                if let Some(port) = suggested_container_port {
-                                    deferred_logs.push(format!("suggested container port {port}"));
                                 }
                             }
+                            Err(error) => {
+                                deferred_logs.push(format!("runtime error: {error}"));
+                            }
                         }
-                        KeyCode::Backspace => {
-                            query.pop();
-                            *filtered_tags = api::filter_tags(all_tags, query, 30);
-                            *selected = 0;
-                        }
-                        KeyCode::Char(ch) => {
-                            query.push(ch);
-                            *filtered_tags = api::filter_tags(all_tags, query, 30);
-                            *selected = 0;
-                        }
-                        _ => {}
                     }
+                }
+                _ => {}
+            },
+            ModalState::SelectImageTag {
+                image_term,
+                namespace,
+                repo,
+                all_tags,
+                query,
+                filtered_tags,
+                selected,
+            } => {
+                let mut next_step: Option<ModalState> = None;
 
-                    if let Some(step) = next_step {
-                        modal_transition = Some(step);
-                        deferred_logs.push(format!(
-                            "resolved {} -> {}/{}; set ports",
-                            image_term, namespace, repo
-                        ));
+                match key_code {
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        *selected = (*selected + 1).min(filtered_tags.len().saturating_sub(1));
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Enter => {
+                        if let Some(tag) = filtered_tags.get(*selected) {
+                            next_step = Some(configure_new_image_modal(
+                                app,
+                                namespace,
+                                repo,
+                                tag,
+                                &mut deferred_logs,
+                            ));
+                        }
+                    }
+                    KeyCode::Backspace => {
+                        query.pop();
+                        *filtered_tags = api::filter_tags(all_tags, query, 30);
+                        *selected = 0;
+                    }
+                    KeyCode::Char(ch) => {
+                        query.push(ch);
+                        *filtered_tags = api::filter_tags(all_tags, query, 30);
+                        *selected = 0;
                     }
+                    _ => {}
                 }
-                ModalState::ConfigureImagePorts {
-                    existing_index,
-                    namespace,
-                    repo,
-                    tag,
-                    host_port_input,
-                    container_port_input,
-                    service_name_input,
-                    active_field,
-                    host_port_typed,
-                    container_port_typed,
-                    service_name_typed,
-                } => {
-                    let mut should_close_modal = false;
-                    let mut log_line: Option<String> = None;
 
-                    match key_code {
-                        KeyCode::Enter => {
-                            let fallback_mapping = if let Some(index) = existing_index {
-                                app.images
-                                    .get(*index)
-                                    .map(|image| image.port_mapping.clone())
-                                    .unwrap_or_else(|| app.next_port_mapping())
-                            } else {
-                                app.next_port_mapping()
-                            };
-                            let (fallback_host, fallback_container) =
-                                split_port_mapping(&fallback_mapping);
+                if let Some(step) = next_step {
+                    modal_transition = Some(step);
+                    deferred_logs.push(format!(
+                        "resolved {} -> {}/{}; set ports",
+                        image_term, namespace, repo
+                    ));
+                }
+            }
+            ModalState::ConfigureImagePorts {
+                existing_index,
+                namespace,
+                repo,
+                tag,
+                host_port_input,
+                container_port_input,
+                service_name_input,
+                active_field,
+                host_port_typed,
+                container_port_typed,
+                service_name_typed,
+            } => {
+                let mut should_close_modal = false;
+                let mut log_line: Option<String> = None;
 
-                            let host = if host_port_input.trim().is_empty() {
-                                fallback_host
-                            } else {
-                                host_port_input.trim().to_string()
-                            };
-                            let container = if container_port_input.trim().is_empty() {
-                                fallback_container
-                            } else {
-                                container_port_input.trim().to_string()
-                            };
-                            let mapping = format!("{host}:{container}");
+                match key_code {
+                    KeyCode::Enter => {
+                        let fallback_mapping = if let Some(index) = existing_index {
+                            app.images
+                                .get(*index)
+                                .map(|image| image.port_mapping.clone())
+                                .unwrap_or_else(|| app.next_port_mapping())
+                        } else {
+                            app.next_port_mapping()
+                        };
+                        let (fallback_host, fallback_container) =
+                            split_port_mapping(&fallback_mapping);
 
+                        let host = if host_port_input.trim().is_empty() {
+                            fallback_host
+                        } else {
+                            host_port_input.trim().to_string()
+                        };
+                        let container = if container_port_input.trim().is_empty() {
+                            fallback_container
+                        } else {
+                            container_port_input.trim().to_string()
+                        };
+                        let mapping = format!("{host}:{container}");
+                        if let Err(error) = ports::validate_mapping(&mapping) {
+                            log_line = Some(error);
+                        } else {
                             let service_name = if service_name_input.trim().is_empty() {
                                 default_service_name(repo, app.images.len())
                             } else {
                                 service_name_input.trim().to_string()
                             };
 
-                            let image = ImageEntry {
-                                service_name,
-                                namespace: namespace.clone(),
-                                repo: repo.clone(),
-                                tag: tag.clone(),
-                                port_mapping: mapping,
-                                command: existing_index
-                                    .and_then(|index| app.images.get(index))
-                                    .and_then(|image| image.command.clone()),
-                                mounts: existing_index
-                                    .and_then(|index| app.images.get(index))
-                                    .map(|image| image.mounts.clone())
-                                    .unwrap_or_default(),
-                                env_vars: existing_index
-                                    .and_then(|index| app.images.get(index))
-                                    .map(|image| image.env_vars.clone())
-                                    .unwrap_or_default(),
-                            };
+                            let mut image = existing_index
+                                .and_then(|index| app.images.get(index))
+                                .cloned()
+                                .unwrap_or_default();
+                            if existing_index.is_some() {
+                                app.rename_service(&image.service_name, &service_name);
+                            }
+                            image.service_name = service_name;
+                            image.namespace = namespace.clone();
+                            image.repo = repo.clone();
+                            image.tag = tag.clone();
+                            image.port_mapping = mapping;
+                            let description = image.describe();
 
                             if let Some(index) = existing_index {
                                 if let Some(slot) = app.images.get_mut(*index) {
                                     *slot = image;
                                     app.images_selected = *index;
-                                    log_line =
-                                        Some(format!("updated image {namespace}/{repo}:{tag}"));
+                                    log_line = Some(format!("updated image {description}"));
                                 }
                             } else {
+                                let reference = image.image_ref();
                                 app.images.push(image);
                                 if !app.images.is_empty() {
                                     app.images_selected = app.images.len() - 1;
                                 }
-                                log_line = Some(format!("added image {namespace}/{repo}:{tag}"));
+                                log_line = Some(format!("added image {description}"));
+                                if let Some(reference) = reference {
+                                    app.engine.inspect(&reference, InspectPurpose::OfferPull);
+                                }
+                                if let Some(entry) = knowledge::lookup(namespace, repo)
+                                    && !entry.required_env.is_empty()
+                                {
+                                    deferred_logs.push(format!(
+                                        "{repo} requires env {} (press a to add)",
+                                        entry.required_env.join(", ")
+                                    ));
+                                }
                             }
                             should_close_modal = true;
                         }
-                        KeyCode::Backspace => match active_field {
-                            ConfigureField::HostPort => {
-                                host_port_input.pop();
-                                *host_port_typed = true;
-                            }
-                            ConfigureField::ContainerPort => {
-                                container_port_input.pop();
-                                *container_port_typed = true;
-                            }
-                            ConfigureField::Name => {
-                                service_name_input.pop();
-                                *service_name_typed = true;
-                            }
-                        },
-                        KeyCode::Char(ch) => match active_field {
-                            ConfigureField::HostPort => {
-                                if ch.is_ascii_digit() {
-                                    if !*host_port_typed {
-                                        host_port_input.clear();
-                                        *host_port_typed = true;
-                                    }
-                                    host_port_input.push(ch);
+                    }
+                    KeyCode::Backspace => match active_field {
+                        ConfigureField::HostPort => {
+                            host_port_input.pop();
+                            *host_port_typed = true;
+                        }
+                        ConfigureField::ContainerPort => {
+                            container_port_input.pop();
+                            *container_port_typed = true;
+                        }
+                        ConfigureField::Name => {
+                            service_name_input.pop();
+                            *service_name_typed = true;
+                        }
+                    },
+                    KeyCode::Char(ch) => match active_field {
+                        ConfigureField::HostPort => {
+                            if ch.is_ascii_digit() {
+                                if !*host_port_typed {
+                                    host_port_input.clear();
+                                    *host_port_typed = true;
                                 }
+                                host_port_input.push(ch);
                             }
-                            ConfigureField::ContainerPort => {
-                                if ch.is_ascii_digit() {
-                                    if !*container_port_typed {
-                                        container_port_input.clear();
-                                        *container_port_typed = true;
-                                    }
-                                    container_port_input.push(ch);
+                        }
+                        ConfigureField::ContainerPort => {
+                            if ch.is_ascii_digit() {
+                                if !*container_port_typed {
+                                    container_port_input.clear();
+                                    *container_port_typed = true;
+                                }
+                                container_port_input.push(ch);
+                                if !*host_port_typed
+                                    && let Ok(container) = container_port_input.parse::<u16>()
+                                {
+                                    *host_port_input = app
+                                        .allocate_host_port(container, *existing_index)
+                                        .host
+                                        .to_string();
                                 }
                             }
-                            ConfigureField::Name => {
-                                if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
-                                    if !*service_name_typed {
-                                        service_name_input.clear();
-                                        *service_name_typed = true;
-                                    }
-                                    service_name_input.push(ch);
+                        }
+                        ConfigureField::Name => {
+                            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+                                if !*service_name_typed {
+                                    service_name_input.clear();
+                                    *service_name_typed = true;
                                 }
+                                service_name_input.push(ch);
                             }
-                        },
-                        KeyCode::Tab => {
-                            *active_field = active_field.next();
                         }
-                        _ => {}
+                    },
+                    KeyCode::Tab => {
+                        *active_field = active_field.next();
                     }
+                    _ => {}
+                }
 
-                    if should_close_modal {
-                        close_modal = true;
+                if should_close_modal {
+                    close_modal = true;
+                }
+                if let Some(line) = log_line {
+                    deferred_logs.push(line);
+                }
+            }
+            ModalState::ConfirmDeleteImage { index } => match key_code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    if let Some(removed) = app.remove_service(*index) {
+                        deferred_logs.push(format!("deleted image {}", removed.describe()));
                     }
-                    if let Some(line) = log_line {
-                        deferred_logs.push(line);
+                    close_modal = true;
+                }
+                KeyCode::Char('n') => {
+                    close_modal = true;
+                    deferred_logs.push("delete canceled".to_string());
+                }
+                _ => {}
+            },
+            ModalState::ImportRun { input, report } => match (key_code, report.is_some()) {
+                (KeyCode::Enter, true) => close_modal = true,
+                (KeyCode::Enter, false) => match docker_run::parse_docker_run(input) {
+                    Ok(import) => {
+                        let lines = docker_run::apply_import(app, import);
+                        deferred_logs.extend(lines.iter().take(2).cloned());
+                        *report = Some(lines);
                     }
+                    Err(error) => deferred_logs.push(format!("import failed: {error}")),
+                },
+                (KeyCode::Char(ch), false) => input.push(ch),
+                (KeyCode::Backspace, false) => {
+                    input.pop();
                 }
-                ModalState::ConfirmDeleteImage { index } => match key_code {
-                    KeyCode::Char('y') | KeyCode::Enter => {
-                        if *index < app.images.len() {
-                            let removed = app.images.remove(*index);
-                            if app.images.is_empty() {
-                                app.images_selected = 0;
-                            } else if app.images_selected >= app.images.len() {
-                                app.images_selected = app.images.len() - 1;
-                            }
-                            deferred_logs.push(format!(
-                                "deleted image {}/{}:{}",
-                                removed.namespace, removed.repo, removed.tag
-                            ));
+                _ => {}
+            },
+            ModalState::Export { format, written } => match (key_code, written.is_some()) {
+                (KeyCode::Enter, true) => close_modal = true,
+                (KeyCode::Tab, false) => *format = format.next(),
+                (KeyCode::Enter, false) => {
+                    let result = export::export(app, *format);
+                    match result.write(Path::new(".")) {
+                        Ok(()) => {
+                            let summary = format!(
+                                "exported {} as {} ({} files, {} warnings)",
+                                app.project_name,
+                                format.label(),
+                                result.files.len(),
+                                result.warnings.len()
+                            );
+                            deferred_logs.push(summary.clone());
+                            *written = Some(summary);
                         }
-                        close_modal = true;
+                        Err(error) => deferred_logs.push(format!("export failed: {error}")),
                     }
-                    KeyCode::Char('n') => {
-                        close_modal = true;
-                        deferred_logs.push("delete canceled".to_string());
+                }
+                _ => {}
+            },
+            ModalState::ConfirmPullImage { reference } => match key_code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    deferred_logs.push(format!("pulling {reference} in the background"));
+                    app.pulls.push(engine::spawn_pull(reference));
+                    close_modal = true;
+                }
+                KeyCode::Char('n') => {
+                    close_modal = true;
+                    deferred_logs.push(format!("{reference} not pulled"));
+                }
+                _ => {}
+            },
+            ModalState::ConfirmComposeDown => match key_code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    let files = app.compose_run_files();
+                    app.runner.down(&files);
+                    deferred_logs.push(format!(
+                        "running {}",
+                        app.runner.command_line(&files, &["down"])
+                    ));
+                    close_modal = true;
+                }
+                KeyCode::Char('n') => {
+                    close_modal = true;
+                    deferred_logs.push("compose down cancelled".to_string());
+                }
+                _ => {}
+            },
+            ModalState::ConfirmWriteCompose => match key_code {
+                KeyCode::Enter if !app.dropped_content.is_empty() => {
+                    deferred_logs.push(
+                        "write would drop unsupported content: press y to confirm".to_string(),
+                    );
+                }
+                KeyCode::Char('y' | 'c') | KeyCode::Enter => {
+                    if key_code == KeyCode::Char('c') {
+                        let missing = app.missing_bind_dirs();
+                        match paths::create_dirs(&app.project_dir(), &missing) {
+                            Ok(()) => deferred_logs.extend(
+                                missing.iter().map(|dir| format!("created directory {dir}")),
+                            ),
+                            Err(error) => {
+                                deferred_logs.push(format!("failed to create directories: {error}"))
+                            }
+                        }
                     }
-                    _ => {}
-                },
-                ModalState::ConfirmWriteCompose => match key_code {
-                    KeyCode::Char('y') | KeyCode::Enter => {
-                        let compose = app.compose_yaml();
-                        match std::fs::write("docker-compose.yaml", compose) {
-                            Ok(_) => {
+                    let compose = app.compose_yaml();
+                    let included_files = app.compose_files_output();
+                    let environment_files = app.environment_files();
+                    let written = std::fs::write(&app.compose_file, compose)
+                        .and_then(|_| {
+                            included_files.iter().try_for_each(|(file, contents)| {
+                                if let Some(parent) = Path::new(file).parent() {
+                                    std::fs::create_dir_all(parent)?;
+                                }
+                                std::fs::write(file, contents)
+                            })
+                        })
+                        .and_then(|_| {
+                            environment_files
+                                .iter()
+                                .try_for_each(|(file, contents)| std::fs::write(file, contents))
+                        })
+                        .and_then(|_| {
+                            if app.dotenv.is_empty() {
+                                Ok(())
+                            } else {
+                                secrets::write_dotenv(Path::new(".env"), &app.dotenv)
+                            }
+                        })
+                        .and_then(|_| secrets::write_secret_files(Path::new("."), &app.secrets));
+                    match written {
+                        Ok(_) => {
+                            app.dropped_content.clear();
+                            deferred_logs.push(format!("wrote {} from preview", app.compose_file));
+                            for (file, _) in included_files.iter().chain(&environment_files) {
+                                deferred_logs.push(format!("wrote {file}"));
+                            }
+                            if !app.dotenv.is_empty() {
                                 deferred_logs
-                                    .push("wrote docker-compose.yaml from preview".to_string());
-                                should_exit_after_modal = true;
+                                    .push(format!("wrote {} variables to .env", app.dotenv.len()));
+                            }
+                            if !app.secrets.is_empty() {
+                                deferred_logs.push(format!(
+                                    "wrote {} secret files to ./{}",
+                                    app.secrets.len(),
+                                    secrets::SECRETS_DIR
+                                ));
                             }
-                            Err(error) => deferred_logs
-                                .push(format!("failed to write docker-compose.yaml: {error}")),
+                            should_exit_after_modal = true;
                         }
-                        close_modal = true;
-                    }
-                    KeyCode::Char('n') => {
-                        close_modal = true;
-                        deferred_logs.push("compose write canceled".to_string());
-                    }
-                    _ => {}
-                },
-                ModalState::AddVolume { input } => match key_code {
-                    KeyCode::Char(ch) => input.push(ch),
-                    KeyCode::Backspace => {
-                        input.pop();
-                    }
-                    KeyCode::Enter => {
-                        let mut name = input.trim().to_string();
-                        if name.is_empty() {
-                            name = format!("volume_{}", app.volumes.len() + 1);
+                        Err(error) => {
+                            deferred_logs.push(format!("failed to write project files: {error}"))
                         }
-
-                        app.volumes.push(VolumeEntry { name: name.clone() });
-                        app.volumes_selected = app.volumes.len() - 1;
-                        close_modal = true;
-                        deferred_logs.push(format!("added volume {name}"));
                     }
-                    _ => {}
-                },
-                ModalState::SelectImageVolumeSource {
-                    image_index,
-                    selected_option,
-                } => match key_code {
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if *selected_option > 0 {
-                            *selected_option -= 1;
-                        }
+                    close_modal = true;
+                }
+                KeyCode::Char('n') => {
+                    close_modal = true;
+                    deferred_logs.push("compose write canceled".to_string());
+                }
+                _ => {}
+            },
+            ModalState::AddVolume { input } => match key_code {
+                KeyCode::Char(ch) => input.push(ch),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let mut name = input.trim().to_string();
+                    if name.is_empty() {
+                        name = format!("volume_{}", app.volumes.len() + 1);
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        *selected_option = (*selected_option + 1).min(2);
-                    }
-                    KeyCode::Enter => match *selected_option {
-                        0 => {
-                            if app.volumes.is_empty() {
-                                modal_transition = Some(ModalState::MountNewVolume {
-                                    image_index: *image_index,
-                                    new_volume_input: default_volume_name(&app),
-                                    target_input: default_mount_target(),
-                                    active_field: MountInputField::Source,
-                                    new_volume_typed: false,
-                                    target_typed: false,
-                                });
-                                deferred_logs.push(
-                                    "no existing volume; creating new volume mount".to_string(),
-                                );
-                            } else {
-                                modal_transition = Some(ModalState::MountExistingVolume {
-                                    image_index: *image_index,
-                                    selected_volume: 0,
-                                    target_input: default_mount_target(),
-                                    active_field: MountExistingField::Volume,
-                                    target_typed: false,
-                                });
-                            }
-                        }
-                        1 => {
+
+                    app.volumes.push(VolumeEntry::new(name.clone()));
+                    app.volumes_selected = app.volumes.len() - 1;
+                    close_modal = true;
+                    deferred_logs.push(format!("added volume {name}"));
+                }
+                _ => {}
+            },
+            ModalState::SelectImageVolumeSource {
+                image_index,
+                selected_option,
+            } => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected_option = selected_option.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    *selected_option = (*selected_option + 1).min(2);
+                }
+                KeyCode::Enter => match *selected_option {
+                    0 => {
+                        if app.volumes.is_empty() {
                             modal_transition = Some(ModalState::MountNewVolume {
                                 image_index: *image_index,
-                                new_volume_input: default_volume_name(&app),
-                                target_input: default_mount_target(),
+                                new_volume_input: default_volume_name(app),
+                                target_input: default_mount_target(app.images.get(*image_index)),
                                 active_field: MountInputField::Source,
                                 new_volume_typed: false,
                                 target_typed: false,
                             });
-                        }
-                        2 => {
-                            modal_transition = Some(ModalState::MountLocalPath {
+                            deferred_logs
+                                .push("no existing volume; creating new volume mount".to_string());
+                        } else {
+                            modal_transition = Some(ModalState::MountExistingVolume {
                                 image_index: *image_index,
-                                local_path_input: "./".to_string(),
-                                target_input: default_mount_target(),
-                                active_field: MountInputField::Source,
-                                local_path_typed: false,
+                                selected_volume: 0,
+                                target_input: default_mount_target(app.images.get(*image_index)),
+                                active_field: MountExistingField::Volume,
                                 target_typed: false,
                             });
                         }
-                        _ => {}
-                    },
+                    }
+                    1 => {
+                        modal_transition = Some(ModalState::MountNewVolume {
+                            image_index: *image_index,
+                            new_volume_input: default_volume_name(app),
+                            target_input: default_mount_target(app.images.get(*image_index)),
+                            active_field: MountInputField::Source,
+                            new_volume_typed: false,
+                            target_typed: false,
+                        });
+                    }
+                    2 => {
+                        modal_transition = Some(ModalState::MountLocalPath {
+                            image_index: *image_index,
+                            local_path_input: "./".to_string(),
+                            target_input: default_mount_target(app.images.get(*image_index)),
+                            active_field: MountInputField::Source,
+                            local_path_typed: false,
+                            target_typed: false,
+                            path_assist: PathAssist::default(),
+                        });
+                    }
                     _ => {}
                 },
-                ModalState::MountExistingVolume {
-                    image_index,
-                    selected_volume,
-                    target_input,
-                    active_field,
-                    target_typed,
-                } => match key_code {
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if matches!(active_field, MountExistingField::Volume) && *selected_volume > 0 {
-                            *selected_volume -= 1;
+                _ => {}
+            },
+            ModalState::MountExistingVolume {
+                image_index,
+                selected_volume,
+                target_input,
+                active_field,
+                target_typed,
+            } => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    if matches!(active_field, MountExistingField::Volume) {
+                        *selected_volume = selected_volume.saturating_sub(1);
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if matches!(active_field, MountExistingField::Volume) {
+                        *selected_volume =
+                            (*selected_volume + 1).min(app.volumes.len().saturating_sub(1));
+                    }
+                }
+                KeyCode::Backspace => {
+                    if matches!(active_field, MountExistingField::Target) {
+                        target_input.pop();
+                        *target_typed = true;
+                    }
+                }
+                KeyCode::Char(ch) => {
+                    let allowed = ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '.');
+                    if matches!(active_field, MountExistingField::Target) && allowed {
+                        if !*target_typed {
+                            target_input.clear();
+                            *target_typed = true;
                         }
+                        target_input.push(ch);
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if matches!(active_field, MountExistingField::Volume)
-                            && !app.volumes.is_empty()
-                        {
-                            *selected_volume = (*selected_volume + 1).min(app.volumes.len() - 1);
+                }
+                KeyCode::Tab => {
+                    *active_field = active_field.next();
+                }
+                KeyCode::Enter => {
+                    if app.volumes.is_empty() {
+                        deferred_logs
+                            .push("no named volumes available; create one first".to_string());
+                    } else {
+                        let chosen = app
+                            .volumes
+                            .get(*selected_volume)
+                            .map(|volume| volume.name.clone())
+                            .unwrap_or_else(|| app.volumes[0].name.clone());
+                        let target = if target_input.trim().is_empty() {
+                            default_mount_target(app.images.get(*image_index))
+                        } else {
+                            target_input.trim().to_string()
+                        };
+
+                        if !app.volumes.iter().any(|volume| volume.name == chosen) {
+                            app.volumes.push(VolumeEntry::new(chosen.clone()));
+                            app.volumes_selected = app.volumes.len() - 1;
+                        }
+
+                        if let Some(image) = app.images.get_mut(*image_index) {
+                            image
+                                .mounts
+                                .push(VolumeMount::new(chosen.clone(), target.clone()));
+                            deferred_logs.push(format!(
+                                "mounted volume {chosen}:{target} on {}",
+                                image.service_name
+                            ));
+                            close_modal = true;
                         }
                     }
-                    KeyCode::Backspace => {
-                        if matches!(active_field, MountExistingField::Target) {
-                            target_input.pop();
-                            *target_typed = true;
+                }
+                _ => {}
+            },
+            ModalState::MountNewVolume {
+                image_index,
+                new_volume_input,
+                target_input,
+                active_field,
+                new_volume_typed,
+                target_typed,
+            } => match key_code {
+                KeyCode::Backspace => match active_field {
+                    MountInputField::Source => {
+                        new_volume_input.pop();
+                        *new_volume_typed = true;
+                    }
+                    MountInputField::Target => {
+                        target_input.pop();
+                        *target_typed = true;
+                    }
+                },
+                KeyCode::Char(ch) => match active_field {
+                    MountInputField::Source => {
+                        if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+                            if !*new_volume_typed {
+                                new_volume_input.clear();
+                                *new_volume_typed = true;
+                            }
+                            new_volume_input.push(ch);
                         }
                     }
-                    KeyCode::Char(ch) => {
-                        if matches!(active_field, MountExistingField::Target)
-                            && (ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '.'))
-                        {
+                    MountInputField::Target => {
+                        if ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '.') {
                             if !*target_typed {
                                 target_input.clear();
                                 *target_typed = true;
@@ -750,364 +1804,1310 @@ fn handle_modal_key(app: &mut App, key_code: KeyCode) -> LoopControl {
                             target_input.push(ch);
                         }
                     }
-                    KeyCode::Tab => {
-                        *active_field = active_field.next();
+                },
+                KeyCode::Tab => {
+                    *active_field = active_field.next();
+                }
+                KeyCode::Enter => {
+                    let mut source = new_volume_input.trim().to_string();
+                    if source.is_empty() {
+                        source = default_volume_name(app);
                     }
-                    KeyCode::Enter => {
-                        if app.volumes.is_empty() {
-                            deferred_logs
-                                .push("no named volumes available; create one first".to_string());
-                        } else {
-                            let chosen = app
-                                .volumes
-                                .get(*selected_volume)
-                                .map(|volume| volume.name.clone())
-                                .unwrap_or_else(|| app.volumes[0].name.clone());
-                            let target = if target_input.trim().is_empty() {
-                                default_mount_target()
-                            } else {
-                                target_input.trim().to_string()
-                            };
+                    let target = if target_input.trim().is_empty() {
+                        default_mount_target(app.images.get(*image_index))
+                    } else {
+                        target_input.trim().to_string()
+                    };
 
-                            if !app.volumes.iter().any(|volume| volume.name == chosen) {
-                                app.volumes.push(VolumeEntry {
-                                    name: chosen.clone(),
-                                });
-                                app.volumes_selected = app.volumes.len() - 1;
-                            }
+                    if !app.volumes.iter().any(|volume| volume.name == source) {
+                        app.volumes.push(VolumeEntry::new(source.clone()));
+                        app.volumes_selected = app.volumes.len() - 1;
+                    }
 
-                            if let Some(image) = app.images.get_mut(*image_index) {
-                                image.mounts.push(VolumeMount {
-                                    source: chosen.clone(),
-                                    target: target.clone(),
-                                });
-                                deferred_logs.push(format!(
-                                    "mounted volume {chosen}:{target} on {}",
-                                    image.service_name
-                                ));
-                                close_modal = true;
-                            }
-                        }
+                    if let Some(image) = app.images.get_mut(*image_index) {
+                        image
+                            .mounts
+                            .push(VolumeMount::new(source.clone(), target.clone()));
+                        deferred_logs.push(format!(
+                            "mounted new volume {source}:{target} on {}",
+                            image.service_name
+                        ));
+                        close_modal = true;
+                    }
+                }
+                _ => {}
+            },
+            ModalState::MountLocalPath {
+                image_index,
+                local_path_input,
+                target_input,
+                active_field,
+                local_path_typed,
+                target_typed,
+                path_assist,
+            } => match key_code {
+                _ if matches!(active_field, MountInputField::Source)
+                    && handle_path_assist(
+                        path_assist,
+                        local_path_input,
+                        &app.project_dir(),
+                        key_code,
+                    ) =>
+                {
+                    *local_path_typed = true;
+                }
+                KeyCode::Backspace => match active_field {
+                    MountInputField::Source => {
+                        local_path_input.pop();
+                        *local_path_typed = true;
+                    }
+                    MountInputField::Target => {
+                        target_input.pop();
+                        *target_typed = true;
                     }
-                    _ => {}
                 },
-                ModalState::MountNewVolume {
-                    image_index,
-                    new_volume_input,
-                    target_input,
-                    active_field,
-                    new_volume_typed,
-                    target_typed,
-                } => match key_code {
-                    KeyCode::Backspace => match active_field {
-                        MountInputField::Source => {
-                            new_volume_input.pop();
-                            *new_volume_typed = true;
-                        }
-                        MountInputField::Target => {
-                            target_input.pop();
-                            *target_typed = true;
-                        }
-                    },
-                    KeyCode::Char(ch) => match active_field {
-                        MountInputField::Source => {
-                            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
-                                if !*new_volume_typed {
-                                    new_volume_input.clear();
-                                    *new_volume_typed = true;
-                                }
-                                new_volume_input.push(ch);
+                KeyCode::Char(ch) => match active_field {
+                    MountInputField::Source => {
+                        if ch.is_ascii_alphanumeric()
+                            || matches!(ch, '/' | '_' | '-' | '.' | '~' | '$' | '{' | '}')
+                        {
+                            if !*local_path_typed {
+                                local_path_input.clear();
+                                *local_path_typed = true;
                             }
+                            local_path_input.push(ch);
                         }
-                        MountInputField::Target => {
-                            if ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '.') {
-                                if !*target_typed {
-                                    target_input.clear();
-                                    *target_typed = true;
-                                }
-                                target_input.push(ch);
+                    }
+                    MountInputField::Target => {
+                        if ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '.') {
+                            if !*target_typed {
+                                target_input.clear();
+                                *target_typed = true;
                             }
+                            target_input.push(ch);
                         }
-                    },
-                    KeyCode::Tab => {
-                        *active_field = active_field.next();
                     }
-                    KeyCode::Enter => {
-                        let mut source = new_volume_input.trim().to_string();
-                        if source.is_empty() {
-                            source = default_volume_name(&app);
+                },
+                KeyCode::Tab => {
+                    *active_field = active_field.next();
+                }
+                KeyCode::Enter => {
+                    let base = app.project_dir();
+                    let source = paths::normalize(&base, local_path_input);
+                    if source.is_empty() {
+                        deferred_logs.push("local path is required".to_string());
+                    } else {
+                        if !paths::exists(&base, &source) {
+                            deferred_logs.push(format!(
+                                    "warning: {source} does not exist yet, press c when writing to create it"
+                                ));
                         }
                         let target = if target_input.trim().is_empty() {
-                            default_mount_target()
+                            default_mount_target(app.images.get(*image_index))
                         } else {
                             target_input.trim().to_string()
                         };
 
-                        if !app.volumes.iter().any(|volume| volume.name == source) {
-                            app.volumes.push(VolumeEntry {
-                                name: source.clone(),
-                            });
-                            app.volumes_selected = app.volumes.len() - 1;
-                        }
-
                         if let Some(image) = app.images.get_mut(*image_index) {
-                            image.mounts.push(VolumeMount {
-                                source: source.clone(),
-                                target: target.clone(),
-                            });
+                            image
+                                .mounts
+                                .push(VolumeMount::new(source.clone(), target.clone()));
                             deferred_logs.push(format!(
-                                "mounted new volume {source}:{target} on {}",
+                                "mounted local path {source}:{target} on {}",
                                 image.service_name
                             ));
                             close_modal = true;
                         }
                     }
-                    _ => {}
-                },
-                ModalState::MountLocalPath {
-                    image_index,
-                    local_path_input,
-                    target_input,
-                    active_field,
-                    local_path_typed,
-                    target_typed,
-                } => match key_code {
-                    KeyCode::Backspace => match active_field {
-                        MountInputField::Source => {
-                            local_path_input.pop();
-                            *local_path_typed = true;
+                }
+                _ => {}
+            },
+            ModalState::DeleteVolume {
+                volume_index,
+                selected_option,
+            } => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected_option = selected_option.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let in_use = app
+                        .volumes
+                        .get(*volume_index)
+                        .is_some_and(|volume| !app.volume_usage(&volume.name).is_empty());
+                    if in_use {
+                        *selected_option =
+                            (*selected_option + 1).min(app.volumes.len().saturating_sub(1));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    if *volume_index < app.volumes.len() {
+                        let name = app.volumes[*volume_index].name.clone();
+                        let retarget = app
+                            .volumes
+                            .iter()
+                            .enumerate()
+                            .filter(|(index, _)| index != volume_index)
+                            .map(|(_, volume)| volume.name.clone())
+                            .nth(selected_option.wrapping_sub(1));
+                        match retarget {
+                            Some(target) => {
+                                let moved = app.retarget_volume(&name, &target);
+                                deferred_logs
+                                    .push(format!("moved {moved} mounts from {name} to {target}"));
+                            }
+                            None => {
+                                let removed = app.unmount_volume(&name);
+                                if removed > 0 {
+                                    deferred_logs
+                                        .push(format!("unmounted {name} from {removed} mounts"));
+                                }
+                            }
                         }
-                        MountInputField::Target => {
-                            target_input.pop();
-                            *target_typed = true;
+                        app.volumes.remove(*volume_index);
+                        if app.volumes.is_empty() {
+                            app.volumes_selected = 0;
+                        } else if app.volumes_selected >= app.volumes.len() {
+                            app.volumes_selected = app.volumes.len() - 1;
                         }
-                    },
-                    KeyCode::Char(ch) => match active_field {
-                        MountInputField::Source => {
-                            if ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '.') {
-                                if !*local_path_typed {
-                                    local_path_input.clear();
-                                    *local_path_typed = true;
-                                }
-                                local_path_input.push(ch);
+                        deferred_logs.push(format!("deleted volume {name}"));
+                    }
+                    close_modal = true;
+                }
+                KeyCode::Char('n') => {
+                    close_modal = true;
+                    deferred_logs.push("delete volume canceled".to_string());
+                }
+                _ => {}
+            },
+            ModalState::RemoveImageMount {
+                image_index,
+                selected_mount,
+            } => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected_mount = selected_mount.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if let Some(image) = app.images.get(*image_index) {
+                        *selected_mount =
+                            (*selected_mount + 1).min(image.mounts.len().saturating_sub(1));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    if let Some(image) = app.images.get_mut(*image_index) {
+                        if !image.mounts.is_empty() {
+                            let index = (*selected_mount).min(image.mounts.len().saturating_sub(1));
+                            let removed = image.mounts.remove(index);
+                            if !image.mounts.is_empty() && *selected_mount >= image.mounts.len() {
+                                *selected_mount = image.mounts.len() - 1;
                             }
+                            deferred_logs.push(format!(
+                                "removed mount {}:{} from {}",
+                                removed.source, removed.target, image.service_name
+                            ));
+                        } else {
+                            deferred_logs.push("selected image has no mounts".to_string());
+                        }
+                    }
+                    close_modal = true;
+                }
+                KeyCode::Char('n') => {
+                    close_modal = true;
+                    deferred_logs.push("unmount canceled".to_string());
+                }
+                _ => {}
+            },
+            ModalState::AddImageEnv {
+                image_index,
+                key_input,
+                value_input,
+                active_field,
+            } => match key_code {
+                KeyCode::Backspace => match active_field {
+                    EnvInputField::Key => {
+                        key_input.pop();
+                    }
+                    EnvInputField::Value => {
+                        value_input.pop();
+                    }
+                },
+                KeyCode::Char(ch) => match active_field {
+                    EnvInputField::Key => {
+                        if ch.is_ascii_alphanumeric() || ch == '_' {
+                            key_input.push(ch.to_ascii_uppercase());
+                        }
+                    }
+                    EnvInputField::Value => {
+                        value_input.push(ch);
+                    }
+                },
+                KeyCode::Tab => {
+                    *active_field = active_field.next();
+                }
+                KeyCode::Enter => {
+                    let key = key_input.trim().to_ascii_uppercase();
+                    if key.is_empty() {
+                        deferred_logs.push("env variable name is required".to_string());
+                    } else if let Some(image) = app.images.get_mut(*image_index) {
+                        let value = value_input.clone();
+                        if secrets::is_secret_key(&key)
+                            && !value.is_empty()
+                            && !secrets::is_interpolated(&value)
+                        {
+                            deferred_logs.push(format!(
+                                    "warning: {key} looks like a secret; press s to generate one instead"
+                                ));
+                        }
+                        if let Some(existing) = image.env_vars.iter_mut().find(|env| env.key == key)
+                        {
+                            existing.value = value.clone();
+                            deferred_logs
+                                .push(format!("updated env {key} on {}", image.service_name));
+                        } else {
+                            image.env_vars.push(EnvVar {
+                                key: key.clone(),
+                                value: value.clone(),
+                            });
+                            deferred_logs
+                                .push(format!("added env {key} on {}", image.service_name));
                         }
-                        MountInputField::Target => {
-                            if ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '.') {
-                                if !*target_typed {
-                                    target_input.clear();
-                                    *target_typed = true;
+                        close_modal = true;
+                    }
+                }
+                _ => {}
+            },
+            ModalState::ImportEnvFile {
+                image_index,
+                path_input,
+                mode,
+                required,
+                raw,
+                active_field,
+                path_assist,
+            } => match key_code {
+                _ if matches!(active_field, EnvFileField::Path)
+                    && handle_path_assist(
+                        path_assist,
+                        path_input,
+                        &app.project_dir(),
+                        key_code,
+                    ) => {}
+                KeyCode::Tab => *active_field = active_field.next(),
+                KeyCode::Char(ch) if matches!(active_field, EnvFileField::Path) => {
+                    path_input.push(ch)
+                }
+                KeyCode::Backspace if matches!(active_field, EnvFileField::Path) => {
+                    path_input.pop();
+                }
+                KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right => match active_field {
+                    EnvFileField::Mode => *mode = mode.next(),
+                    EnvFileField::Required => *required = !*required,
+                    EnvFileField::Format => *raw = !*raw,
+                    EnvFileField::Path => {}
+                },
+                KeyCode::Enter => {
+                    let base = app.project_dir();
+                    let path = paths::normalize(&base, path_input);
+                    if let Some(image) = app.images.get_mut(*image_index) {
+                        match mode {
+                            EnvFileMode::Import => {
+                                let resolved = paths::resolve(&base, &path);
+                                if resolved.is_file() {
+                                    let vars = env_files::read_env_file(&resolved, false);
+                                    deferred_logs.extend(env_files::import(image, vars, &path));
+                                    close_modal = true;
+                                } else {
+                                    deferred_logs.push(format!("{path} is not a file"));
                                 }
-                                target_input.push(ch);
+                            }
+                            EnvFileMode::Reference => {
+                                let file = EnvFile {
+                                    path,
+                                    required: *required,
+                                    raw: *raw,
+                                };
+                                deferred_logs.extend(env_files::reference(image, file, &base));
+                                close_modal = true;
                             }
                         }
-                    },
+                    }
+                }
+                _ => {}
+            },
+            ModalState::RemoveImageEnv {
+                image_index,
+                selected_env,
+            } => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected_env = selected_env.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if let Some(image) = app.images.get(*image_index) {
+                        let total = image.env_vars.len() + image.env_files.len();
+                        *selected_env = (*selected_env + 1).min(total.saturating_sub(1));
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    if let Some(image) = app.images.get_mut(*image_index) {
+                        let inline = image.env_vars.len();
+                        if *selected_env < inline {
+                            let removed = image.env_vars.remove(*selected_env);
+                            deferred_logs.push(format!(
+                                "removed env {} from {}",
+                                removed.key, image.service_name
+                            ));
+                        } else if *selected_env - inline < image.env_files.len() {
+                            let removed = image.env_files.remove(*selected_env - inline);
+                            deferred_logs.push(format!(
+                                "removed env_file {} from {}",
+                                removed.path, image.service_name
+                            ));
+                        } else {
+                            deferred_logs.push("selected image has no env vars".to_string());
+                        }
+                    }
+                    close_modal = true;
+                }
+                KeyCode::Char('n') => {
+                    close_modal = true;
+                    deferred_logs.push("remove env canceled".to_string());
+                }
+                _ => {}
+            },
+            ModalState::SetImageCommand {
+                image_index,
+                field,
+                command,
+                entrypoint,
+                input,
+            } => {
+                let draft = match field {
+                    CommandField::Command => &mut *command,
+                    CommandField::Entrypoint => &mut *entrypoint,
+                };
+                match key_code {
+                    KeyCode::BackTab => {
+                        if draft.exec && !input.trim().is_empty() {
+                            draft.insert(input);
+                        }
+                        input.clear();
+                        *field = field.next();
+                    }
                     KeyCode::Tab => {
-                        *active_field = active_field.next();
+                        if draft.exec && !input.trim().is_empty() {
+                            draft.insert(input);
+                            input.clear();
+                        }
+                        draft.toggle_form();
+                    }
+                    KeyCode::Char(ch) if draft.exec => input.push(ch),
+                    KeyCode::Char(ch) => draft.shell.push(ch),
+                    KeyCode::Backspace if !draft.exec => {
+                        draft.shell.pop();
+                    }
+                    KeyCode::Backspace if input.is_empty() => {
+                        if let Some(arg) = draft.take_selected() {
+                            *input = arg;
+                        }
+                    }
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Up if draft.exec => draft.select(true),
+                    KeyCode::Down if draft.exec => draft.select(false),
+                    KeyCode::PageUp if draft.exec => draft.move_selected(true),
+                    KeyCode::PageDown if draft.exec => draft.move_selected(false),
+                    KeyCode::Delete if draft.exec => {
+                        if let Some(arg) = draft.remove_selected() {
+                            deferred_logs.push(format!("removed arg {arg}"));
+                        }
+                    }
+                    KeyCode::Enter if draft.exec && !input.trim().is_empty() => {
+                        let count = draft.insert(input);
+                        input.clear();
+                        if count > 1 {
+                            deferred_logs.push(format!("split into {count} args"));
+                        }
                     }
                     KeyCode::Enter => {
-                        let source = local_path_input.trim().to_string();
-                        if !(source.starts_with("./") || source.starts_with('/')) {
-                            deferred_logs.push("local path must start with ./ or /".to_string());
-                        } else {
-                            let target = if target_input.trim().is_empty() {
-                                default_mount_target()
-                            } else {
-                                target_input.trim().to_string()
-                            };
-
-                            if let Some(image) = app.images.get_mut(*image_index) {
-                                image.mounts.push(VolumeMount {
-                                    source: source.clone(),
-                                    target: target.clone(),
-                                });
+                        if let Some(image) = app.images.get_mut(*image_index) {
+                            image.command = command.spec();
+                            image.options.entrypoint = entrypoint.spec();
+                            deferred_logs.push(format!(
+                                "updated command and entrypoint on {}",
+                                image.service_name
+                            ));
+                        }
+                        close_modal = true;
+                    }
+                    _ => {}
+                }
+            }
+            ModalState::GenerateSecret {
+                image_index,
+                key_input,
+                storage,
+            } => match key_code {
+                KeyCode::Char(ch) if ch.is_ascii_alphanumeric() || ch == '_' => {
+                    key_input.push(ch.to_ascii_uppercase());
+                }
+                KeyCode::Backspace => {
+                    key_input.pop();
+                }
+                KeyCode::Tab => {
+                    *storage = storage.next();
+                }
+                KeyCode::Enter => {
+                    let key = key_input.trim().to_ascii_uppercase();
+                    if key.is_empty() {
+                        deferred_logs.push("secret variable name is required".to_string());
+                    } else if let Some(image) = app.images.get_mut(*image_index) {
+                        let value = secrets::generate_secret(32);
+                        match storage {
+                            SecretStorage::DotEnv => {
+                                let variable =
+                                    secrets::interpolation_name(&image.service_name, &key);
+                                upsert_env(&mut image.env_vars, &key, format!("${{{variable}}}"));
+                                upsert_env(&mut app.dotenv, &variable, value);
                                 deferred_logs.push(format!(
-                                    "mounted local path {source}:{target} on {}",
+                                    "generated {key} for {} in .env as {variable}",
+                                    image.service_name
+                                ));
+                            }
+                            SecretStorage::File => {
+                                let name = secrets::secret_name(&image.service_name, &key);
+                                image.env_vars.retain(|env| env.key != key);
+                                upsert_env(
+                                    &mut image.env_vars,
+                                    &format!("{key}_FILE"),
+                                    format!("/run/secrets/{name}"),
+                                );
+                                if !image.secrets.contains(&name) {
+                                    image.secrets.push(name.clone());
+                                }
+                                let entry = SecretEntry {
+                                    name: name.clone(),
+                                    file: secrets::secret_file_path(&name),
+                                    value,
+                                };
+                                match app.secrets.iter_mut().find(|secret| secret.name == name) {
+                                    Some(existing) => *existing = entry,
+                                    None => app.secrets.push(entry),
+                                }
+                                deferred_logs.push(format!(
+                                    "generated secret {name} for {}",
                                     image.service_name
                                 ));
-                                close_modal = true;
                             }
                         }
+                        close_modal = true;
                     }
-                    _ => {}
-                },
-                ModalState::RemoveImageMount {
-                    image_index,
-                    selected_mount,
-                } => match key_code {
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if *selected_mount > 0 {
-                            *selected_mount -= 1;
-                        }
+                }
+                _ => {}
+            },
+            ModalState::ServiceProperties {
+                image_index,
+                sheet,
+                selected,
+                input,
+            } => {
+                let properties = sheet.properties();
+                let property = properties[(*selected).min(properties.len() - 1)];
+
+                match (input.as_mut(), key_code) {
+                    (Some(buffer), KeyCode::Char(ch)) => buffer.push(ch),
+                    (Some(buffer), KeyCode::Backspace) => {
+                        buffer.pop();
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if let Some(image) = app.images.get(*image_index) {
-                            if !image.mounts.is_empty() {
-                                *selected_mount = (*selected_mount + 1).min(image.mounts.len() - 1);
+                    (Some(buffer), KeyCode::Enter) => {
+                        if let Some(image) = app.images.get_mut(*image_index) {
+                            match property.apply(image, buffer) {
+                                Ok(()) => {
+                                    deferred_logs.push(format!(
+                                        "set {} on {}",
+                                        property.label(),
+                                        image.service_name
+                                    ));
+                                    *input = None;
+                                }
+                                Err(error) => deferred_logs.push(error),
                             }
                         }
                     }
-                    KeyCode::Enter | KeyCode::Char('y') => {
+                    (None, KeyCode::Up | KeyCode::Char('k')) => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    (None, KeyCode::Down | KeyCode::Char('j')) => {
+                        *selected = (*selected + 1).min(properties.len() - 1);
+                    }
+                    (None, KeyCode::Tab) => {
+                        *sheet = sheet.next();
+                        *selected = 0;
+                    }
+                    (None, KeyCode::Enter) => {
                         if let Some(image) = app.images.get_mut(*image_index) {
-                            if !image.mounts.is_empty() {
-                                let index = (*selected_mount).min(image.mounts.len().saturating_sub(1));
-                                let removed = image.mounts.remove(index);
-                                if !image.mounts.is_empty() && *selected_mount >= image.mounts.len() {
-                                    *selected_mount = image.mounts.len() - 1;
-                                }
+                            if property.is_toggle() {
+                                property.toggle(image);
                                 deferred_logs.push(format!(
-                                    "removed mount {}:{} from {}",
-                                    removed.source, removed.target, image.service_name
+                                    "{} = {} on {}",
+                                    property.label(),
+                                    property.display(image),
+                                    image.service_name
                                 ));
                             } else {
-                                deferred_logs.push("selected image has no mounts".to_string());
+                                *input = Some(property.display(image));
                             }
                         }
-                        close_modal = true;
                     }
-                    KeyCode::Char('n') => {
-                        close_modal = true;
-                        deferred_logs.push("unmount canceled".to_string());
+                    (None, KeyCode::Char('d') | KeyCode::Backspace) => {
+                        if let Some(image) = app.images.get_mut(*image_index) {
+                            if property.is_toggle() {
+                                if property.display(image) == "yes" {
+                                    property.toggle(image);
+                                }
+                            } else if let Err(error) = property.apply(image, "") {
+                                deferred_logs.push(error);
+                            }
+                            deferred_logs.push(format!(
+                                "cleared {} on {}",
+                                property.label(),
+                                image.service_name
+                            ));
+                        }
                     }
                     _ => {}
-                },
-                ModalState::AddImageEnv {
-                    image_index,
-                    key_input,
-                    value_input,
-                    active_field,
-                } => match key_code {
-                    KeyCode::Backspace => match active_field {
-                        EnvInputField::Key => {
-                            key_input.pop();
-                        }
-                        EnvInputField::Value => {
-                            value_input.pop();
-                        }
-                    },
-                    KeyCode::Char(ch) => match active_field {
-                        EnvInputField::Key => {
-                            if ch.is_ascii_alphanumeric() || ch == '_' {
-                                key_input.push(ch.to_ascii_uppercase());
+                }
+            }
+            ModalState::VolumeProperties {
+                volume_index,
+                selected,
+                input,
+            } => {
+                let properties = VolumeProperty::all();
+                let property = properties[(*selected).min(properties.len() - 1)];
+
+                match (input.as_mut(), key_code) {
+                    (Some(buffer), KeyCode::Char(ch)) => buffer.push(ch),
+                    (Some(buffer), KeyCode::Backspace) => {
+                        buffer.pop();
+                    }
+                    (Some(buffer), KeyCode::Enter) => {
+                        if let Some(volume) = app.volumes.get_mut(*volume_index) {
+                            match property.apply(volume, buffer) {
+                                Ok(()) => {
+                                    deferred_logs.push(format!(
+                                        "set {} on volume {}",
+                                        property.label(),
+                                        volume.name
+                                    ));
+                                    *input = None;
+                                }
+                                Err(error) => deferred_logs.push(error),
                             }
                         }
-                        EnvInputField::Value => {
-                            value_input.push(ch);
-                        }
-                    },
-                    KeyCode::Tab => {
-                        *active_field = active_field.next();
                     }
-                    KeyCode::Enter => {
-                        let key = key_input.trim().to_ascii_uppercase();
-                        if key.is_empty() {
-                            deferred_logs.push("env variable name is required".to_string());
-                        } else if let Some(image) = app.images.get_mut(*image_index) {
-                            let value = value_input.clone();
-                            if let Some(existing) =
-                                image.env_vars.iter_mut().find(|env| env.key == key)
-                            {
-                                existing.value = value.clone();
+                    (None, KeyCode::Up | KeyCode::Char('k')) => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    (None, KeyCode::Down | KeyCode::Char('j')) => {
+                        *selected = (*selected + 1).min(properties.len() - 1);
+                    }
+                    (None, KeyCode::Enter) => {
+                        if let Some(volume) = app.volumes.get_mut(*volume_index) {
+                            if property.is_toggle() {
+                                property.toggle(volume);
                                 deferred_logs.push(format!(
-                                    "updated env {key} on {}",
-                                    image.service_name
+                                    "{} = {} on volume {}",
+                                    property.label(),
+                                    property.display(volume),
+                                    volume.name
                                 ));
                             } else {
-                                image.env_vars.push(EnvVar {
-                                    key: key.clone(),
-                                    value: value.clone(),
-                                });
-                                deferred_logs.push(format!(
-                                    "added env {key} on {}",
-                                    image.service_name
-                                ));
+                                *input = Some(property.display(volume));
                             }
-                            close_modal = true;
                         }
                     }
-                    _ => {}
-                },
-                ModalState::RemoveImageEnv {
-                    image_index,
-                    selected_env,
-                } => match key_code {
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if *selected_env > 0 {
-                            *selected_env -= 1;
+                    (None, KeyCode::Char('d') | KeyCode::Backspace) => {
+                        if let Some(volume) = app.volumes.get_mut(*volume_index) {
+                            if let Err(error) = property.apply(volume, "") {
+                                deferred_logs.push(error);
+                            }
+                            deferred_logs.push(format!(
+                                "cleared {} on volume {}",
+                                property.label(),
+                                volume.name
+                            ));
                         }
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if let Some(image) = app.images.get(*image_index) {
-                            if !image.env_vars.is_empty() {
-                                *selected_env = (*selected_env + 1).min(image.env_vars.len() - 1);
+                    _ => {}
+                }
+            }
+            ModalState::MountProperties {
+                image_index,
+                mount_index,
+                selected,
+                input,
+            } => {
+                let properties = MountProperty::all();
+                let property = properties[(*selected).min(properties.len() - 1)];
+                let Some(image) = app.images.get_mut(*image_index) else {
+                    return LoopControl::Continue;
+                };
+
+                match (input.as_mut(), key_code) {
+                    (Some(buffer), KeyCode::Char(ch)) => buffer.push(ch),
+                    (Some(buffer), KeyCode::Backspace) => {
+                        buffer.pop();
+                    }
+                    (Some(buffer), KeyCode::Enter) => {
+                        if let Some(mount) = image.mounts.get_mut(*mount_index) {
+                            match property.apply(mount, buffer) {
+                                Ok(()) => {
+                                    deferred_logs.push(format!(
+                                        "set {} on {} mount {}",
+                                        property.label(),
+                                        image.service_name,
+                                        mount.container_path()
+                                    ));
+                                    *input = None;
+                                }
+                                Err(error) => deferred_logs.push(error),
                             }
                         }
                     }
-                    KeyCode::Enter | KeyCode::Char('y') => {
-                        if let Some(image) = app.images.get_mut(*image_index) {
-                            if !image.env_vars.is_empty() {
-                                let index = (*selected_env).min(image.env_vars.len() - 1);
-                                let removed = image.env_vars.remove(index);
-                                if !image.env_vars.is_empty() && *selected_env >= image.env_vars.len() {
-                                    *selected_env = image.env_vars.len() - 1;
-                                }
+                    (None, KeyCode::Up | KeyCode::Char('k')) => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    (None, KeyCode::Down | KeyCode::Char('j')) => {
+                        *selected = (*selected + 1).min(properties.len() - 1);
+                    }
+                    (None, KeyCode::Tab) if !image.mounts.is_empty() => {
+                        *mount_index = (*mount_index + 1) % image.mounts.len();
+                    }
+                    (None, KeyCode::BackTab) if !image.mounts.is_empty() => {
+                        *mount_index = (*mount_index + image.mounts.len() - 1) % image.mounts.len();
+                    }
+                    (None, KeyCode::Char('t')) => {
+                        image.mounts.push(VolumeMount::tmpfs("/tmp"));
+                        *mount_index = image.mounts.len() - 1;
+                        *selected = MountProperty::all()
+                            .iter()
+                            .position(|candidate| *candidate == MountProperty::Target)
+                            .unwrap_or(0);
+                        deferred_logs.push(format!("added tmpfs mount to {}", image.service_name));
+                    }
+                    (None, KeyCode::Enter) => {
+                        if let Some(mount) = image.mounts.get_mut(*mount_index) {
+                            if property.is_toggle() {
+                                property.toggle(mount);
                                 deferred_logs.push(format!(
-                                    "removed env {} from {}",
-                                    removed.key, image.service_name
+                                    "{} = {} on {} mount {}",
+                                    property.label(),
+                                    property.display(mount),
+                                    image.service_name,
+                                    mount.container_path()
                                 ));
                             } else {
-                                deferred_logs.push("selected image has no env vars".to_string());
+                                *input = Some(match property {
+                                    MountProperty::Type => mount
+                                        .options
+                                        .kind
+                                        .map(|kind| kind.label().to_string())
+                                        .unwrap_or_default(),
+                                    _ => property.display(mount),
+                                });
                             }
                         }
-                        close_modal = true;
                     }
-                    KeyCode::Char('n') => {
-                        close_modal = true;
-                        deferred_logs.push("remove env canceled".to_string());
+                    (None, KeyCode::Char('d') | KeyCode::Backspace) => {
+                        if let Some(mount) = image.mounts.get_mut(*mount_index) {
+                            let result = if property.is_toggle() {
+                                property.apply(mount, "no")
+                            } else {
+                                property.apply(mount, "")
+                            };
+                            match result {
+                                Ok(()) => deferred_logs.push(format!(
+                                    "cleared {} on {} mount {}",
+                                    property.label(),
+                                    image.service_name,
+                                    mount.container_path()
+                                )),
+                                Err(error) => deferred_logs.push(error),
+                            }
+                        }
                     }
                     _ => {}
-                },
-                ModalState::SetImageCommand { image_index, input } => match key_code {
-                    KeyCode::Char(ch) => {
-                        if is_shell_command_wrapper(input) {
-                            let insert_at = input.len().saturating_sub(1);
-                            input.insert(insert_at, ch);
+                }
+            }
+            ModalState::DuplicateService {
+                image_index,
+                name_input,
+                clone_volumes,
+            } => match key_code {
+                KeyCode::Char(ch) if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' => {
+                    name_input.push(ch)
+                }
+                KeyCode::Backspace => {
+                    name_input.pop();
+                }
+                KeyCode::Tab | KeyCode::BackTab => *clone_volumes = !*clone_volumes,
+                KeyCode::Enter => {
+                    let name = name_input.trim();
+                    if name.is_empty() {
+                        deferred_logs.push("service name is required".to_string());
+                    } else {
+                        deferred_logs.extend(app.duplicate_service(
+                            *image_index,
+                            name,
+                            *clone_volumes,
+                        ));
+                        close_modal = true;
+                    }
+                }
+                _ => {}
+            },
+            ModalState::ServiceDetail {
+                image_index,
+                section,
+                selected,
+                marked,
+                input,
+                adding,
+            } => {
+                let services: Vec<String> = app
+                    .images
+                    .iter()
+                    .map(|image| image.service_name.clone())
+                    .collect();
+                let secrets: Vec<String> = app
+                    .secrets
+                    .iter()
+                    .map(|secret| secret.name.clone())
+                    .collect();
+                let Some(image) = app.images.get_mut(*image_index) else {
+                    return LoopControl::Continue;
+                };
+                let count = section.items(image).len();
+
+                match (input.as_mut(), key_code) {
+                    (Some(buffer), KeyCode::Char(ch)) => buffer.push(ch),
+                    (Some(buffer), KeyCode::Backspace) => {
+                        buffer.pop();
+                    }
+                    (Some(buffer), KeyCode::Enter) => {
+                        let index = if *adding { None } else { Some(*selected) };
+                        match section.apply(image, index, buffer, &services, &secrets) {
+                            Ok(message) => {
+                                deferred_logs.push(format!("{}: {message}", image.service_name));
+                                if *adding {
+                                    *selected = section.items(image).len().saturating_sub(1);
+                                }
+                                *input = None;
+                                *adding = false;
+                                if *section == DetailSection::Mounts {
+                                    let image = image.clone();
+                                    app.ensure_named_volumes(&image);
+                                }
+                            }
+                            Err(error) => deferred_logs.push(error),
+                        }
+                    }
+                    (None, KeyCode::Tab) => {
+                        *section = section.next();
+                        *selected = 0;
+                        marked.clear();
+                    }
+                    (None, KeyCode::BackTab) => {
+                        *section = section.previous();
+                        *selected = 0;
+                        marked.clear();
+                    }
+                    (None, KeyCode::Up | KeyCode::Char('k')) => {
+                        *selected = selected.saturating_sub(1);
+                    }
+                    (None, KeyCode::Down | KeyCode::Char('j')) => {
+                        *selected = (*selected + 1).min(count.saturating_sub(1));
+                    }
+                    (None, KeyCode::Char('K' | 'J')) if section.can_reorder() => {
+                        let up = key_code == KeyCode::Char('K');
+                        if let Some(target) = section.move_item(image, *selected, up) {
+                            *selected = target;
+                            marked.clear();
+                        }
+                    }
+                    (None, KeyCode::Char(' ')) if *selected < count => {
+                        match marked.iter().position(|index| index == selected) {
+                            Some(position) => {
+                                marked.remove(position);
+                            }
+                            None => marked.push(*selected),
+                        }
+                    }
+                    (None, KeyCode::Char('a')) => {
+                        if section.can_add() {
+                            *input = Some(String::new());
+                            *adding = true;
+                        } else if *section == DetailSection::Settings {
+                            deferred_logs.push(
+                                "use o to set properties that are not configured yet".to_string(),
+                            );
                         } else {
-                            input.push(ch);
+                            deferred_logs
+                                .push("command and entrypoint are edited with Enter".to_string());
                         }
                     }
-                    KeyCode::Backspace => {
-                        if is_shell_command_wrapper(input) && input.len() > "sh -c \"\"".len() {
-                            let remove_at = input.len().saturating_sub(2);
-                            input.remove(remove_at);
+                    (None, KeyCode::Enter) => {
+                        if let Some(message) = section.toggles(image, *selected) {
+                            deferred_logs.push(format!("{}: {message}", image.service_name));
+                        } else if let Some(text) = section.edit_text(image, *selected) {
+                            *input = Some(text);
+                            *adding = false;
+                        } else if section.can_add() {
+                            *input = Some(String::new());
+                            *adding = true;
+                        }
+                    }
+                    (None, KeyCode::Char('d') | KeyCode::Delete) if count > 0 => {
+                        let targets = if marked.is_empty() {
+                            vec![*selected]
                         } else {
-                            input.pop();
+                            std::mem::take(marked)
+                        };
+                        let removed = section.remove(image, &targets);
+                        deferred_logs.push(format!(
+                            "{}: removed {removed} from {}",
+                            image.service_name,
+                            section.label().to_lowercase()
+                        ));
+                        *selected = (*selected).min(section.items(image).len().saturating_sub(1));
+                    }
+                    _ => {}
+                }
+            }
+            ModalState::AddEnvironment { input } => match key_code {
+                KeyCode::Char(ch) => input.push(ch),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let name = input.trim();
+                    if !environments::is_valid_environment_name(name) {
+                        deferred_logs
+                            .push("environment names use letters, digits, - and _".to_string());
+                    } else if app
+                        .environments
+                        .iter()
+                        .any(|environment| environment.name == name)
+                    {
+                        deferred_logs.push(format!("environment {name} already exists"));
+                    } else {
+                        let environment = Environment::new(name);
+                        deferred_logs.push(format!(
+                            "added environment {name} ({})",
+                            environment.file_name()
+                        ));
+                        app.environments.push(environment);
+                        app.active_environment = Some(app.environments.len() - 1);
+                        close_modal = true;
+                    }
+                }
+                _ => {}
+            },
+            ModalState::EditOverride {
+                image_index,
+                environment_index,
+                port_input,
+                env_input,
+                mounts_input,
+                replicas_input,
+                active_field,
+            } => match key_code {
+                KeyCode::Tab => {
+                    *active_field = active_field.next();
+                }
+                KeyCode::Char(_) | KeyCode::Backspace => {
+                    let field_input = match active_field {
+                        OverrideField::Ports => port_input,
+                        OverrideField::Env => env_input,
+                        OverrideField::Mounts => mounts_input,
+                        OverrideField::Replicas => replicas_input,
+                    };
+                    match key_code {
+                        KeyCode::Char(ch)
+                            if !matches!(active_field, OverrideField::Replicas)
+                                || ch.is_ascii_digit() =>
+                        {
+                            field_input.push(ch)
                         }
+                        KeyCode::Backspace => {
+                            field_input.pop();
+                        }
+                        _ => {}
                     }
-                    KeyCode::Enter => {
-                        if let Some(image) = app.images.get_mut(*image_index) {
-                            let raw = input.clone();
-                            if raw.trim().is_empty() {
-                                image.command = None;
-                                deferred_logs.push(format!(
-                                    "cleared command on {}",
-                                    image.service_name
-                                ));
-                            } else {
-                                image.command = Some(raw);
+                }
+                KeyCode::Enter => {
+                    let port_mapping = match port_input.trim() {
+                        "" => Ok(None),
+                        "none" => Ok(Some(String::new())),
+                        mapping => {
+                            ports::validate_mapping(mapping).map(|()| Some(mapping.to_string()))
+                        }
+                    };
+                    let mounts: Result<Vec<VolumeMount>, String> =
+                        properties::parse_list(mounts_input)
+                            .into_iter()
+                            .map(|mount| match mount.split_once(':') {
+                                Some((source, target)) => {
+                                    Ok(VolumeMount::new(source.to_string(), target.to_string()))
+                                }
+                                None => Err(format!("expected source:target, got '{mount}'")),
+                            })
+                            .collect();
+                    let env_vars = properties::parse_key_values(env_input);
+
+                    match (port_mapping, env_vars, mounts) {
+                        (Ok(port_mapping), Ok(env_vars), Ok(mounts)) => {
+                            let service = app
+                                .images
+                                .get(*image_index)
+                                .map(|image| image.service_name.clone())
+                                .unwrap_or_default();
+                            if let Some(environment) = app.environments.get_mut(*environment_index)
+                            {
+                                environment.set_override(ServiceOverride {
+                                    service: service.clone(),
+                                    port_mapping,
+                                    env_vars: env_vars
+                                        .into_iter()
+                                        .map(|pair| EnvVar {
+                                            key: pair.key,
+                                            value: pair.value,
+                                        })
+                                        .collect(),
+                                    mounts,
+                                    replicas: replicas_input.trim().parse().ok(),
+                                });
                                 deferred_logs.push(format!(
-                                    "updated command on {}",
-                                    image.service_name
+                                    "updated {} override for {service}",
+                                    environment.name
                                 ));
                             }
+                            close_modal = true;
+                        }
+                        (Err(error), _, _) | (_, Err(error), _) | (_, _, Err(error)) => {
+                            deferred_logs.push(format!("invalid override: {error}"));
+                        }
+                    }
+                }
+                _ => {}
+            },
+            ModalState::ReviewScan {
+                proposals,
+                selected,
+                ..
+            } => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected = selected.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    *selected = (*selected + 1).min(proposals.len().saturating_sub(1));
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(proposal) = proposals.get_mut(*selected) {
+                        proposal.accepted = !proposal.accepted;
+                    }
+                }
+                KeyCode::Enter => {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build();
+                    let tags = match runtime {
+                        Ok(runtime) => runtime.block_on(scan::resolve_proposal_tags(proposals)),
+                        Err(error) => {
+                            deferred_logs
+                                .push(format!("runtime error for tag resolution: {error}"));
+                            Vec::new()
+                        }
+                    };
+                    deferred_logs.extend(scan::apply_proposals(app, proposals, &tags));
+                    close_modal = true;
+                }
+                _ => {}
+            },
+            ModalState::EditBuild {
+                existing_index,
+                context_input,
+                dockerfile_input,
+                target_input,
+                args_input,
+                cache_from_input,
+                image_input,
+                service_name_input,
+                active_field,
+                context_assist,
+            } => match key_code {
+                _ if matches!(active_field, BuildField::Context)
+                    && handle_path_assist(
+                        context_assist,
+                        context_input,
+                        &app.project_dir(),
+                        key_code,
+                    ) => {}
+                KeyCode::Tab => {
+                    *active_field = active_field.next();
+                }
+                KeyCode::Char(_) | KeyCode::Backspace => {
+                    let field_input = match active_field {
+                        BuildField::Context => context_input,
+                        BuildField::Dockerfile => dockerfile_input,
+                        BuildField::Target => target_input,
+                        BuildField::Args => args_input,
+                        BuildField::CacheFrom => cache_from_input,
+                        BuildField::Image => image_input,
+                        BuildField::Name => service_name_input,
+                    };
+                    if let KeyCode::Char(ch) = key_code {
+                        field_input.push(ch);
+                    } else {
+                        field_input.pop();
+                    }
+                }
+                KeyCode::Enter => {
+                    let context = paths::normalize(&app.project_dir(), context_input);
+                    let context = context.as_str();
+                    let optional = |value: &str| {
+                        let trimmed = value.trim();
+                        (!trimmed.is_empty()).then(|| trimmed.to_string())
+                    };
+
+                    if context.is_empty() {
+                        deferred_logs.push("build context is required".to_string());
+                    } else {
+                        match properties::parse_key_values(args_input) {
+                            Err(error) => {
+                                deferred_logs.push(format!("invalid build args: {error}"));
+                            }
+                            Ok(args) => {
+                                let config = BuildConfig {
+                                    context: context.to_string(),
+                                    dockerfile: optional(dockerfile_input),
+                                    target: optional(target_input),
+                                    args,
+                                    cache_from: properties::parse_list(cache_from_input),
+                                };
+                                let mut image = existing_index
+                                    .and_then(|index| app.images.get(index))
+                                    .cloned()
+                                    .unwrap_or_default();
+                                let (namespace, repo, tag) =
+                                    build::split_image_ref(image_input).unwrap_or_default();
+                                image.namespace = namespace;
+                                image.repo = repo;
+                                image.tag = tag;
+                                let name = optional(service_name_input)
+                                    .unwrap_or_else(|| build::service_name_for_context(context));
+                                let name = default_service_name(&name, app.images.len());
+                                let current = existing_index
+                                    .and_then(|index| app.images.get(index))
+                                    .map(|image| image.service_name.clone());
+                                image.service_name = if current.as_deref() == Some(name.as_str()) {
+                                    name
+                                } else {
+                                    app.unique_service_name(&name)
+                                };
+                                if let Some(current) = current.as_deref() {
+                                    app.rename_service(current, &image.service_name);
+                                }
+
+                                if !Path::new(context).is_dir() {
+                                    deferred_logs.push(format!(
+                                        "warning: build context {context} does not exist"
+                                    ));
+                                }
+                                if existing_index.is_none() {
+                                    let stage = config
+                                        .read_dockerfile()
+                                        .map_err(|error| format!("Dockerfile not read: {error}"))
+                                        .and_then(|info| {
+                                            build::select_stage(&info, config.target.as_deref())
+                                                .cloned()
+                                        });
+                                    match stage {
+                                        Ok(stage) => {
+                                            if let Some(port) = stage.exposed_ports.first() {
+                                                app.refresh_listening_ports();
+                                                image.port_mapping =
+                                                    app.suggested_port_mapping(Some(*port));
+                                                deferred_logs
+                                                    .push(format!("Dockerfile exposes {port}"));
+                                            }
+                                            deferred_logs.extend(build::prefill_from_stage(
+                                                &mut image, &stage,
+                                            ));
+                                            app.ensure_named_volumes(&image);
+                                        }
+                                        Err(error) => deferred_logs.push(error),
+                                    }
+                                }
+                                image.build = Some(config);
+
+                                let description = image.describe();
+                                match existing_index.and_then(|index| app.images.get_mut(index)) {
+                                    Some(slot) => {
+                                        *slot = image;
+                                        deferred_logs
+                                            .push(format!("updated build for {description}"));
+                                    }
+                                    None => {
+                                        app.images.push(image);
+                                        app.images_selected = app.images.len() - 1;
+                                        deferred_logs
+                                            .push(format!("added build service {description}"));
+                                    }
+                                }
+                                close_modal = true;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            },
+            ModalState::EditHealthcheck {
+                image_index,
+                form,
+                test_input,
+                interval_input,
+                timeout_input,
+                retries_input,
+                start_period_input,
+                disable,
+                active_field,
+                edited,
+            } => match key_code {
+                KeyCode::Tab => {
+                    *active_field = active_field.next();
+                }
+                KeyCode::Char(' ') if matches!(active_field, HealthcheckField::Form) => {
+                    *form = form.next();
+                    *edited = true;
+                }
+                KeyCode::Char(' ') if matches!(active_field, HealthcheckField::Disable) => {
+                    *disable = !*disable;
+                    *edited = true;
+                }
+                KeyCode::Char(ch) => {
+                    *edited = true;
+                    match active_field {
+                        HealthcheckField::Test => test_input.push(ch),
+                        HealthcheckField::Interval if ch.is_ascii_alphanumeric() => {
+                            interval_input.push(ch)
+                        }
+                        HealthcheckField::Timeout if ch.is_ascii_alphanumeric() => {
+                            timeout_input.push(ch)
+                        }
+                        HealthcheckField::StartPeriod if ch.is_ascii_alphanumeric() => {
+                            start_period_input.push(ch)
+                        }
+                        HealthcheckField::Retries if ch.is_ascii_digit() => retries_input.push(ch),
+                        _ => {}
+                    }
+                }
+                KeyCode::Backspace => {
+                    *edited = true;
+                    match active_field {
+                        HealthcheckField::Test => {
+                            test_input.pop();
+                        }
+                        HealthcheckField::Interval => {
+                            interval_input.pop();
+                        }
+                        HealthcheckField::Timeout => {
+                            timeout_input.pop();
+                        }
+                        HealthcheckField::Retries => {
+                            retries_input.pop();
+                        }
+                        HealthcheckField::StartPeriod => {
+                            start_period_input.pop();
+                        }
+                        HealthcheckField::Form | HealthcheckField::Disable => {}
+                    }
+                }
+                KeyCode::Enter => {
+                    let invalid = [
+                        ("interval", &*interval_input),
+                        ("timeout", &*timeout_input),
+                        ("start_period", &*start_period_input),
+                    ]
+                    .into_iter()
+                    .find(|(_, value)| {
+                        !value.trim().is_empty() && !healthcheck::is_valid_duration(value)
+                    });
+                    let optional = |value: &str| {
+                        let trimmed = value.trim();
+                        (!trimmed.is_empty()).then(|| trimmed.to_string())
+                    };
+
+                    if let Some((name, value)) = invalid {
+                        deferred_logs.push(format!(
+                            "invalid {name} '{value}' (use durations like 30s or 1m30s)"
+                        ));
+                    } else if let Some(image) = app.images.get_mut(*image_index) {
+                        if test_input.trim().is_empty() && !*disable {
+                            image.healthcheck = None;
+                            deferred_logs
+                                .push(format!("cleared healthcheck on {}", image.service_name));
+                        } else {
+                            image.healthcheck = Some(Healthcheck {
+                                form: *form,
+                                test: test_input.trim().to_string(),
+                                interval: optional(interval_input),
+                                timeout: optional(timeout_input),
+                                retries: retries_input.trim().parse().ok(),
+                                start_period: optional(start_period_input),
+                                disable: *disable,
+                            });
+                            deferred_logs
+                                .push(format!("updated healthcheck on {}", image.service_name));
                         }
                         close_modal = true;
                     }
-                    _ => {}
-                },
+                }
+                _ => {}
+            },
+            ModalState::SelectTemplate {
+                templates,
+                selected,
+            } => match key_code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    *selected = selected.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    *selected = (*selected + 1).min(templates.len().saturating_sub(1));
+                }
+                KeyCode::Enter => {
+                    if let Some(template) = templates.get(*selected) {
+                        let runtime = tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build();
+                        let tags = match runtime {
+                            Ok(runtime) => {
+                                runtime.block_on(templates::resolve_template_tags(template))
+                            }
+                            Err(error) => {
+                                deferred_logs
+                                    .push(format!("runtime error for tag resolution: {error}"));
+                                Vec::new()
+                            }
+                        };
+                        deferred_logs.extend(templates::apply_template(app, template, &tags));
+                    }
+                    close_modal = true;
+                }
+                _ => {}
             },
-            None => {}
         },
+        (_, None) => {}
     }
 
     if close_modal {
@@ -1128,3 +3128,199 @@ fn handle_modal_key(app: &mut App, key_code: KeyCode) -> LoopControl {
         LoopControl::Continue
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_name_accepts_only_service_key_characters() {
+        let mut app = App::new();
+        app.check_host_ports = false;
+        app.images.push(ImageEntry {
+            service_name: "web".to_string(),
+            ..ImageEntry::default()
+        });
+        app.modal = Some(ModalState::DuplicateService {
+            image_index: 0,
+            name_input: String::new(),
+            clone_volumes: false,
+        });
+        for ch in "a p/i:_2-x".chars() {
+            handle_key(&mut app, KeyCode::Char(ch));
+        }
+        assert!(matches!(
+            &app.modal,
+            Some(ModalState::DuplicateService { name_input, .. }) if name_input == "api_2-x"
+        ));
+
+        app.modal = Some(ModalState::DuplicateService {
+            image_index: 0,
+            name_input: String::new(),
+            clone_volumes: false,
+        });
+        handle_key(&mut app, KeyCode::Enter);
+        assert!(app.modal.is_some());
+        assert_eq!(app.images.len(), 1);
+    }
+
+    #[test]
+    fn build_services_get_sanitized_unique_names() {
+        let dir = std::env::temp_dir().join(format!("acdc-build-My App.v2-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let context = dir.to_string_lossy().to_string();
+        let mut app = App::new();
+        for _ in 0..2 {
+            app.modal = Some(edit_build_modal(None, None, &context));
+            handle_key(&mut app, KeyCode::Enter);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let expected = format!("acdc-build-my_app_v2-{}", std::process::id());
+        let names: Vec<&str> = app
+            .images
+            .iter()
+            .map(|image| image.service_name.as_str())
+            .collect();
+        assert_eq!(names, [expected.clone(), format!("{expected}_2")]);
+    }
+
+    #[test]
+    fn override_ports_are_validated_like_service_ports() {
+        let mut app = App::new();
+        app.images.push(ImageEntry {
+            service_name: "web".to_string(),
+            ..ImageEntry::default()
+        });
+        let edit = |port_input: &str| ModalState::EditOverride {
+            image_index: 0,
+            environment_index: 0,
+            port_input: port_input.to_string(),
+            env_input: String::new(),
+            mounts_input: String::new(),
+            replicas_input: String::new(),
+            active_field: OverrideField::Ports,
+        };
+
+        app.modal = Some(edit("8080:http"));
+        handle_key(&mut app, KeyCode::Enter);
+        assert!(app.modal.is_some());
+        assert!(app.environments[0].override_for("web").is_none());
+
+        app.modal = Some(edit("127.0.0.1:8080:80"));
+        handle_key(&mut app, KeyCode::Enter);
+        assert!(app.modal.is_none());
+        assert_eq!(
+            app.environments[0]
+                .override_for("web")
+                .and_then(|entry| entry.port_mapping.as_deref()),
+            Some("127.0.0.1:8080:80")
+        );
+    }
+
+    #[test]
+    fn engine_replies_fill_open_modals() {
+        let mut app = App::new();
+        app.modal = Some(ModalState::AddImageType {
+            input: String::new(),
+            local_images: Vec::new(),
+            selected: None,
+        });
+        handle_engine_reply(
+            &mut app,
+            EngineReply::LocalImages(Ok(vec!["redis:7".to_string()])),
+        );
+        assert!(matches!(
+            &app.modal,
+            Some(ModalState::AddImageType { local_images, .. }) if local_images == &["redis:7"]
+        ));
+
+        app.modal = None;
+        handle_engine_reply(
+            &mut app,
+            EngineReply::Inspected {
+                reference: "redis:7".to_string(),
+                purpose: InspectPurpose::OfferPull,
+                result: Ok(None),
+            },
+        );
+        assert!(matches!(
+            &app.modal,
+            Some(ModalState::ConfirmPullImage { reference }) if reference == "redis:7"
+        ));
+    }
+
+    #[test]
+    fn local_healthcheck_does_not_overwrite_edits() {
+        let mut app = App::new();
+        app.images.push(ImageEntry {
+            service_name: "cache".to_string(),
+            namespace: "library".to_string(),
+            repo: "redis".to_string(),
+            tag: "7".to_string(),
+            ..ImageEntry::default()
+        });
+        let reply = || EngineReply::Inspected {
+            reference: "redis:7".to_string(),
+            purpose: InspectPurpose::SuggestHealthcheck(0),
+            result: Ok(Some(api::LocalImageConfig {
+                healthcheck: Some(api::ImageHealthcheck {
+                    test: vec![
+                        "CMD".to_string(),
+                        "redis-cli".to_string(),
+                        "ping".to_string(),
+                    ],
+                    interval_ns: None,
+                    timeout_ns: None,
+                    start_period_ns: None,
+                    retries: None,
+                }),
+                ..api::LocalImageConfig::default()
+            })),
+        };
+
+        app.modal = Some(edit_healthcheck_modal(0, healthcheck::from_shell("")));
+        handle_key(&mut app, KeyCode::Char('x'));
+        handle_engine_reply(&mut app, reply());
+        assert!(matches!(
+            &app.modal,
+            Some(ModalState::EditHealthcheck { test_input, .. }) if test_input == "x"
+        ));
+
+        app.modal = Some(edit_healthcheck_modal(0, healthcheck::from_shell("")));
+        handle_engine_reply(&mut app, reply());
+        assert!(matches!(
+            &app.modal,
+            Some(ModalState::EditHealthcheck { test_input, .. }) if test_input.contains("redis-cli")
+        ));
+    }
+
+    #[test]
+    fn write_with_dropped_content_needs_an_explicit_y() {
+        let mut app = App::new();
+        app.compose_file = std::env::temp_dir()
+            .join(format!("acdc-dropped-{}.yaml", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        app.dropped_content = vec!["web: drops unsupported key networks".to_string()];
+        app.modal = Some(ModalState::ConfirmWriteCompose);
+
+        handle_key(&mut app, KeyCode::Enter);
+        assert!(matches!(app.modal, Some(ModalState::ConfirmWriteCompose)));
+        assert!(!Path::new(&app.compose_file).exists());
+    }
+
+    #[test]
+    fn p_on_project_tab_asks_to_write_compose() {
+        let mut app = App::new();
+        app.active_tab = Tab::Project;
+        handle_key(&mut app, KeyCode::Char('p'));
+        assert!(matches!(app.modal, Some(ModalState::ConfirmWriteCompose)));
+        assert!(app.check_host_ports);
+
+        app.modal = None;
+        handle_key(&mut app, KeyCode::Char('P'));
+        assert!(app.modal.is_none());
+        assert!(!app.check_host_ports);
+    }
+}
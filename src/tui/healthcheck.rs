@@ -0,0 +1,158 @@
+use crate::api::ImageHealthcheck;
+use crate::tui::{
+    app::{Healthcheck, HealthcheckForm, yaml_quote},
+    command, dockerfile,
+};
+
+const DURATION_UNITS: [(&str, u64); 6] = [
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+pub fn test_args(healthcheck: &Healthcheck) -> Vec<String> {
+    match healthcheck.form {
+        HealthcheckForm::Cmd => dockerfile::split_words(&healthcheck.test),
+        HealthcheckForm::CmdShell => vec![healthcheck.test.clone()],
+    }
+}
+
+pub fn parse_test(parts: &[String]) -> Option<(HealthcheckForm, String, bool)> {
+    let (kind, args) = parts.split_first()?;
+    Some(match kind.as_str() {
+        "NONE" => (HealthcheckForm::CmdShell, String::new(), true),
+        "CMD" => (HealthcheckForm::Cmd, command::shell_join(args), false),
+        _ => (HealthcheckForm::CmdShell, args.join(" "), false),
+    })
+}
+
+pub fn test_yaml(healthcheck: &Healthcheck) -> String {
+    let mut parts = vec![healthcheck.form.label().to_string()];
+    parts.extend(test_args(healthcheck));
+
+    let quoted: Vec<String> = parts.iter().map(|part| yaml_quote(part)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+pub fn format_duration(nanos: u64) -> String {
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+
+    let mut remaining = nanos;
+    let mut output = String::new();
+    for (unit, size) in DURATION_UNITS {
+        let amount = remaining / size;
+        if amount > 0 {
+            output.push_str(&format!("{amount}{unit}"));
+            remaining -= amount * size;
+        }
+    }
+    output
+}
+
+pub fn is_valid_duration(input: &str) -> bool {
+    let mut rest = input.trim();
+    if rest.is_empty() {
+        return false;
+    }
+
+    while !rest.is_empty() {
+        let digits = rest.chars().take_while(|ch| ch.is_ascii_digit()).count();
+        if digits == 0 {
+            return false;
+        }
+        rest = &rest[digits..];
+
+        let unit = ["ms", "us", "ns", "h", "m", "s"]
+            .into_iter()
+            .find(|unit| rest.starts_with(unit));
+        match unit {
+            Some(unit) => rest = &rest[unit.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+pub fn from_shell(command: &str) -> Healthcheck {
+    Healthcheck {
+        form: HealthcheckForm::CmdShell,
+        test: command.to_string(),
+        interval: Some("10s".to_string()),
+        timeout: Some("5s".to_string()),
+        retries: Some(5),
+        start_period: None,
+        disable: false,
+    }
+}
+
+pub fn from_image_config(config: &ImageHealthcheck) -> Option<Healthcheck> {
+    let (form, test, disable) = parse_test(&config.test)?;
+
+    Some(Healthcheck {
+        form,
+        test,
+        interval: config.interval_ns.map(format_duration),
+        timeout: config.timeout_ns.map(format_duration),
+        retries: config.retries,
+        start_period: config.start_period_ns.map(format_duration),
+        disable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image_config(test: &[&str]) -> ImageHealthcheck {
+        ImageHealthcheck {
+            test: test.iter().map(ToString::to_string).collect(),
+            interval_ns: Some(30_000_000_000),
+            timeout_ns: Some(1_500_000_000),
+            start_period_ns: None,
+            retries: Some(3),
+        }
+    }
+
+    #[test]
+    fn from_image_config_keeps_cmd_arguments_intact() {
+        let config = image_config(&["CMD", "pg_isready", "-U", "app user", "-d", "it's"]);
+        let healthcheck = from_image_config(&config).unwrap();
+        assert_eq!(healthcheck.form, HealthcheckForm::Cmd);
+        assert_eq!(test_args(&healthcheck), &config.test[1..]);
+        assert_eq!(
+            test_yaml(&healthcheck),
+            r#"["CMD", "pg_isready", "-U", "app user", "-d", "it's"]"#
+        );
+        assert_eq!(healthcheck.interval.as_deref(), Some("30s"));
+        assert_eq!(healthcheck.timeout.as_deref(), Some("1s500ms"));
+    }
+
+    #[test]
+    fn from_image_config_handles_shell_and_none() {
+        let shell = from_image_config(&image_config(&[
+            "CMD-SHELL",
+            "curl -f http://localhost/ || exit 1",
+        ]))
+        .unwrap();
+        assert_eq!(
+            test_yaml(&shell),
+            r#"["CMD-SHELL", "curl -f http://localhost/ || exit 1"]"#
+        );
+        assert!(from_image_config(&image_config(&["NONE"])).unwrap().disable);
+        assert!(from_image_config(&image_config(&[])).is_none());
+    }
+
+    #[test]
+    fn durations_validate_go_syntax() {
+        assert!(is_valid_duration("1m30s"));
+        assert!(is_valid_duration("500ms"));
+        assert!(!is_valid_duration("10"));
+        assert!(!is_valid_duration("1.5s"));
+        assert_eq!(format_duration(90_000_000_000), "1m30s");
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::tui::config_dir;
+
+const BUNDLED_KNOWLEDGE: &str = include_str!("../../assets/knowledge.toml");
+
+static KNOWLEDGE: OnceLock<(Vec<ImageKnowledge>, Vec<String>)> = OnceLock::new();
+
+#[derive(Debug, Deserialize)]
+struct KnowledgeFile {
+    #[serde(default)]
+    images: Vec<ImageKnowledge>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageKnowledge {
+    pub image: String,
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub data_dirs: Vec<String>,
+    #[serde(default)]
+    pub required_env: Vec<String>,
+    #[serde(default)]
+    pub recommended_env: Vec<String>,
+    pub healthcheck: Option<String>,
+}
+
+impl ImageKnowledge {
+    fn matches(&self, namespace: &str, repo: &str) -> bool {
+        self.image == format!("{namespace}/{repo}")
+            || (namespace == "library" && self.image == repo)
+    }
+
+    pub fn recommended_env_pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.recommended_env
+            .iter()
+            .map(|pair| pair.split_once('=').unwrap_or((pair.as_str(), "")))
+    }
+}
+
+fn parse_knowledge(raw: &str) -> Result<Vec<ImageKnowledge>, String> {
+    toml::from_str::<KnowledgeFile>(raw)
+        .map(|file| file.images)
+        .map_err(|error| error.to_string())
+}
+
+fn load_knowledge() -> (Vec<ImageKnowledge>, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut entries = parse_knowledge(BUNDLED_KNOWLEDGE).unwrap_or_else(|error| {
+        warnings.push(format!("bundled knowledge invalid: {error}"));
+        Vec::new()
+    });
+
+    let Some(path) = config_dir().map(|dir| dir.join("knowledge.toml")) else {
+        return (entries, warnings);
+    };
+    let Ok(raw) = std::fs::read_to_string(&path) else {
+        return (entries, warnings);
+    };
+    match parse_knowledge(&raw) {
+        Ok(user_entries) => {
+            for entry in user_entries {
+                entries.retain(|existing| existing.image != entry.image);
+                entries.push(entry);
+            }
+        }
+        Err(error) => warnings.push(format!(
+            "skipped knowledge file {}: {error}",
+            path.display()
+        )),
+    }
+
+    (entries, warnings)
+}
+
+pub fn load_warnings() -> &'static [String] {
+    &KNOWLEDGE.get_or_init(load_knowledge).1
+}
+
+pub fn lookup(namespace: &str, repo: &str) -> Option<&'static ImageKnowledge> {
+    KNOWLEDGE
+        .get_or_init(load_knowledge)
+        .0
+        .iter()
+        .find(|entry| entry.matches(namespace, repo))
+}
+
+pub fn preferred_container_port(
+    knowledge: Option<&ImageKnowledge>,
+    exposed_ports: &[u16],
+) -> Option<u16> {
+    knowledge
+        .and_then(|entry| entry.port)
+        .or_else(|| exposed_ports.first().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_knowledge_parses() {
+        let entries = parse_knowledge(BUNDLED_KNOWLEDGE).unwrap();
+        assert!(!entries.is_empty());
+    }
+
+    #[test]
+    fn invalid_knowledge_reports_an_error() {
+        assert!(parse_knowledge("[[images]]\nport = 5432\n").is_err());
+        assert!(parse_knowledge("images = [").is_err());
+        let entries = parse_knowledge("[[images]]\nimage = \"postgres\"\nport = 5432\n").unwrap();
+        assert_eq!(entries[0].port, Some(5432));
+    }
+}
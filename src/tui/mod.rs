@@ -1,32 +1,240 @@
 mod app;
+mod build;
+mod command;
+mod compose_files;
+mod deploy;
+mod detail;
+mod docker_run;
+mod dockerfile;
+mod engine;
+mod env_files;
+mod environments;
+mod export;
 mod handlers;
+mod healthcheck;
+mod knowledge;
+mod paths;
+mod ports;
+mod properties;
+mod runner;
+mod scan;
+mod secrets;
 mod tab;
+mod templates;
 mod theme;
 mod ui;
+mod volumes;
 
-use crossterm::event::{self, Event};
-use ratatui::DefaultTerminal;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event};
+use ratatui::{DefaultTerminal, layout::Rect};
 
 use app::App;
 use handlers::LoopControl;
 
+const TICK_RATE: Duration = Duration::from_millis(250);
+
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("acdc"))
+}
+
 pub fn run() -> color_eyre::Result<()> {
     color_eyre::install()?;
-    ratatui::run(app)?;
+    ratatui::run(|terminal| app(terminal, App::new()))?;
+    Ok(())
+}
+
+pub fn run_with_template(name: &str) -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    let Some(template) = templates::find_template(name) else {
+        return Err(color_eyre::eyre::eyre!(
+            "unknown template '{name}' (run `acdc new` to list templates)"
+        ));
+    };
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let tags = runtime.block_on(templates::resolve_template_tags(&template));
+
+    let mut app = App::new();
+    for line in templates::apply_template(&mut app, &template, &tags) {
+        app.push_log(line);
+    }
+
+    ratatui::run(|terminal| self::app(terminal, app))?;
+    Ok(())
+}
+
+pub fn run_init() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    let root = std::env::current_dir()?;
+    let scan = scan::scan_project(&root);
+    let mut app = App::new();
+    app.push_log(format!(
+        "init: {} proposals from {}",
+        scan.proposals.len(),
+        root.display()
+    ));
+    app.modal = Some(app::ModalState::ReviewScan {
+        proposals: scan.proposals,
+        notes: scan.notes,
+        selected: 0,
+    });
+
+    ratatui::run(|terminal| self::app(terminal, app))?;
+    Ok(())
+}
+
+fn open_project(path: Option<PathBuf>) -> color_eyre::Result<(App, Vec<String>)> {
+    let path = match path {
+        Some(path) if path.is_dir() => compose_files::find_root_file(&path),
+        Some(path) => Some(path),
+        None => compose_files::find_root_file(&std::env::current_dir()?),
+    };
+    let Some(path) = path else {
+        return Err(color_eyre::eyre::eyre!(
+            "no compose file found (looked for {})",
+            compose_files::ROOT_FILE_CANDIDATES.join(", ")
+        ));
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::env::set_current_dir(dir)?;
+    }
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let project =
+        compose_files::load_project(&file_name).map_err(|error| color_eyre::eyre::eyre!(error))?;
+
+    let mut app = App::new();
+    let logs = app.load_project(project);
+    Ok((app, logs))
+}
+
+pub fn run_open(path: Option<PathBuf>) -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    let (mut app, logs) = open_project(path)?;
+    for line in logs {
+        app.push_log(line);
+    }
+    ratatui::run(|terminal| self::app(terminal, app))?;
     Ok(())
 }
 
-fn app(terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+pub fn run_export(
+    format: &str,
+    path: Option<PathBuf>,
+    output: Option<PathBuf>,
+) -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    let Some(format) = export::ExportFormat::parse(format) else {
+        let formats: Vec<&str> = export::ExportFormat::all()
+            .iter()
+            .map(|format| format.label())
+            .collect();
+        return Err(color_eyre::eyre::eyre!(
+            "unknown export format '{format}' (expected {})",
+            formats.join(", ")
+        ));
+    };
+    let output = match output {
+        Some(output) if output.is_relative() => Some(std::env::current_dir()?.join(output)),
+        output => output,
+    };
+    let (app, logs) = open_project(path)?;
+    for line in logs {
+        eprintln!("{line}");
+    }
+
+    let result = export::export(&app, format);
+    let base = output.unwrap_or(std::env::current_dir()?);
+    result.write(&base)?;
+    for file in &result.files {
+        println!("wrote {}", base.join(&file.path).display());
+    }
+    for warning in &result.warnings {
+        eprintln!("warning: {warning}");
+    }
+    Ok(())
+}
+
+pub fn run_import(command: &str) -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
     let mut app = App::new();
+    let report = match docker_run::parse_docker_run(command) {
+        Ok(import) => docker_run::apply_import(&mut app, import),
+        Err(error) => vec![error],
+    };
+    app.modal = Some(app::ModalState::ImportRun {
+        input: command.to_string(),
+        report: Some(report),
+    });
 
+    ratatui::run(|terminal| self::app(terminal, app))?;
+    Ok(())
+}
+
+pub fn print_templates() {
+    let (templates, warnings) = templates::load_templates();
+    for warning in warnings {
+        eprintln!("{warning}");
+    }
+
+    for template in templates {
+        println!("{:<20} {}", template.name, template.description);
+    }
+    if let Some(dir) = templates::user_templates_dir() {
+        println!("\nuser templates: {}", dir.display());
+    }
+}
+
+fn app(terminal: &mut DefaultTerminal, app: App) -> std::io::Result<()> {
+    crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
+    let result = event_loop(terminal, app);
+    crossterm::execute!(std::io::stdout(), DisableMouseCapture)?;
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> std::io::Result<()> {
+    for warning in knowledge::load_warnings() {
+        app.push_log(warning.clone());
+    }
     loop {
+        app.poll_runtime();
+        for reply in app.engine.poll() {
+            handlers::handle_engine_reply(&mut app, reply);
+        }
         terminal.draw(|frame| ui::render(frame, &app))?;
 
-        if let Event::Key(key) = event::read()? {
-            match handlers::handle_key(&mut app, key.code) {
-                LoopControl::Continue => {}
-                LoopControl::Exit => break Ok(()),
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
+        let control = match event::read()? {
+            Event::Key(key) => handlers::handle_key(&mut app, key.code),
+            Event::Mouse(mouse) => {
+                let size = terminal.size()?;
+                handlers::handle_mouse(&mut app, mouse, Rect::new(0, 0, size.width, size.height))
+            }
+            _ => LoopControl::Continue,
+        };
+        match control {
+            LoopControl::Continue => {
+                app.apply_list_order();
+                app.refresh_env_files();
             }
+            LoopControl::Exit => break Ok(()),
         }
     }
 }
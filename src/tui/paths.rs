@@ -0,0 +1,284 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    pub input: String,
+    pub candidates: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathAssist {
+    pub candidates: Vec<String>,
+    pub browser: Option<DirBrowser>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DirBrowser {
+    pub dir: PathBuf,
+    pub entries: Vec<String>,
+    pub selected: usize,
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+pub fn resolve(base: &Path, input: &str) -> PathBuf {
+    let input = input.trim();
+    if let Some(rest) = input.strip_prefix("~/")
+        && let Some(home) = home_dir()
+    {
+        return home.join(rest);
+    }
+    if input == "~"
+        && let Some(home) = home_dir()
+    {
+        return home;
+    }
+    let path = Path::new(input);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    }
+}
+
+pub fn exists(base: &Path, input: &str) -> bool {
+    resolve(base, input).exists()
+}
+
+pub fn normalize(base: &Path, input: &str) -> String {
+    let trimmed = input.trim();
+    if trimmed.is_empty() || trimmed.starts_with('~') || trimmed.starts_with('$') {
+        return trimmed.to_string();
+    }
+    let path = Path::new(trimmed);
+    if path.is_absolute() {
+        let base = std::fs::canonicalize(base).unwrap_or_else(|_| base.to_path_buf());
+        return match path.strip_prefix(&base) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => format!("./{}", relative.display()),
+            Err(_) => trimmed.to_string(),
+        };
+    }
+    if trimmed == "." || trimmed.starts_with("./") || trimmed.starts_with("../") || trimmed == ".."
+    {
+        trimmed.to_string()
+    } else {
+        format!("./{trimmed}")
+    }
+}
+
+fn list_dir(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() {
+                format!("{name}/")
+            } else {
+                name
+            }
+        })
+        .collect();
+    names.sort_by(|left, right| {
+        right
+            .ends_with('/')
+            .cmp(&left.ends_with('/'))
+            .then(left.cmp(right))
+    });
+    names
+}
+
+fn common_prefix(names: &[String]) -> String {
+    let Some(first) = names.first() else {
+        return String::new();
+    };
+    let mut prefix = first.as_str();
+    for name in &names[1..] {
+        let shared = prefix
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, left), right)| left != right)
+            .map(|((index, _), _)| index)
+            .unwrap_or(prefix.len().min(name.len()));
+        prefix = &prefix[..shared];
+    }
+    prefix.to_string()
+}
+
+pub fn complete(base: &Path, input: &str) -> Completion {
+    let (dir_part, partial) = match input.rfind('/') {
+        Some(index) => input.split_at(index + 1),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        base.to_path_buf()
+    } else {
+        resolve(base, dir_part)
+    };
+    let candidates: Vec<String> = list_dir(&dir)
+        .into_iter()
+        .filter(|name| {
+            name.starts_with(partial) && (!name.starts_with('.') || partial.starts_with('.'))
+        })
+        .collect();
+    let prefix = common_prefix(&candidates);
+    let completed = if prefix.len() > partial.len() {
+        format!("{dir_part}{prefix}")
+    } else {
+        input.to_string()
+    };
+    Completion {
+        input: completed,
+        candidates: if candidates.len() > 1 {
+            candidates
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+impl DirBrowser {
+    pub fn open(base: &Path, input: &str) -> Self {
+        let resolved = resolve(base, input);
+        let dir = if resolved.is_dir() {
+            resolved
+        } else {
+            resolved
+                .parent()
+                .filter(|parent| parent.is_dir())
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| base.to_path_buf())
+        };
+        let mut browser = Self {
+            dir: std::fs::canonicalize(&dir).unwrap_or(dir),
+            entries: Vec::new(),
+            selected: 0,
+        };
+        browser.refresh();
+        browser
+    }
+
+    fn refresh(&mut self) {
+        self.entries = std::iter::once("./".to_string())
+            .chain(
+                list_dir(&self.dir)
+                    .into_iter()
+                    .filter(|name| name.ends_with('/')),
+            )
+            .collect();
+        self.selected = 0;
+    }
+
+    pub fn select(&mut self, up: bool) {
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        } else if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn enter(&mut self) {
+        if self.selected == 0 {
+            return;
+        }
+        if let Some(name) = self.entries.get(self.selected) {
+            self.dir = self.dir.join(name.trim_end_matches('/'));
+            self.refresh();
+        }
+    }
+
+    pub fn parent(&mut self) {
+        let child = self
+            .dir
+            .file_name()
+            .map(|name| format!("{}/", name.to_string_lossy()));
+        if let Some(parent) = self.dir.parent() {
+            self.dir = parent.to_path_buf();
+            self.refresh();
+            if let Some(child) = child
+                && let Some(index) = self.entries.iter().position(|entry| *entry == child)
+            {
+                self.selected = index;
+            }
+        }
+    }
+
+    pub fn selected_path(&self, base: &Path) -> String {
+        let path = match self.entries.get(self.selected) {
+            Some(name) if self.selected > 0 => self.dir.join(name.trim_end_matches('/')),
+            _ => self.dir.clone(),
+        };
+        normalize(base, &path.to_string_lossy())
+    }
+}
+
+pub fn create_dirs(base: &Path, paths: &[String]) -> std::io::Result<()> {
+    paths
+        .iter()
+        .try_for_each(|path| std::fs::create_dir_all(resolve(base, path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("acdc-paths-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("config/nested")).unwrap();
+        std::fs::create_dir_all(dir.join("configs")).unwrap();
+        std::fs::write(dir.join("config/app.toml"), "").unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn resolve_expands_home_and_joins_relative_paths() {
+        let base = Path::new("/srv/project");
+        if let Some(home) = home_dir() {
+            assert_eq!(resolve(base, "~"), home);
+            assert_eq!(resolve(base, " ~/data/ "), home.join("data/"));
+        }
+        assert_eq!(resolve(base, "./data"), base.join("./data"));
+        assert_eq!(resolve(base, "../shared"), base.join("../shared"));
+        assert_eq!(resolve(base, "/var/lib"), PathBuf::from("/var/lib"));
+        assert_eq!(resolve(base, "~user/data"), base.join("~user/data"));
+    }
+
+    #[test]
+    fn normalize_prefixes_relative_paths_and_keeps_trailing_slashes() {
+        let dir = temp_project("normalize");
+        assert_eq!(normalize(&dir, "data"), "./data");
+        assert_eq!(normalize(&dir, "data/"), "./data/");
+        assert_eq!(normalize(&dir, "../shared/"), "../shared/");
+        assert_eq!(normalize(&dir, "~/data/"), "~/data/");
+        assert_eq!(normalize(&dir, "${DATA_DIR}"), "${DATA_DIR}");
+        assert_eq!(normalize(&dir, &dir.to_string_lossy()), ".");
+        assert_eq!(
+            normalize(&dir, &format!("{}/config/", dir.display())),
+            "./config"
+        );
+        assert_eq!(normalize(&dir, "/etc/app/"), "/etc/app/");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn complete_extends_to_the_common_prefix() {
+        let dir = temp_project("complete");
+        let completion = complete(&dir, "con");
+        assert_eq!(completion.input, "config");
+        assert_eq!(completion.candidates, ["config/", "configs/"]);
+
+        let completion = complete(&dir, "config/");
+        assert_eq!(completion.input, "config/");
+        assert_eq!(completion.candidates, ["nested/", "app.toml"]);
+
+        let completion = complete(&dir, "./config/a");
+        assert_eq!(completion.input, "./config/app.toml");
+        assert!(completion.candidates.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+const PRIVILEGED_PORT_LIMIT: u16 = 1024;
+const PROC_NET_FILES: [&str; 2] = ["/proc/net/tcp", "/proc/net/tcp6"];
+const TCP_LISTEN: &str = "0A";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortChoice {
+    pub host: u16,
+    pub container: u16,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortConflict<'a> {
+    Service(&'a str),
+    Host,
+}
+
+impl PortChoice {
+    pub fn mapping(&self) -> String {
+        format!("{}:{}", self.host, self.container)
+    }
+}
+
+impl PortConflict<'_> {
+    pub fn describe(self, port: u16) -> String {
+        match self {
+            PortConflict::Service(service) => format!("{port} is used by {service}"),
+            PortConflict::Host => format!("{port} is in use on this machine"),
+        }
+    }
+}
+
+pub fn host_port(mapping: &str) -> Option<u16> {
+    let (host, _) = mapping.trim().rsplit_once(':')?;
+    host.rsplit(':').next()?.parse().ok()
+}
+
+pub fn container_port(mapping: &str) -> Option<u16> {
+    let container = mapping.trim().rsplit(':').next()?;
+    container.split('/').next()?.parse().ok()
+}
+
+pub fn validate_mapping(mapping: &str) -> Result<(), String> {
+    let mapping = mapping.trim();
+    let (ports, protocol) = mapping.split_once('/').unwrap_or((mapping, "tcp"));
+    let valid = |port: &str| port.parse::<u16>().is_ok_and(|port| port > 0);
+    let parts: Vec<&str> = ports.split(':').collect();
+    let valid_ports = match parts.as_slice() {
+        [host, container] => valid(host) && valid(container),
+        [address, host, container] => {
+            address.parse::<std::net::Ipv4Addr>().is_ok() && valid(host) && valid(container)
+        }
+        _ => false,
+    };
+    if valid_ports && matches!(protocol, "tcp" | "udp" | "sctp") {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid port mapping '{mapping}', expected host:container with ports 1-65535"
+        ))
+    }
+}
+
+pub fn listening_ports() -> Vec<u16> {
+    let mut ports: Vec<u16> = PROC_NET_FILES
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .skip(1)
+                .filter_map(|line| {
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    if fields.get(3) != Some(&TCP_LISTEN) {
+                        return None;
+                    }
+                    let (_, port) = fields.get(1)?.rsplit_once(':')?;
+                    u16::from_str_radix(port, 16).ok()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+pub fn conflict<'a>(
+    port: u16,
+    taken: &[(u16, &'a str)],
+    listening: &[u16],
+) -> Option<PortConflict<'a>> {
+    taken
+        .iter()
+        .find(|(used, _)| *used == port)
+        .map(|(_, service)| PortConflict::Service(service))
+        .or_else(|| listening.contains(&port).then_some(PortConflict::Host))
+}
+
+pub fn allocate(container: u16, taken: &[(u16, &str)], listening: &[u16]) -> PortChoice {
+    let preferred = container;
+    let mut host = preferred;
+    let mut first_conflict = None;
+    while let Some(found) = conflict(host, taken, listening) {
+        first_conflict.get_or_insert(found);
+        host = if host == u16::MAX {
+            PRIVILEGED_PORT_LIMIT
+        } else {
+            host + 1
+        };
+        if host == preferred {
+            break;
+        }
+    }
+    let reason = match first_conflict {
+        Some(found) => format!(
+            "{}; {host} is the next free port",
+            found.describe(preferred)
+        ),
+        None if host < PRIVILEGED_PORT_LIMIT => format!(
+            "{host} matches the container port and is free; ports below {PRIVILEGED_PORT_LIMIT} may need root"
+        ),
+        None => format!("{host} matches the container port and is free"),
+    };
+    PortChoice {
+        host,
+        container,
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocate_prefers_the_container_port_when_free() {
+        let choice = allocate(3000, &[], &[]);
+        assert_eq!(choice.mapping(), "3000:3000");
+        let choice = allocate(80, &[], &[]);
+        assert_eq!(choice.mapping(), "80:80");
+        assert!(choice.reason.ends_with("may need root"));
+    }
+
+    #[test]
+    fn allocate_skips_services_and_listening_ports() {
+        let taken = [(80, "web"), (81, "api")];
+        let choice = allocate(80, &taken, &[82]);
+        assert_eq!(choice.host, 83);
+        assert_eq!(choice.reason, "80 is used by web; 83 is the next free port");
+
+        let choice = allocate(5432, &[], &[5432]);
+        assert_eq!(choice.host, 5433);
+        assert!(choice.reason.starts_with("5432 is in use on this machine"));
+    }
+
+    #[test]
+    fn allocate_wraps_past_the_last_port() {
+        let choice = allocate(u16::MAX, &[], &[u16::MAX]);
+        assert_eq!(choice.host, PRIVILEGED_PORT_LIMIT);
+    }
+
+    #[test]
+    fn validate_mapping_checks_ports_address_and_protocol() {
+        for mapping in ["8080:80", " 127.0.0.1:5432:5432 ", "53:53/udp"] {
+            assert!(validate_mapping(mapping).is_ok(), "{mapping}");
+        }
+        for mapping in [
+            "",
+            "80",
+            "0:80",
+            "8080:65536",
+            "host:80:80",
+            "80:80/icmp",
+            "a:b",
+        ] {
+            assert!(validate_mapping(mapping).is_err(), "{mapping}");
+        }
+    }
+}
@@ -0,0 +1,742 @@
+use crate::tui::{
+    app::{ImageEntry, yaml_quote},
+    command::CommandSpec,
+    deploy::DeployProperty,
+    healthcheck,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestartPolicy {
+    No,
+    Always,
+    OnFailure(Option<u32>),
+    UnlessStopped,
+}
+
+impl RestartPolicy {
+    fn parse(input: &str) -> Result<Self, String> {
+        match input.trim() {
+            "no" => Ok(RestartPolicy::No),
+            "always" => Ok(RestartPolicy::Always),
+            "unless-stopped" => Ok(RestartPolicy::UnlessStopped),
+            "on-failure" => Ok(RestartPolicy::OnFailure(None)),
+            other => match other.strip_prefix("on-failure:") {
+                Some(count) => count
+                    .parse()
+                    .map(|count| RestartPolicy::OnFailure(Some(count)))
+                    .map_err(|_| format!("invalid retry count '{count}'")),
+                None => Err(format!(
+                    "unknown restart policy '{other}' (no, always, on-failure[:N], unless-stopped)"
+                )),
+            },
+        }
+    }
+
+    pub fn as_compose(&self) -> String {
+        match self {
+            RestartPolicy::No => "\"no\"".to_string(),
+            RestartPolicy::Always => "always".to_string(),
+            RestartPolicy::OnFailure(None) => "on-failure".to_string(),
+            RestartPolicy::OnFailure(Some(count)) => format!("on-failure:{count}"),
+            RestartPolicy::UnlessStopped => "unless-stopped".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ulimit {
+    pub name: String,
+    pub soft: i64,
+    pub hard: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Logging {
+    pub driver: String,
+    pub options: Vec<KeyValue>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ServiceOptions {
+    pub profiles: Vec<String>,
+    pub restart: Option<RestartPolicy>,
+    pub entrypoint: Option<CommandSpec>,
+    pub user: Option<String>,
+    pub working_dir: Option<String>,
+    pub hostname: Option<String>,
+    pub container_name: Option<String>,
+    pub network_mode: Option<String>,
+    pub labels: Vec<KeyValue>,
+    pub extra_hosts: Vec<String>,
+    pub dns: Vec<String>,
+    pub tty: bool,
+    pub stdin_open: bool,
+    pub init: bool,
+    pub stop_grace_period: Option<String>,
+    pub read_only: bool,
+    pub cap_add: Vec<String>,
+    pub cap_drop: Vec<String>,
+    pub security_opt: Vec<String>,
+    pub ulimits: Vec<Ulimit>,
+    pub sysctls: Vec<KeyValue>,
+    pub logging: Option<Logging>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceProperty {
+    Profiles,
+    Restart,
+    Entrypoint,
+    User,
+    WorkingDir,
+    Hostname,
+    ContainerName,
+    NetworkMode,
+    Labels,
+    ExtraHosts,
+    Dns,
+    Tty,
+    StdinOpen,
+    Init,
+    StopGracePeriod,
+    ReadOnly,
+    CapAdd,
+    CapDrop,
+    SecurityOpt,
+    Ulimits,
+    Sysctls,
+    Logging,
+}
+
+fn optional_text(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+pub fn parse_list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+pub fn parse_key_values(input: &str) -> Result<Vec<KeyValue>, String> {
+    parse_list(input)
+        .into_iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok(KeyValue {
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+            }),
+            _ => Err(format!("expected key=value, got '{pair}'")),
+        })
+        .collect()
+}
+
+fn parse_ulimits(input: &str) -> Result<Vec<Ulimit>, String> {
+    parse_key_values(input)?
+        .into_iter()
+        .map(|pair| {
+            let (soft, hard) = match pair.value.split_once(':') {
+                Some((soft, hard)) => (soft, Some(hard)),
+                None => (pair.value.as_str(), None),
+            };
+            let soft = soft
+                .parse::<i64>()
+                .map_err(|_| format!("invalid ulimit value '{}'", pair.value))?;
+            let hard = hard
+                .map(|hard| hard.parse::<i64>())
+                .transpose()
+                .map_err(|_| format!("invalid ulimit value '{}'", pair.value))?;
+            Ok(Ulimit {
+                name: pair.key,
+                soft,
+                hard,
+            })
+        })
+        .collect()
+}
+
+fn parse_logging(input: &str) -> Result<Option<Logging>, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+
+    let (driver, options) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+    Ok(Some(Logging {
+        driver: driver.to_string(),
+        options: parse_key_values(options)?,
+    }))
+}
+
+fn join_key_values(pairs: &[KeyValue]) -> String {
+    pairs
+        .iter()
+        .map(|pair| format!("{}={}", pair.key, pair.value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn is_valid_profile(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '.' | '-'))
+}
+
+pub fn is_valid_network_mode(mode: &str) -> bool {
+    matches!(mode, "bridge" | "host" | "none")
+        || ["service:", "container:"].iter().any(|prefix| {
+            mode.strip_prefix(prefix)
+                .is_some_and(|name| !name.is_empty())
+        })
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+impl ServiceProperty {
+    pub fn all() -> [Self; 22] {
+        [
+            Self::Profiles,
+            Self::Restart,
+            Self::Entrypoint,
+            Self::User,
+            Self::WorkingDir,
+            Self::Hostname,
+            Self::ContainerName,
+            Self::NetworkMode,
+            Self::Labels,
+            Self::ExtraHosts,
+            Self::Dns,
+            Self::Tty,
+            Self::StdinOpen,
+            Self::Init,
+            Self::StopGracePeriod,
+            Self::ReadOnly,
+            Self::CapAdd,
+            Self::CapDrop,
+            Self::SecurityOpt,
+            Self::Ulimits,
+            Self::Sysctls,
+            Self::Logging,
+        ]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Profiles => "profiles",
+            Self::Restart => "restart",
+            Self::Entrypoint => "entrypoint",
+            Self::User => "user",
+            Self::WorkingDir => "working_dir",
+            Self::Hostname => "hostname",
+            Self::ContainerName => "container_name",
+            Self::NetworkMode => "network_mode",
+            Self::Labels => "labels",
+            Self::ExtraHosts => "extra_hosts",
+            Self::Dns => "dns",
+            Self::Tty => "tty",
+            Self::StdinOpen => "stdin_open",
+            Self::Init => "init",
+            Self::StopGracePeriod => "stop_grace_period",
+            Self::ReadOnly => "read_only",
+            Self::CapAdd => "cap_add",
+            Self::CapDrop => "cap_drop",
+            Self::SecurityOpt => "security_opt",
+            Self::Ulimits => "ulimits",
+            Self::Sysctls => "sysctls",
+            Self::Logging => "logging",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Profiles => "comma separated profile names like debug, tools",
+            Self::Restart => "no | always | on-failure[:N] | unless-stopped",
+            Self::NetworkMode => "bridge | host | none | service:<name> | container:<name>",
+            Self::Labels | Self::Sysctls => "comma separated key=value",
+            Self::ExtraHosts => "comma separated host:ip",
+            Self::Dns | Self::CapAdd | Self::CapDrop | Self::SecurityOpt => "comma separated list",
+            Self::StopGracePeriod => "duration like 10s or 1m30s",
+            Self::Ulimits => "comma separated name=soft[:hard]",
+            Self::Logging => "driver key=value,key=value",
+            Self::Tty | Self::StdinOpen | Self::Init | Self::ReadOnly => "Enter toggles",
+            Self::Entrypoint
+            | Self::User
+            | Self::WorkingDir
+            | Self::Hostname
+            | Self::ContainerName => "free text, empty clears",
+        }
+    }
+
+    pub fn is_toggle(self) -> bool {
+        matches!(
+            self,
+            Self::Tty | Self::StdinOpen | Self::Init | Self::ReadOnly
+        )
+    }
+
+    pub fn toggle(self, options: &mut ServiceOptions) {
+        match self {
+            Self::Tty => options.tty = !options.tty,
+            Self::StdinOpen => options.stdin_open = !options.stdin_open,
+            Self::Init => options.init = !options.init,
+            Self::ReadOnly => options.read_only = !options.read_only,
+            _ => {}
+        }
+    }
+
+    pub fn display(self, options: &ServiceOptions) -> String {
+        match self {
+            Self::Profiles => options.profiles.join(", "),
+            Self::Restart => options
+                .restart
+                .as_ref()
+                .map(|policy| policy.as_compose().trim_matches('"').to_string())
+                .unwrap_or_default(),
+            Self::Entrypoint => options
+                .entrypoint
+                .as_ref()
+                .map(CommandSpec::display)
+                .unwrap_or_default(),
+            Self::User => options.user.clone().unwrap_or_default(),
+            Self::WorkingDir => options.working_dir.clone().unwrap_or_default(),
+            Self::Hostname => options.hostname.clone().unwrap_or_default(),
+            Self::ContainerName => options.container_name.clone().unwrap_or_default(),
+            Self::NetworkMode => options.network_mode.clone().unwrap_or_default(),
+            Self::Labels => join_key_values(&options.labels),
+            Self::ExtraHosts => options.extra_hosts.join(", "),
+            Self::Dns => options.dns.join(", "),
+            Self::Tty => yes_no(options.tty),
+            Self::StdinOpen => yes_no(options.stdin_open),
+            Self::Init => yes_no(options.init),
+            Self::StopGracePeriod => options.stop_grace_period.clone().unwrap_or_default(),
+            Self::ReadOnly => yes_no(options.read_only),
+            Self::CapAdd => options.cap_add.join(", "),
+            Self::CapDrop => options.cap_drop.join(", "),
+            Self::SecurityOpt => options.security_opt.join(", "),
+            Self::Ulimits => options
+                .ulimits
+                .iter()
+                .map(|ulimit| match ulimit.hard {
+                    Some(hard) => format!("{}={}:{}", ulimit.name, ulimit.soft, hard),
+                    None => format!("{}={}", ulimit.name, ulimit.soft),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            Self::Sysctls => join_key_values(&options.sysctls),
+            Self::Logging => options
+                .logging
+                .as_ref()
+                .map(|logging| {
+                    let pairs = logging
+                        .options
+                        .iter()
+                        .map(|pair| format!("{}={}", pair.key, pair.value))
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    format!("{} {}", logging.driver, pairs).trim().to_string()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn apply(self, options: &mut ServiceOptions, input: &str) -> Result<(), String> {
+        match self {
+            Self::Profiles => {
+                let profiles = parse_list(input);
+                if let Some(invalid) = profiles.iter().find(|profile| !is_valid_profile(profile)) {
+                    return Err(format!("invalid profile name '{invalid}'"));
+                }
+                options.profiles = profiles;
+            }
+            Self::Restart => {
+                options.restart = match optional_text(input) {
+                    Some(value) => Some(RestartPolicy::parse(&value)?),
+                    None => None,
+                }
+            }
+            Self::Entrypoint => options.entrypoint = CommandSpec::parse(input),
+            Self::User => options.user = optional_text(input),
+            Self::WorkingDir => options.working_dir = optional_text(input),
+            Self::Hostname => options.hostname = optional_text(input),
+            Self::ContainerName => options.container_name = optional_text(input),
+            Self::NetworkMode => {
+                let value = optional_text(input);
+                if let Some(value) = value.as_deref()
+                    && !is_valid_network_mode(value)
+                {
+                    return Err(format!("invalid network mode '{value}'"));
+                }
+                options.network_mode = value;
+            }
+            Self::Labels => options.labels = parse_key_values(input)?,
+            Self::ExtraHosts => {
+                let hosts = parse_list(input);
+                if let Some(invalid) = hosts.iter().find(|host| !host.contains(':')) {
+                    return Err(format!("expected host:ip, got '{invalid}'"));
+                }
+                options.extra_hosts = hosts;
+            }
+            Self::Dns => options.dns = parse_list(input),
+            Self::StopGracePeriod => {
+                let value = optional_text(input);
+                if let Some(value) = value.as_deref()
+                    && !healthcheck::is_valid_duration(value)
+                {
+                    return Err(format!("invalid duration '{value}'"));
+                }
+                options.stop_grace_period = value;
+            }
+            Self::CapAdd => options.cap_add = parse_list(input),
+            Self::CapDrop => options.cap_drop = parse_list(input),
+            Self::SecurityOpt => options.security_opt = parse_list(input),
+            Self::Ulimits => options.ulimits = parse_ulimits(input)?,
+            Self::Sysctls => options.sysctls = parse_key_values(input)?,
+            Self::Logging => options.logging = parse_logging(input)?,
+            Self::Tty | Self::StdinOpen | Self::Init | Self::ReadOnly => self.toggle(options),
+        }
+        Ok(())
+    }
+}
+
+pub fn merge_pairs(base: &mut Vec<KeyValue>, other: &[KeyValue]) {
+    for pair in other {
+        match base.iter_mut().find(|existing| existing.key == pair.key) {
+            Some(existing) => existing.value = pair.value.clone(),
+            None => base.push(pair.clone()),
+        }
+    }
+}
+
+fn merge_list(base: &mut Vec<String>, other: &[String]) {
+    for item in other {
+        if !base.contains(item) {
+            base.push(item.clone());
+        }
+    }
+}
+
+impl ServiceOptions {
+    pub fn merge_from(&mut self, other: &ServiceOptions) {
+        for (base, value) in [
+            (&mut self.user, &other.user),
+            (&mut self.working_dir, &other.working_dir),
+            (&mut self.hostname, &other.hostname),
+            (&mut self.container_name, &other.container_name),
+            (&mut self.network_mode, &other.network_mode),
+            (&mut self.stop_grace_period, &other.stop_grace_period),
+        ] {
+            if value.is_some() {
+                *base = value.clone();
+            }
+        }
+        if other.entrypoint.is_some() {
+            self.entrypoint = other.entrypoint.clone();
+        }
+        if other.restart.is_some() {
+            self.restart = other.restart.clone();
+        }
+        if other.logging.is_some() {
+            self.logging = other.logging.clone();
+        }
+        merge_pairs(&mut self.labels, &other.labels);
+        merge_pairs(&mut self.sysctls, &other.sysctls);
+        for (base, items) in [
+            (&mut self.profiles, &other.profiles),
+            (&mut self.extra_hosts, &other.extra_hosts),
+            (&mut self.dns, &other.dns),
+            (&mut self.cap_add, &other.cap_add),
+            (&mut self.cap_drop, &other.cap_drop),
+            (&mut self.security_opt, &other.security_opt),
+        ] {
+            merge_list(base, items);
+        }
+        for ulimit in &other.ulimits {
+            self.ulimits.retain(|existing| existing.name != ulimit.name);
+            self.ulimits.push(ulimit.clone());
+        }
+        self.tty |= other.tty;
+        self.stdin_open |= other.stdin_open;
+        self.init |= other.init;
+        self.read_only |= other.read_only;
+    }
+
+    pub fn configured_count(&self) -> usize {
+        ServiceProperty::all()
+            .into_iter()
+            .filter(|property| {
+                property.display(self) != property.display(&ServiceOptions::default())
+            })
+            .count()
+    }
+
+    pub fn render(&self, output: &mut String) {
+        let scalars = [
+            ("user", &self.user),
+            ("working_dir", &self.working_dir),
+            ("hostname", &self.hostname),
+            ("container_name", &self.container_name),
+            ("network_mode", &self.network_mode),
+            ("stop_grace_period", &self.stop_grace_period),
+        ];
+
+        if let Some(restart) = self.restart.as_ref() {
+            output.push_str(&format!("    restart: {}\n", restart.as_compose()));
+        }
+        if let Some(entrypoint) = self.entrypoint.as_ref() {
+            entrypoint.render("entrypoint", output);
+        }
+        for (key, value) in scalars {
+            if let Some(value) = value {
+                output.push_str(&format!("    {key}: {}\n", yaml_quote(value)));
+            }
+        }
+
+        for (key, enabled) in [
+            ("tty", self.tty),
+            ("stdin_open", self.stdin_open),
+            ("init", self.init),
+            ("read_only", self.read_only),
+        ] {
+            if enabled {
+                output.push_str(&format!("    {key}: true\n"));
+            }
+        }
+
+        for (key, pairs) in [("labels", &self.labels), ("sysctls", &self.sysctls)] {
+            if !pairs.is_empty() {
+                output.push_str(&format!("    {key}:\n"));
+                for pair in pairs {
+                    output.push_str(&format!(
+                        "      {}: {}\n",
+                        pair.key,
+                        yaml_quote(&pair.value)
+                    ));
+                }
+            }
+        }
+
+        for (key, items) in [
+            ("profiles", &self.profiles),
+            ("extra_hosts", &self.extra_hosts),
+            ("dns", &self.dns),
+            ("cap_add", &self.cap_add),
+            ("cap_drop", &self.cap_drop),
+            ("security_opt", &self.security_opt),
+        ] {
+            if !items.is_empty() {
+                output.push_str(&format!("    {key}:\n"));
+                for item in items {
+                    output.push_str(&format!("      - {}\n", yaml_quote(item)));
+                }
+            }
+        }
+
+        if !self.ulimits.is_empty() {
+            output.push_str("    ulimits:\n");
+            for ulimit in &self.ulimits {
+                match ulimit.hard {
+                    Some(hard) => output.push_str(&format!(
+                        "      {}:\n        soft: {}\n        hard: {}\n",
+                        ulimit.name, ulimit.soft, hard
+                    )),
+                    None => output.push_str(&format!("      {}: {}\n", ulimit.name, ulimit.soft)),
+                }
+            }
+        }
+
+        if let Some(logging) = self.logging.as_ref() {
+            output.push_str(&format!("    logging:\n      driver: {}\n", logging.driver));
+            if !logging.options.is_empty() {
+                output.push_str("      options:\n");
+                for pair in &logging.options {
+                    output.push_str(&format!(
+                        "        {}: {}\n",
+                        pair.key,
+                        yaml_quote(&pair.value)
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertySheet {
+    Service,
+    Deploy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetProperty {
+    Service(ServiceProperty),
+    Deploy(DeployProperty),
+}
+
+impl PropertySheet {
+    pub fn next(self) -> Self {
+        match self {
+            PropertySheet::Service => PropertySheet::Deploy,
+            PropertySheet::Deploy => PropertySheet::Service,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            PropertySheet::Service => "Service Properties",
+            PropertySheet::Deploy => "Deploy & Resources",
+        }
+    }
+
+    pub fn properties(self) -> Vec<SheetProperty> {
+        match self {
+            PropertySheet::Service => ServiceProperty::all()
+                .into_iter()
+                .map(SheetProperty::Service)
+                .collect(),
+            PropertySheet::Deploy => DeployProperty::all()
+                .into_iter()
+                .map(SheetProperty::Deploy)
+                .collect(),
+        }
+    }
+}
+
+impl SheetProperty {
+    pub fn label(self) -> &'static str {
+        match self {
+            SheetProperty::Service(property) => property.label(),
+            SheetProperty::Deploy(property) => property.label(),
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            SheetProperty::Service(property) => property.hint(),
+            SheetProperty::Deploy(property) => property.hint(),
+        }
+    }
+
+    pub fn is_toggle(self) -> bool {
+        match self {
+            SheetProperty::Service(property) => property.is_toggle(),
+            SheetProperty::Deploy(property) => property.is_toggle(),
+        }
+    }
+
+    pub fn toggle(self, image: &mut ImageEntry) {
+        match self {
+            SheetProperty::Service(property) => property.toggle(&mut image.options),
+            SheetProperty::Deploy(property) => property.toggle(&mut image.deploy),
+        }
+    }
+
+    pub fn display(self, image: &ImageEntry) -> String {
+        match self {
+            SheetProperty::Service(property) => property.display(&image.options),
+            SheetProperty::Deploy(property) => property.display(&image.deploy),
+        }
+    }
+
+    pub fn apply(self, image: &mut ImageEntry, input: &str) -> Result<(), String> {
+        match self {
+            SheetProperty::Service(property) => property.apply(&mut image.options, input),
+            SheetProperty::Deploy(property) => property.apply(&mut image.deploy, input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_policies_parse_and_render() {
+        for (input, compose) in [
+            ("no", "\"no\""),
+            (" always ", "always"),
+            ("on-failure", "on-failure"),
+            ("on-failure:3", "on-failure:3"),
+            ("unless-stopped", "unless-stopped"),
+        ] {
+            let policy = RestartPolicy::parse(input).unwrap();
+            assert_eq!(policy.as_compose(), compose, "{input}");
+        }
+        assert!(RestartPolicy::parse("on-failure:x").is_err());
+        assert!(RestartPolicy::parse("sometimes").is_err());
+
+        let mut options = ServiceOptions::default();
+        ServiceProperty::Restart
+            .apply(&mut options, "always")
+            .unwrap();
+        ServiceProperty::Restart.apply(&mut options, " ").unwrap();
+        assert_eq!(options.restart, None);
+    }
+
+    #[test]
+    fn ulimits_accept_single_and_soft_hard_values() {
+        assert_eq!(
+            parse_ulimits("nproc=65535, nofile=20000:40000").unwrap(),
+            [
+                Ulimit {
+                    name: "nproc".to_string(),
+                    soft: 65535,
+                    hard: None,
+                },
+                Ulimit {
+                    name: "nofile".to_string(),
+                    soft: 20000,
+                    hard: Some(40000),
+                },
+            ]
+        );
+        assert_eq!(
+            parse_ulimits("nofile=20000:lots").unwrap_err(),
+            "invalid ulimit value '20000:lots'"
+        );
+        assert!(parse_ulimits("nofile").is_err());
+        assert!(parse_ulimits("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn logging_takes_a_driver_and_options() {
+        assert_eq!(
+            parse_logging("json-file max-size=10m, max-file=3").unwrap(),
+            Some(Logging {
+                driver: "json-file".to_string(),
+                options: vec![
+                    KeyValue {
+                        key: "max-size".to_string(),
+                        value: "10m".to_string(),
+                    },
+                    KeyValue {
+                        key: "max-file".to_string(),
+                        value: "3".to_string(),
+                    },
+                ],
+            })
+        );
+        assert_eq!(
+            parse_logging(" syslog ")
+                .unwrap()
+                .map(|logging| logging.driver),
+            Some("syslog".to_string())
+        );
+        assert_eq!(parse_logging("").unwrap(), None);
+        assert!(parse_logging("json-file max-size").is_err());
+    }
+}
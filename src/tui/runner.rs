@@ -0,0 +1,410 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+pub const DOCKER_BINARY_ENV: &str = "ACDC_DOCKER";
+const STATUS_INTERVAL: Duration = Duration::from_secs(2);
+const LOG_TAIL: &str = "200";
+const LOG_VIEWER_LIMIT: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceStatus {
+    pub service: String,
+    pub state: String,
+    pub health: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PsEntry {
+    #[serde(rename = "Service")]
+    service: String,
+    #[serde(rename = "State")]
+    state: String,
+    #[serde(rename = "Health", default)]
+    health: String,
+}
+
+enum RunnerEvent {
+    Log(String),
+    Status(Result<Vec<ServiceStatus>, String>),
+}
+
+pub struct ComposeRunner {
+    program: String,
+    sender: Sender<RunnerEvent>,
+    events: Receiver<RunnerEvent>,
+    statuses: Vec<ServiceStatus>,
+    last_status: Option<Instant>,
+    status_pending: bool,
+    available: bool,
+}
+
+pub struct LogViewer {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+    pub follow: bool,
+    child: Child,
+    receiver: Receiver<String>,
+}
+
+impl ServiceStatus {
+    pub fn label(&self) -> String {
+        match self.health.as_deref() {
+            Some(health) => format!("{} ({health})", self.state),
+            None => self.state.clone(),
+        }
+    }
+}
+
+pub fn docker_program() -> String {
+    std::env::var(DOCKER_BINARY_ENV)
+        .ok()
+        .filter(|program| !program.trim().is_empty())
+        .unwrap_or_else(|| "docker".to_string())
+}
+
+pub fn parse_ps_output(output: &str) -> Result<Vec<ServiceStatus>, String> {
+    let trimmed = output.trim();
+    let entries: Vec<PsEntry> = if trimmed.is_empty() {
+        Vec::new()
+    } else if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).map_err(|error| format!("unexpected ps output: {error}"))?
+    } else {
+        trimmed
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|error| format!("unexpected ps output: {error}"))?
+    };
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| ServiceStatus {
+            service: entry.service,
+            state: entry.state,
+            health: (!entry.health.is_empty()).then_some(entry.health),
+        })
+        .collect())
+}
+
+fn last_line(output: &[u8]) -> String {
+    String::from_utf8_lossy(output)
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+fn forward_lines(reader: impl Read + Send + 'static, sender: Sender<String>) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+impl ComposeRunner {
+    pub fn new() -> Self {
+        Self::with_program(docker_program())
+    }
+
+    fn with_program(program: String) -> Self {
+        let (sender, events) = mpsc::channel();
+        Self {
+            program,
+            sender,
+            events,
+            statuses: Vec::new(),
+            last_status: None,
+            status_pending: false,
+            available: true,
+        }
+    }
+
+    fn command(&self, files: &[String], args: &[&str]) -> Command {
+        let mut command = Command::new(&self.program);
+        command.arg("compose");
+        for file in files {
+            command.arg("-f").arg(file);
+        }
+        command.args(args);
+        command
+    }
+
+    pub fn command_line(&self, files: &[String], args: &[&str]) -> String {
+        let mut parts = vec![self.program.clone(), "compose".to_string()];
+        for file in files {
+            parts.push("-f".to_string());
+            parts.push(file.clone());
+        }
+        parts.extend(args.iter().map(ToString::to_string));
+        parts.join(" ")
+    }
+
+    fn spawn_action(&self, files: &[String], args: &[&str], label: String) {
+        let mut command = self.command(files, args);
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let line = match command.stdin(Stdio::null()).output() {
+                Ok(output) if output.status.success() => format!("{label}: done"),
+                Ok(output) => {
+                    let reason = last_line(&output.stderr);
+                    format!(
+                        "{label} failed: {}",
+                        if reason.is_empty() {
+                            output.status.to_string()
+                        } else {
+                            reason
+                        }
+                    )
+                }
+                Err(error) => format!("{label} failed: {error}"),
+            };
+            let _ = sender.send(RunnerEvent::Log(line));
+        });
+    }
+
+    pub fn up(&self, files: &[String]) {
+        self.spawn_action(files, &["up", "-d"], "compose up".to_string());
+    }
+
+    pub fn down(&self, files: &[String]) {
+        self.spawn_action(files, &["down"], "compose down".to_string());
+    }
+
+    pub fn restart(&self, files: &[String], service: &str) {
+        self.spawn_action(files, &["restart", service], format!("restart {service}"));
+    }
+
+    fn spawn_status(&mut self, files: &[String]) {
+        let mut command = self.command(files, &["ps", "--all", "--format", "json"]);
+        let sender = self.sender.clone();
+        self.status_pending = true;
+        self.last_status = Some(Instant::now());
+        thread::spawn(move || {
+            let result = match command.stdin(Stdio::null()).output() {
+                Ok(output) if output.status.success() => {
+                    parse_ps_output(&String::from_utf8_lossy(&output.stdout))
+                }
+                Ok(output) => Err(last_line(&output.stderr)),
+                Err(error) => Err(error.to_string()),
+            };
+            let _ = sender.send(RunnerEvent::Status(result));
+        });
+    }
+
+    pub fn poll(&mut self, files: &[String]) -> Vec<String> {
+        let mut logs = Vec::new();
+        let mut refresh = false;
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                RunnerEvent::Log(line) => {
+                    refresh = true;
+                    logs.push(line);
+                }
+                RunnerEvent::Status(Ok(statuses)) => {
+                    self.status_pending = false;
+                    self.statuses = statuses;
+                }
+                RunnerEvent::Status(Err(error)) => {
+                    self.status_pending = false;
+                    self.statuses.clear();
+                    if self.available && !error.is_empty() {
+                        logs.push(format!("status unavailable: {error}"));
+                    }
+                    self.available = false;
+                }
+            }
+        }
+        if refresh {
+            self.available = true;
+            self.last_status = None;
+        }
+
+        let due = self
+            .last_status
+            .is_none_or(|last| last.elapsed() >= STATUS_INTERVAL);
+        let root_written = files.first().is_some_and(|file| Path::new(file).exists());
+        if self.available && due && !self.status_pending && root_written {
+            self.spawn_status(files);
+        }
+        logs
+    }
+
+    pub fn status_for(&self, service: &str) -> Option<&ServiceStatus> {
+        self.statuses
+            .iter()
+            .find(|status| status.service == service)
+    }
+
+    pub fn open_logs(&self, files: &[String], service: Option<&str>) -> Result<LogViewer, String> {
+        let mut args = vec!["logs", "--follow", "--no-color", "--tail", LOG_TAIL];
+        args.extend(service);
+        let mut child = self
+            .command(files, &args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| format!("{}: {error}", self.program))?;
+
+        let (sender, receiver) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, sender);
+        }
+
+        Ok(LogViewer {
+            title: match service {
+                Some(service) => format!("Logs: {service}"),
+                None => "Logs: all services".to_string(),
+            },
+            lines: Vec::new(),
+            scroll: 0,
+            follow: true,
+            child,
+            receiver,
+        })
+    }
+}
+
+impl LogViewer {
+    pub fn poll(&mut self) {
+        while let Ok(line) = self.receiver.try_recv() {
+            self.lines.push(line);
+        }
+        if self.lines.len() > LOG_VIEWER_LIMIT {
+            let excess = self.lines.len() - LOG_VIEWER_LIMIT;
+            self.lines.drain(..excess);
+            self.scroll = self.scroll.saturating_sub(excess);
+        }
+        if self.follow {
+            self.scroll = self.lines.len().saturating_sub(1);
+        }
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.follow = false;
+        self.scroll = self.scroll.saturating_sub(amount);
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll = (self.scroll + amount).min(self.lines.len().saturating_sub(1));
+        self.follow = self.scroll + 1 >= self.lines.len();
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.follow = false;
+        self.scroll = 0;
+    }
+
+    pub fn follow(&mut self) {
+        self.follow = true;
+        self.scroll = self.lines.len().saturating_sub(1);
+    }
+}
+
+impl Drop for LogViewer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poll_until(
+        runner: &mut ComposeRunner,
+        files: &[String],
+        done: impl Fn(&ComposeRunner, &[String]) -> bool,
+    ) -> Vec<String> {
+        let mut logs = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            logs.extend(runner.poll(files));
+            if done(runner, &logs) {
+                return logs;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        panic!("runner did not finish: {logs:?}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runner_drives_compose_through_the_docker_binary() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("acdc-runner-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let calls = dir.join("calls.log");
+        let script = dir.join("docker");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho \"$@\" >> {}\ncase \"$*\" in\n  *\" ps \"*) echo '{{\"Service\":\"web\",\"State\":\"running\",\"Health\":\"healthy\"}}' ;;\n  *\" down\"*) echo \"network busy\" >&2; exit 1 ;;\nesac\n",
+                calls.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let compose = dir.join("compose.yaml").to_string_lossy().to_string();
+        std::fs::write(&compose, "services: {}\n").unwrap();
+        let files = vec![compose.clone()];
+
+        let mut runner = ComposeRunner::with_program(script.to_string_lossy().to_string());
+        runner.up(&files);
+        let logs = poll_until(&mut runner, &files, |runner, logs| {
+            !logs.is_empty() && runner.status_for("web").is_some()
+        });
+        assert_eq!(logs, ["compose up: done"]);
+        assert_eq!(
+            runner
+                .status_for("web")
+                .map(ServiceStatus::label)
+                .as_deref(),
+            Some("running (healthy)")
+        );
+
+        runner.down(&files);
+        let logs = poll_until(&mut runner, &files, |_, logs| !logs.is_empty());
+        assert_eq!(logs, ["compose down failed: network busy"]);
+
+        let calls = std::fs::read_to_string(&calls).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let calls: Vec<&str> = calls.lines().collect();
+        for args in ["up -d", "ps --all --format json", "down"] {
+            assert!(
+                calls.contains(&format!("compose -f {compose} {args}").as_str()),
+                "{calls:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_ps_output_accepts_arrays_and_lines() {
+        let array = parse_ps_output(r#"[{"Service":"db","State":"exited","Health":""}]"#).unwrap();
+        assert_eq!(array[0].label(), "exited");
+        let lines = parse_ps_output("{\"Service\":\"a\",\"State\":\"running\"}\n{\"Service\":\"b\",\"State\":\"created\"}\n").unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(parse_ps_output("not json").is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use std::path::Path;
+
+use rand::{Rng, distr::Alphanumeric};
+
+use crate::tui::app::{EnvVar, SecretEntry};
+
+pub const SECRET_MASK: &str = "********";
+pub const SECRETS_DIR: &str = "secrets";
+
+const SECRET_KEY_MARKERS: [&str; 6] = [
+    "PASSWORD",
+    "_PASS",
+    "TOKEN",
+    "SECRET",
+    "API_KEY",
+    "PRIVATE_KEY",
+];
+
+pub fn is_secret_key(key: &str) -> bool {
+    let upper = key.to_ascii_uppercase();
+    !upper.ends_with("_FILE")
+        && SECRET_KEY_MARKERS
+            .iter()
+            .any(|marker| upper.contains(marker))
+}
+
+pub fn is_interpolated(value: &str) -> bool {
+    value.trim_start().starts_with("${")
+}
+
+pub fn masked_value(env: &EnvVar) -> &str {
+    if is_secret_key(&env.key) && !env.value.is_empty() && !is_interpolated(&env.value) {
+        SECRET_MASK
+    } else {
+        env.value.as_str()
+    }
+}
+
+pub fn generate_secret(length: usize) -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+pub fn secret_name(service_name: &str, key: &str) -> String {
+    format!("{}_{}", service_name, key)
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' {
+                ch.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub fn interpolation_name(service_name: &str, key: &str) -> String {
+    format!("{}_{}", service_name, key)
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub fn secret_file_path(name: &str) -> String {
+    format!("./{SECRETS_DIR}/{name}.txt")
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::{
+        fs::{OpenOptions, Permissions},
+        io::Write,
+        os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    };
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.set_permissions(Permissions::from_mode(0o600))?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+pub fn write_dotenv(path: &Path, entries: &[EnvVar]) -> std::io::Result<()> {
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = existing.lines().map(ToString::to_string).collect();
+
+    for entry in entries {
+        let line = format!("{}={}", entry.key, entry.value);
+        let prefix = format!("{}=", entry.key);
+        match lines
+            .iter_mut()
+            .find(|existing| existing.starts_with(&prefix))
+        {
+            Some(existing) => *existing = line,
+            None => lines.push(line),
+        }
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    write_private(path, output.as_bytes())
+}
+
+pub fn write_secret_files(base: &Path, secrets: &[SecretEntry]) -> std::io::Result<()> {
+    for secret in secrets.iter().filter(|secret| !secret.value.is_empty()) {
+        let path = base.join(secret.file.trim_start_matches("./"));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_private(&path, secret.value.as_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolation_name_is_a_valid_variable() {
+        assert_eq!(
+            interpolation_name("web-app", "db_password"),
+            "WEB_APP_DB_PASSWORD"
+        );
+        assert_eq!(interpolation_name("api.v2", "TOKEN"), "API_V2_TOKEN");
+    }
+
+    #[test]
+    fn secret_name_is_a_valid_key_and_file_name() {
+        assert_eq!(secret_name("web-app", "DB_PASSWORD"), "web-app_db_password");
+        assert_eq!(secret_name("api", "../key: x"), "api____key__x");
+        assert_eq!(
+            secret_file_path(&secret_name("a/b", "K")),
+            "./secrets/a_b_k.txt"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secret_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let base = std::env::temp_dir().join(format!("acdc-secrets-{}", std::process::id()));
+        std::fs::create_dir_all(&base).unwrap();
+        let dotenv = base.join(".env");
+        std::fs::write(&dotenv, "OTHER=1\n").unwrap();
+        write_dotenv(
+            &dotenv,
+            &[EnvVar {
+                key: "WEB_DB_PASSWORD".to_string(),
+                value: "hunter2".to_string(),
+            }],
+        )
+        .unwrap();
+        write_secret_files(
+            &base,
+            &[SecretEntry {
+                name: "web_token".to_string(),
+                file: secret_file_path("web_token"),
+                value: "abc".to_string(),
+            }],
+        )
+        .unwrap();
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dotenv), 0o600);
+        assert_eq!(
+            std::fs::read_to_string(&dotenv).unwrap(),
+            "OTHER=1\nWEB_DB_PASSWORD=hunter2\n"
+        );
+        assert_eq!(mode(&base.join("secrets/web_token.txt")), 0o600);
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -1,23 +1,51 @@
 pub struct TabStats<'a> {
     pub project_name: &'a str,
+    pub environment: &'a str,
     pub images_count: usize,
     pub exposed_ports_count: usize,
     pub volumes_count: usize,
+    pub orphan_volumes_count: usize,
+    pub list_order: &'a str,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TabCommand {
     RenameProject,
+    ApplyTemplate,
+    ScanProject,
+    SwitchEnvironment,
+    AddEnvironment,
+    ComposeUp,
+    ComposeDown,
+    ProjectLogs,
+    ExportProject,
+    ToggleHostPortCheck,
     NewImage,
+    ImportRun,
     EditImage,
     SetImageCommand,
     AddImageEnv,
+    ImportEnvFile,
     RemoveImageEnv,
+    GenerateSecret,
+    EditHealthcheck,
+    EditServiceProperties,
+    EditDeploy,
+    EditBuild,
+    EditOverride,
+    RestartService,
+    ServiceLogs,
+    DuplicateService,
     DeleteImage,
     MountImageVolume,
     RemoveImageVolume,
+    EditMounts,
     AddVolume,
+    EditVolume,
     DeleteVolume,
+    MoveUp,
+    MoveDown,
+    CycleOrder,
     EditEnv,
 }
 
@@ -63,29 +91,63 @@ impl Tab {
 
     pub fn keybind_hint(self) -> &'static str {
         match self {
-            Tab::Project => "r rename project",
+            Tab::Project => {
+                "r rename project, t apply template, i scan project, e switch environment, a add environment, u up, d down, L logs, x export, P host port check"
+            }
             Tab::Images => {
-                "n new image, e edit image, c command, a add env, x remove env, d delete image, v mount volume, u unmount"
+                "Enter details, n new image, i import docker run, e edit image, c command/entrypoint, a add env, f env file, x remove env, s generate secret, H healthcheck, o properties, r resources, b build, E env override, R restart, L logs, D duplicate, d delete image, v mount volume, m mount options, u unmount, J/K move, S sort"
             }
-            Tab::Volume => "a add volume, d delete volume",
+            Tab::Volume => "a add volume, e edit volume, d delete volume, J/K move, S sort",
             Tab::Env => "e edit env",
         }
     }
 
     pub fn action_labels(self) -> &'static [&'static str] {
         match self {
-            Tab::Project => &["R: rename project"],
+            Tab::Project => &[
+                "R: rename project",
+                "T: apply template",
+                "I: scan project",
+                "E: switch environment",
+                "A: add environment",
+                "U: compose up -d",
+                "D: compose down",
+                "Shift-L: project logs",
+                "X: export project",
+                "Shift-P: toggle host port check",
+            ],
             Tab::Images => &[
+                "Enter: service detail",
                 "N: new image",
+                "I: import docker run",
                 "E: edit image",
-                "C: set command",
+                "C: command/entrypoint",
                 "A: add env",
+                "F: import/reference env file",
                 "X: remove env",
+                "S: generate secret",
+                "Shift-H: healthcheck",
+                "O: service properties",
+                "R: resources/deploy",
+                "B: build from Dockerfile",
+                "Shift-E: environment override",
+                "Shift-R: restart service",
+                "Shift-L: service logs",
+                "Shift-D: duplicate service",
                 "D: delete image",
                 "V: mount volume",
+                "M: mount options",
                 "U: unmount volume",
+                "Shift-J/K: move service",
+                "Shift-S: sort order",
+            ],
+            Tab::Volume => &[
+                "A: add volume",
+                "E: edit volume",
+                "D: delete volume",
+                "Shift-J/K: move volume",
+                "Shift-S: sort order",
             ],
-            Tab::Volume => &["A: add volume", "D: delete volume"],
             Tab::Env => &["E: edit env"],
         }
     }
@@ -93,21 +155,27 @@ impl Tab {
     pub fn active_sidebar_text(self, stats: &TabStats<'_>, actions_text: &str) -> String {
         match self {
             Tab::Project => format!(
-                "Directory: {}\nTemp: 74°C\nCPU: 12%\nMem: 418MB\n\nAction: {}",
-                stats.project_name, actions_text
+                "Directory: {}\nEnvironment: {}\nTemp: 74°C\nCPU: 12%\nMem: 418MB\n\nAction: {}",
+                stats.project_name, stats.environment, actions_text
             ),
             Tab::Images => format!(
-                "Loaded images: {}\nExposed ports: {}\n\nAction: {}",
-                stats.images_count, stats.exposed_ports_count, actions_text
+                "Loaded images: {}\nExposed ports: {}\nOrder: {}\n\nAction: {}",
+                stats.images_count, stats.exposed_ports_count, stats.list_order, actions_text
+            ),
+            Tab::Volume => format!(
+                "Volumes: {}\nOrphans: {}\nOrder: {}\n\nAction: {}",
+                stats.volumes_count, stats.orphan_volumes_count, stats.list_order, actions_text
+            ),
+            Tab::Env => format!(
+                "Environment settings\nplaceholder\n\nAction: {}",
+                actions_text
             ),
-            Tab::Volume => format!("Volumes: {}\n\nAction: {}", stats.volumes_count, actions_text),
-            Tab::Env => format!("Environment settings\nplaceholder\n\nAction: {}", actions_text),
         }
     }
 
     pub fn inactive_summary(self, stats: &TabStats<'_>) -> String {
         match self {
-            Tab::Project => "Compose preview".to_string(),
+            Tab::Project => format!("Compose preview ({})", stats.environment),
             Tab::Images => format!("{} images", stats.images_count),
             Tab::Volume => format!("{} volumes", stats.volumes_count),
             Tab::Env => "Env vars".to_string(),
@@ -117,16 +185,41 @@ impl Tab {
     pub fn command_for_key(self, key: char) -> Option<TabCommand> {
         match (self, key) {
             (Tab::Project, 'r') => Some(TabCommand::RenameProject),
+            (Tab::Project, 't') => Some(TabCommand::ApplyTemplate),
+            (Tab::Project, 'i') => Some(TabCommand::ScanProject),
+            (Tab::Project, 'e') => Some(TabCommand::SwitchEnvironment),
+            (Tab::Project, 'a') => Some(TabCommand::AddEnvironment),
+            (Tab::Project, 'u') => Some(TabCommand::ComposeUp),
+            (Tab::Project, 'd') => Some(TabCommand::ComposeDown),
+            (Tab::Project, 'L') => Some(TabCommand::ProjectLogs),
+            (Tab::Project, 'x') => Some(TabCommand::ExportProject),
+            (Tab::Project, 'P') => Some(TabCommand::ToggleHostPortCheck),
             (Tab::Images, 'n') => Some(TabCommand::NewImage),
+            (Tab::Images, 'i') => Some(TabCommand::ImportRun),
             (Tab::Images, 'e') => Some(TabCommand::EditImage),
             (Tab::Images, 'c') => Some(TabCommand::SetImageCommand),
             (Tab::Images, 'a') => Some(TabCommand::AddImageEnv),
+            (Tab::Images, 'f') => Some(TabCommand::ImportEnvFile),
             (Tab::Images, 'x') => Some(TabCommand::RemoveImageEnv),
+            (Tab::Images, 's') => Some(TabCommand::GenerateSecret),
+            (Tab::Images, 'H') => Some(TabCommand::EditHealthcheck),
+            (Tab::Images, 'o') => Some(TabCommand::EditServiceProperties),
+            (Tab::Images, 'r') => Some(TabCommand::EditDeploy),
+            (Tab::Images, 'b') => Some(TabCommand::EditBuild),
+            (Tab::Images, 'E') => Some(TabCommand::EditOverride),
+            (Tab::Images, 'R') => Some(TabCommand::RestartService),
+            (Tab::Images, 'L') => Some(TabCommand::ServiceLogs),
+            (Tab::Images, 'D') => Some(TabCommand::DuplicateService),
             (Tab::Images, 'd') => Some(TabCommand::DeleteImage),
             (Tab::Images, 'v') => Some(TabCommand::MountImageVolume),
+            (Tab::Images, 'm') => Some(TabCommand::EditMounts),
             (Tab::Images, 'u') => Some(TabCommand::RemoveImageVolume),
             (Tab::Volume, 'a') => Some(TabCommand::AddVolume),
+            (Tab::Volume, 'e') => Some(TabCommand::EditVolume),
             (Tab::Volume, 'd') => Some(TabCommand::DeleteVolume),
+            (Tab::Images | Tab::Volume, 'K') => Some(TabCommand::MoveUp),
+            (Tab::Images | Tab::Volume, 'J') => Some(TabCommand::MoveDown),
+            (Tab::Images | Tab::Volume, 'S') => Some(TabCommand::CycleOrder),
             (Tab::Env, 'e') => Some(TabCommand::EditEnv),
             _ => None,
         }
@@ -147,3 +240,19 @@ impl Tab {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn p_is_left_for_writing_the_compose_file() {
+        for tab in Tab::all() {
+            assert_eq!(tab.command_for_key('p'), None, "{}", tab.title());
+        }
+        assert_eq!(
+            Tab::Project.command_for_key('P'),
+            Some(TabCommand::ToggleHostPortCheck)
+        );
+    }
+}
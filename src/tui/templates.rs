@@ -0,0 +1,337 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::api;
+use crate::tui::{
+    app::{App, EnvVar, ImageEntry, VolumeEntry, VolumeMount},
+    command::CommandSpec,
+    config_dir, healthcheck, knowledge,
+    properties::{KeyValue, ServiceOptions, ServiceProperty},
+};
+
+const BUNDLED_TEMPLATES: [&str; 4] = [
+    include_str!("../../assets/templates/postgres-pgadmin.toml"),
+    include_str!("../../assets/templates/redis-app.toml"),
+    include_str!("../../assets/templates/traefik-whoami.toml"),
+    include_str!("../../assets/templates/elk.toml"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateSource {
+    Bundled,
+    User,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StackTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub services: Vec<TemplateService>,
+    #[serde(default)]
+    pub volumes: Vec<TemplateVolume>,
+    #[serde(skip, default = "bundled_source")]
+    pub source: TemplateSource,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateService {
+    pub name: String,
+    pub image: String,
+    #[serde(default = "default_tag_query")]
+    pub tag: String,
+    #[serde(default)]
+    pub ports: String,
+    pub command: Option<String>,
+    #[serde(default)]
+    pub mounts: Vec<String>,
+    #[serde(default)]
+    pub env: Vec<String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub restart: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateVolume {
+    pub name: String,
+}
+
+fn bundled_source() -> TemplateSource {
+    TemplateSource::Bundled
+}
+
+fn default_tag_query() -> String {
+    "latest".to_string()
+}
+
+impl TemplateService {
+    pub fn namespace_and_repo(&self) -> (String, String) {
+        match self.image.trim().split_once('/') {
+            Some((namespace, repo)) => (namespace.to_string(), repo.to_string()),
+            None => ("library".to_string(), self.image.trim().to_string()),
+        }
+    }
+}
+
+pub fn user_templates_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("templates"))
+}
+
+pub fn load_templates() -> (Vec<StackTemplate>, Vec<String>) {
+    let mut templates = Vec::new();
+    let mut warnings = Vec::new();
+
+    for raw in BUNDLED_TEMPLATES {
+        match toml::from_str::<StackTemplate>(raw) {
+            Ok(template) => templates.push(template),
+            Err(error) => warnings.push(format!("bundled template invalid: {error}")),
+        }
+    }
+
+    let Some(dir) = user_templates_dir() else {
+        return (templates, warnings);
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return (templates, warnings);
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|raw| {
+                toml::from_str::<StackTemplate>(&raw).map_err(|error| error.to_string())
+            });
+        match parsed {
+            Ok(mut template) => {
+                template.source = TemplateSource::User;
+                templates.retain(|existing| existing.name != template.name);
+                templates.push(template);
+            }
+            Err(error) => warnings.push(format!("skipped template {}: {error}", path.display())),
+        }
+    }
+
+    (templates, warnings)
+}
+
+pub fn find_template(name: &str) -> Option<StackTemplate> {
+    let (templates, _) = load_templates();
+    templates.into_iter().find(|template| template.name == name)
+}
+
+pub async fn resolve_template_tag(namespace: &str, repo: &str, query: &str) -> String {
+    match api::list_docker_hub_tags(namespace, repo).await {
+        Ok(all_tags) if all_tags.iter().any(|tag| tag == query) => query.to_string(),
+        Ok(all_tags) => api::filter_tags(&all_tags, query, 1)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| query.to_string()),
+        Err(_) => query.to_string(),
+    }
+}
+
+pub async fn resolve_template_tags(template: &StackTemplate) -> Vec<String> {
+    let mut tags = Vec::with_capacity(template.services.len());
+    for service in &template.services {
+        let (namespace, repo) = service.namespace_and_repo();
+        tags.push(resolve_template_tag(&namespace, &repo, &service.tag).await);
+    }
+    tags
+}
+
+pub fn apply_template(app: &mut App, template: &StackTemplate, tags: &[String]) -> Vec<String> {
+    let mut logs = Vec::new();
+    app.refresh_listening_ports();
+
+    let mut volume_names = Vec::new();
+    for volume in &template.volumes {
+        let name = app.unique_volume_name(&volume.name);
+        if name != volume.name {
+            logs.push(format!("volume {} exists; using {name}", volume.name));
+        }
+        app.volumes.push(VolumeEntry::new(name.clone()));
+        volume_names.push((volume.name.clone(), name));
+    }
+
+    let renamed: Vec<(String, String)> = template
+        .services
+        .iter()
+        .map(|service| (service.name.clone(), app.unique_service_name(&service.name)))
+        .collect();
+    let rename = |name: &str| {
+        renamed
+            .iter()
+            .find(|(original, _)| original == name)
+            .map(|(_, unique)| unique.clone())
+            .unwrap_or_else(|| name.to_string())
+    };
+
+    for (index, service) in template.services.iter().enumerate() {
+        let (namespace, repo) = service.namespace_and_repo();
+        let tag = tags
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| service.tag.clone());
+
+        let mounts = service
+            .mounts
+            .iter()
+            .filter_map(|mount| mount.split_once(':'))
+            .map(|(source, target)| {
+                let source = volume_names
+                    .iter()
+                    .find(|(original, _)| original == source)
+                    .map_or(source, |(_, unique)| unique.as_str());
+                VolumeMount::new(source, target)
+            })
+            .collect();
+        let env_vars = service
+            .env
+            .iter()
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) => EnvVar {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                },
+                None => EnvVar {
+                    key: pair.clone(),
+                    value: String::new(),
+                },
+            })
+            .collect();
+
+        let healthcheck = knowledge::lookup(&namespace, &repo)
+            .and_then(|entry| entry.healthcheck.as_deref())
+            .map(healthcheck::from_shell);
+
+        let mut options = ServiceOptions::default();
+        for label in &service.labels {
+            match label.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => options.labels.push(KeyValue {
+                    key: key.trim().to_string(),
+                    value: value.trim().to_string(),
+                }),
+                _ => logs.push(format!(
+                    "{}: expected key=value label, got '{label}'",
+                    service.name
+                )),
+            }
+        }
+        if let Some(restart) = service.restart.as_deref()
+            && let Err(error) = ServiceProperty::Restart.apply(&mut options, restart)
+        {
+            logs.push(format!("{}: {error}", service.name));
+        }
+
+        let service_name = rename(&service.name);
+        let port_mapping = app.free_port_mapping(&service.ports);
+        if port_mapping != service.ports {
+            logs.push(format!(
+                "{service_name}: host port taken; using {port_mapping}"
+            ));
+        }
+        logs.push(format!("added {service_name} ({namespace}/{repo}:{tag})"));
+        app.images.push(ImageEntry {
+            service_name,
+            namespace,
+            repo,
+            tag,
+            port_mapping,
+            command: service.command.as_deref().and_then(CommandSpec::parse),
+            mounts,
+            env_vars,
+            depends_on: service.depends_on.iter().map(|name| rename(name)).collect(),
+            healthcheck,
+            options,
+            ..ImageEntry::default()
+        });
+    }
+
+    if !app.images.is_empty() {
+        app.images_selected = app.images.len() - 1;
+    }
+    logs.push(format!("applied template {}", template.name));
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(name: &str) -> StackTemplate {
+        let raw = BUNDLED_TEMPLATES
+            .iter()
+            .find(|raw| raw.starts_with(&format!("name = \"{name}\"")))
+            .unwrap();
+        toml::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn bundled_templates_parse() {
+        let (templates, warnings) = load_templates();
+        assert!(
+            warnings
+                .iter()
+                .all(|warning| !warning.starts_with("bundled")),
+            "{warnings:?}"
+        );
+        assert!(templates.len() >= BUNDLED_TEMPLATES.len());
+    }
+
+    #[test]
+    fn applying_a_template_twice_keeps_stacks_apart() {
+        let mut app = App::new();
+        app.check_host_ports = false;
+        let template = template("postgres-pgadmin");
+        apply_template(&mut app, &template, &[]);
+        let logs = apply_template(&mut app, &template, &[]);
+
+        let names: Vec<&str> = app
+            .images
+            .iter()
+            .map(|image| image.service_name.as_str())
+            .collect();
+        assert_eq!(names, ["db", "pgadmin", "db_2", "pgadmin_2"]);
+        assert_eq!(app.images[3].depends_on, ["db_2"]);
+        let volumes: Vec<&str> = app
+            .volumes
+            .iter()
+            .map(|volume| volume.name.as_str())
+            .collect();
+        assert_eq!(volumes, ["pgdata", "pgdata_2"]);
+        assert_eq!(app.images[2].mounts[0].source, "pgdata_2");
+        assert_eq!(app.images[0].port_mapping, "5432:5432");
+        assert_eq!(app.images[2].port_mapping, "5433:5432");
+        assert_eq!(app.images[3].port_mapping, "5051:80");
+        assert!(
+            logs.iter()
+                .any(|line| line == "db_2: host port taken; using 5433:5432"),
+            "{logs:?}"
+        );
+    }
+
+    #[test]
+    fn labels_keep_commas_in_values() {
+        let mut app = App::new();
+        app.check_host_ports = false;
+        let mut template = template("traefik-whoami");
+        template.services[1].labels =
+            vec!["traefik.http.routers.whoami.rule=Host(`a.localhost`, `b.localhost`)".to_string()];
+        apply_template(&mut app, &template, &[]);
+
+        let labels = &app.images[1].options.labels;
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].value, "Host(`a.localhost`, `b.localhost`)");
+    }
+}
@@ -1,17 +1,28 @@
+use std::path::Path;
+
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
 use crate::tui::{
     app::{
-        App, ConfigureField, EnvInputField, FocusArea, ModalState, MountExistingField,
-        MountInputField,
+        App, BuildField, ConfigureField, EnvFileField, EnvInputField, FocusArea, HealthcheckField,
+        ImageEntry, ModalState, MountExistingField, MountInputField, OverrideField, SecretStorage,
     },
+    command::CommandField,
+    detail::DetailSection,
+    engine, env_files,
+    export::{self, ExportFormat},
+    knowledge,
+    paths::{self, PathAssist},
+    ports, secrets,
     tab::{Tab, TabStats},
+    templates::TemplateSource,
     theme::THEME,
+    volumes::{self, MountProperty, VolumeProperty},
 };
 
 const ACTION_SPACING: &str = "\n        ";
@@ -73,10 +84,76 @@ fn pane_block(title: &str, active: bool) -> Block<'_> {
         .border_style(border_style)
 }
 
-pub fn render(frame: &mut Frame, app: &App) {
-    let area = frame.area();
-    frame.render_widget(Clear, area);
+fn path_assist_text(assist: &PathAssist, input: &str, base: &Path) -> String {
+    if let Some(browser) = assist.browser.as_ref() {
+        let (start, end) = visible_window(browser.entries.len(), browser.selected, 10);
+        let entries = browser.entries[start..end]
+            .iter()
+            .enumerate()
+            .map(|(offset, entry)| {
+                let marker = if start + offset == browser.selected {
+                    ">"
+                } else {
+                    " "
+                };
+                format!("  {marker} {entry}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        return format!(
+            "Browse {}\n{entries}\nj/k: move  |  Enter/l: open  |  h/Backspace: up  |  Space: pick  |  Tab: back to typing",
+            browser.dir.display()
+        );
+    }
+
+    let mut lines = Vec::new();
+    if !input.trim().is_empty() && !paths::exists(base, input) {
+        lines.push(
+            "Warning: path does not exist yet (press c when writing to create it)".to_string(),
+        );
+    }
+    if !assist.candidates.is_empty() {
+        let shown: Vec<&str> = assist
+            .candidates
+            .iter()
+            .take(12)
+            .map(String::as_str)
+            .collect();
+        let more = assist.candidates.len().saturating_sub(shown.len());
+        lines.push(format!(
+            "Matches: {}{}",
+            shown.join("  "),
+            if more > 0 {
+                format!("  (+{more} more)")
+            } else {
+                String::new()
+            }
+        ));
+    }
+    lines.push("Tab: complete path  |  Down: browse directories".to_string());
+    lines.join("\n")
+}
+
+struct PropertySheetView<'a> {
+    title: &'a str,
+    header: String,
+    rows: Vec<(&'a str, String)>,
+    selected: usize,
+    input: Option<&'a str>,
+    format_hint: &'a str,
+    extra_keys: &'a str,
+}
 
+pub struct ScreenLayout {
+    pub root: Vec<Rect>,
+    pub sidebar: Vec<Rect>,
+    pub right: Vec<Rect>,
+    pub list: Rect,
+    pub detail: Rect,
+    pub popup: Rect,
+}
+
+pub fn screen_layout(area: Rect, app: &App) -> ScreenLayout {
     let root = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -86,11 +163,6 @@ pub fn render(frame: &mut Frame, app: &App) {
         ])
         .split(area);
 
-    let header = Paragraph::new("acdc - Docker Compose   |   <Tab> cycle panes   q quit")
-        .style(Style::default().fg(THEME.header_fg).bg(THEME.header_bg))
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(header, root[0]);
-
     let sidebar_width = if matches!(app.focus, FocusArea::Sidebar) {
         Constraint::Percentage(34)
     } else {
@@ -102,14 +174,7 @@ pub fn render(frame: &mut Frame, app: &App) {
         .constraints([sidebar_width, Constraint::Min(60)])
         .split(root[1]);
 
-    let tabs = Tab::all();
-    let tab_stats = TabStats {
-        project_name: &app.project_name,
-        images_count: app.images.len(),
-        exposed_ports_count: app.total_exposed_ports(),
-        volumes_count: app.volumes.len(),
-    };
-    let sidebar_constraints: Vec<Constraint> = tabs
+    let sidebar_constraints: Vec<Constraint> = Tab::all()
         .iter()
         .map(|tab| {
             if *tab == app.active_tab {
@@ -125,11 +190,371 @@ pub fn render(frame: &mut Frame, app: &App) {
         .constraints(sidebar_constraints)
         .split(body[0]);
 
+    let right_constraints = if app.log_viewer.is_some() {
+        vec![
+            Constraint::Min(8),
+            Constraint::Percentage(45),
+            Constraint::Length(8),
+        ]
+    } else {
+        vec![Constraint::Min(12), Constraint::Length(8)]
+    };
     let right = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(12), Constraint::Length(8)])
+        .constraints(right_constraints)
         .split(body[1]);
 
+    let (list, detail) = if matches!(app.active_tab, Tab::Images) {
+        let split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(right[0]);
+        (split[0], split[1])
+    } else {
+        (right[0], Rect::default())
+    };
+
+    ScreenLayout {
+        root: root.to_vec(),
+        sidebar: sidebar.to_vec(),
+        right: right.to_vec(),
+        list,
+        detail,
+        popup: centered_rect(70, 70, area),
+    }
+}
+
+pub fn tag_picker_sections(popup: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(6),
+            Constraint::Min(8),
+            Constraint::Length(3),
+        ])
+        .split(popup)
+        .to_vec()
+}
+
+pub fn list_row(area: Rect, row: u16, total: usize, selected: usize) -> Option<usize> {
+    let height = area.height.saturating_sub(2) as usize;
+    if row <= area.y || row + 1 >= area.y + area.height {
+        return None;
+    }
+    let (start, end) = visible_window(total, selected, height.max(1));
+    let index = start + (row - area.y - 1) as usize;
+    (index < end).then_some(index)
+}
+
+fn modal_buttons(modal: &ModalState) -> &'static [(&'static str, char)] {
+    match modal {
+        ModalState::ConfirmWriteCompose => &[
+            ("y: write", 'y'),
+            ("c: write + create dirs", 'c'),
+            ("n: cancel", 'n'),
+        ],
+        ModalState::ConfirmDeleteImage { .. } | ModalState::DeleteVolume { .. } => {
+            &[("y: delete", 'y'), ("n: cancel", 'n')]
+        }
+        ModalState::ConfirmPullImage { .. } => &[("y: pull", 'y'), ("n: skip", 'n')],
+        ModalState::ConfirmComposeDown => &[("y: stop", 'y'), ("n: cancel", 'n')],
+        ModalState::RemoveImageMount { .. } | ModalState::RemoveImageEnv { .. } => {
+            &[("y: remove", 'y'), ("n: cancel", 'n')]
+        }
+        _ => &[],
+    }
+}
+
+pub fn modal_button_areas(popup: Rect, modal: &ModalState) -> Vec<(Rect, &'static str, char)> {
+    let y = (popup.y + popup.height).saturating_sub(2);
+    let mut x = popup.x + 2;
+    modal_buttons(modal)
+        .iter()
+        .map(|(label, key)| {
+            let width = label.chars().count() as u16 + 4;
+            let area = Rect::new(x, y, width, 1).intersection(popup);
+            x += width + 2;
+            (area, *label, *key)
+        })
+        .collect()
+}
+
+fn render_modal_buttons(frame: &mut Frame, popup: Rect, modal: &ModalState) {
+    for (area, label, _) in modal_button_areas(popup, modal) {
+        let button = Paragraph::new(format!("[ {label} ]"))
+            .style(Style::default().fg(THEME.header_fg).bg(THEME.header_bg));
+        frame.render_widget(button, area);
+    }
+}
+
+fn render_property_sheet(frame: &mut Frame, popup: Rect, view: PropertySheetView<'_>) {
+    let PropertySheetView {
+        title,
+        header,
+        rows,
+        selected,
+        input,
+        format_hint,
+        extra_keys,
+    } = view;
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(8),
+            Constraint::Length(4),
+        ])
+        .split(popup);
+
+    let header = Paragraph::new(header).block(pane_block(title, true));
+    frame.render_widget(header, sections[0]);
+
+    let view_height = sections[1].height.saturating_sub(2) as usize;
+    let (start, end) = visible_window(rows.len(), selected, view_height.max(1));
+    let items: Vec<ListItem> = rows[start..end]
+        .iter()
+        .enumerate()
+        .map(|(offset, (label, value))| {
+            let index = start + offset;
+            let value = match (index == selected, input) {
+                (true, Some(buffer)) => format!("{buffer}_"),
+                _ => value.clone(),
+            };
+            let line = format!(
+                "{} {:<22} {}",
+                if index == selected { ">" } else { " " },
+                label,
+                value
+            );
+            if index == selected {
+                ListItem::new(line).style(Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .style(Style::default().fg(THEME.text_fg))
+        .block(pane_block("Properties", true));
+    frame.render_widget(list, sections[1]);
+
+    let hint = format!(
+        "Format: {format_hint}\n{}",
+        if input.is_some() {
+            "Type value  |  Enter: apply  |  Esc: close".to_string()
+        } else {
+            format!("j/k: move  |  Enter: edit/toggle  |  d: clear  |  {extra_keys}  |  Esc: close")
+        }
+    );
+    let hint = Paragraph::new(hint).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(hint, sections[2]);
+}
+
+fn render_service_detail(frame: &mut Frame, popup: Rect, image: &ImageEntry, modal: &ModalState) {
+    let ModalState::ServiceDetail {
+        section,
+        selected,
+        marked,
+        input,
+        adding,
+        ..
+    } = modal
+    else {
+        return;
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(8),
+            Constraint::Length(4),
+        ])
+        .split(popup);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(18), Constraint::Min(20)])
+        .split(rows[1]);
+
+    let header = Paragraph::new(format!(
+        "Service: {}  |  {}",
+        image.service_name,
+        image.describe()
+    ))
+    .block(pane_block("Service Detail", true));
+    frame.render_widget(header, rows[0]);
+
+    let sections: Vec<ListItem> = DetailSection::all()
+        .into_iter()
+        .map(|candidate| {
+            let line = format!(
+                "{} {} ({})",
+                if candidate == *section { ">" } else { " " },
+                candidate.label(),
+                candidate.items(image).len()
+            );
+            if candidate == *section {
+                ListItem::new(line).style(Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+    let sections = List::new(sections)
+        .style(Style::default().fg(THEME.text_fg))
+        .block(pane_block("Sections", false));
+    frame.render_widget(sections, columns[0]);
+
+    let mut values = section.items(image);
+    if *adding {
+        values.push(String::new());
+    }
+    let selected = if *adding { values.len() - 1 } else { *selected };
+    let view_height = columns[1].height.saturating_sub(2) as usize;
+    let (start, end) = visible_window(values.len(), selected, view_height.max(1));
+    let mut items: Vec<ListItem> = values[start..end]
+        .iter()
+        .enumerate()
+        .map(|(offset, value)| {
+            let index = start + offset;
+            let value = match (index == selected, input) {
+                (true, Some(buffer)) => format!("{buffer}_"),
+                _ => value.clone(),
+            };
+            let line = format!(
+                "{}{} {}",
+                if index == selected { ">" } else { " " },
+                if marked.contains(&index) { "*" } else { " " },
+                value
+            );
+            if index == selected {
+                ListItem::new(line).style(Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+    if items.is_empty() {
+        items.push(
+            ListItem::new("  (none, press a to add)").style(Style::default().fg(THEME.footer_fg)),
+        );
+    }
+    let list = List::new(items)
+        .style(Style::default().fg(THEME.text_fg))
+        .block(pane_block(section.label(), true));
+    frame.render_widget(list, columns[1]);
+
+    let hint = format!(
+        "Format: {}\n{}",
+        section.hint(),
+        if input.is_some() {
+            "Type value  |  Enter: apply  |  Esc: close".to_string()
+        } else {
+            format!(
+                "Tab: section  |  j/k: move  |  Enter: edit  |  a: add  |  Space: mark  |  d: delete{}  |  Esc: close",
+                if section.can_reorder() {
+                    "  |  J/K: reorder"
+                } else {
+                    ""
+                }
+            )
+        }
+    );
+    let hint = Paragraph::new(hint).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(hint, rows[2]);
+}
+
+fn service_detail_text(app: &App, index: usize, image: &ImageEntry) -> String {
+    let mut lines = vec![format!("Image: {}", image.describe())];
+    lines.push(format!(
+        "Ports: {}",
+        if image.port_mapping.is_empty() {
+            "none"
+        } else {
+            image.port_mapping.as_str()
+        }
+    ));
+    lines.push(format!(
+        "Command: {}",
+        image
+            .command
+            .as_ref()
+            .map(|command| command.display())
+            .unwrap_or_else(|| "image default".to_string())
+    ));
+    if let Some(entrypoint) = image.options.entrypoint.as_ref() {
+        lines.push(format!("Entrypoint: {}", entrypoint.display()));
+    }
+
+    lines.push(format!("Mounts: {}", image.mounts.len()));
+    for mount in &image.mounts {
+        lines.push(format!(
+            "  {:<6} {}",
+            mount.kind().label(),
+            mount.describe()
+        ));
+    }
+    lines.push(format!("Environment: {}", image.env_vars.len()));
+    for env in &image.env_vars {
+        lines.push(format!("  {}={}", env.key, secrets::masked_value(env)));
+    }
+    for file in &image.env_files {
+        lines.push(format!("  env_file {}", file.describe()));
+    }
+    if !image.env_files.is_empty() {
+        lines.push(format!(
+            "  {} from env files",
+            app.env_file_keys.var_count(image)
+        ));
+    }
+    lines.push(format!(
+        "Healthcheck: {}  |  options: {}  |  deploy: {}",
+        if image.healthcheck.is_some() {
+            "yes"
+        } else {
+            "no"
+        },
+        image.options.configured_count(),
+        image.deploy.summary()
+    ));
+
+    if let Some(yaml) = app.service_yaml(index) {
+        lines.push(String::new());
+        lines.extend(yaml.lines().map(ToString::to_string));
+    }
+    lines.join("\n")
+}
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    frame.render_widget(Clear, area);
+
+    let layout = screen_layout(area, app);
+    let root = &layout.root;
+
+    let header = Paragraph::new("acdc - Docker Compose   |   <Tab> cycle panes   q quit")
+        .style(Style::default().fg(THEME.header_fg).bg(THEME.header_bg))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, root[0]);
+
+    let tabs = Tab::all();
+    let environment = app.environment_label();
+    let tab_stats = TabStats {
+        project_name: &app.project_name,
+        environment: &environment,
+        images_count: app.images.len(),
+        exposed_ports_count: app.total_exposed_ports(),
+        volumes_count: app.volumes.len(),
+        orphan_volumes_count: app
+            .volumes
+            .iter()
+            .filter(|volume| app.volume_usage(&volume.name).is_empty())
+            .count(),
+        list_order: app.list_order.label(),
+    };
+    let sidebar = &layout.sidebar;
+    let right = &layout.right;
+    let actions_area = right[right.len() - 1];
+
     for (index, tab) in tabs.iter().enumerate() {
         let is_active = *tab == app.active_tab;
         let title = if is_active {
@@ -150,14 +575,15 @@ pub fn render(frame: &mut Frame, app: &App) {
             Style::default()
         };
 
-        let tab_panel = Paragraph::new(tab_text)
-            .style(style)
-            .block(pane_block(&title, is_active && matches!(app.focus, FocusArea::Sidebar)));
+        let tab_panel = Paragraph::new(tab_text).style(style).block(pane_block(
+            &title,
+            is_active && matches!(app.focus, FocusArea::Sidebar),
+        ));
         frame.render_widget(tab_panel, sidebar[index]);
     }
 
     let main_text = match app.active_tab {
-        Tab::Project => app.compose_yaml(),
+        Tab::Project => app.compose_preview(),
         Tab::Images => String::new(),
         Tab::Volume => String::new(),
         Tab::Env => {
@@ -174,7 +600,7 @@ pub fn render(frame: &mut Frame, app: &App) {
             ]
         } else {
             let selected = app.images_selected.min(app.images.len() - 1);
-            let list_height = right[0].height.saturating_sub(2) as usize;
+            let list_height = layout.list.height.saturating_sub(2) as usize;
             let (start, end) = visible_window(app.images.len(), selected, list_height.max(1));
 
             app.images[start..end]
@@ -183,16 +609,20 @@ pub fn render(frame: &mut Frame, app: &App) {
                 .map(|(offset, image)| {
                     let index = start + offset;
                     ListItem::new(format!(
-                        "{} {}: {}/{}:{}   ->   {}   cmd:{}   mounts:{}   env:{}",
+                        "{} {}: {}{}   ->   {}   status:{}",
                         if index == selected { "▶" } else { " " },
                         image.service_name,
-                        image.namespace,
-                        image.repo,
-                        image.tag,
+                        image.describe(),
+                        image
+                            .source_file
+                            .as_ref()
+                            .map(|file| format!(" [{file}]"))
+                            .unwrap_or_default(),
                         image.port_mapping,
-                        if image.command.is_some() { "yes" } else { "no" },
-                        image.mounts.len(),
-                        image.env_vars.len()
+                        app.runner
+                            .status_for(&image.service_name)
+                            .map(|status| status.label())
+                            .unwrap_or_else(|| "-".to_string()),
                     ))
                     .style(if index == selected {
                         Style::default().add_modifier(Modifier::BOLD)
@@ -203,10 +633,32 @@ pub fn render(frame: &mut Frame, app: &App) {
                 .collect()
         };
 
+        let images_title = match app.active_environment() {
+            Some(environment) => format!(
+                "Images ({} overrides: {})",
+                environment.name,
+                environment.overrides.len()
+            ),
+            None => "Images".to_string(),
+        };
         let images_panel = List::new(image_items)
             .style(Style::default().fg(THEME.text_fg))
-            .block(pane_block("Images", matches!(app.focus, FocusArea::Main)));
-        frame.render_widget(images_panel, right[0]);
+            .block(pane_block(
+                &images_title,
+                matches!(app.focus, FocusArea::Main),
+            ));
+        frame.render_widget(images_panel, layout.list);
+
+        let selected = app.images_selected.min(app.images.len().saturating_sub(1));
+        let detail = match app.images.get(selected) {
+            Some(image) => service_detail_text(app, selected, image),
+            None => "Select a service to see its details.".to_string(),
+        };
+        let detail = Paragraph::new(detail)
+            .style(Style::default().fg(THEME.text_fg))
+            .block(pane_block("Detail", false))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(detail, layout.detail);
     } else if matches!(app.active_tab, Tab::Volume) {
         let volume_items: Vec<ListItem> = if app.volumes.is_empty() {
             vec![
@@ -215,7 +667,7 @@ pub fn render(frame: &mut Frame, app: &App) {
             ]
         } else {
             let selected = app.volumes_selected.min(app.volumes.len() - 1);
-            let list_height = right[0].height.saturating_sub(2) as usize;
+            let list_height = layout.list.height.saturating_sub(2) as usize;
             let (start, end) = visible_window(app.volumes.len(), selected, list_height.max(1));
 
             app.volumes[start..end]
@@ -223,16 +675,19 @@ pub fn render(frame: &mut Frame, app: &App) {
                 .enumerate()
                 .map(|(offset, volume)| {
                     let index = start + offset;
+                    let usage = app.volume_usage(&volume.name);
+                    let style = match (index == selected, usage.is_empty()) {
+                        (true, _) => Style::default().add_modifier(Modifier::BOLD),
+                        (false, true) => Style::default().fg(THEME.footer_fg),
+                        (false, false) => Style::default(),
+                    };
                     ListItem::new(format!(
-                        "{} {}",
+                        "{} {}  - {}",
                         if index == selected { "▶" } else { " " },
-                        volume.name
+                        volume.describe(),
+                        volumes::usage_summary(&usage)
                     ))
-                    .style(if index == selected {
-                        Style::default().add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default()
-                    })
+                    .style(style)
                 })
                 .collect()
         };
@@ -240,11 +695,19 @@ pub fn render(frame: &mut Frame, app: &App) {
         let volume_panel = List::new(volume_items)
             .style(Style::default().fg(THEME.text_fg))
             .block(pane_block("Volumes", matches!(app.focus, FocusArea::Main)));
-        frame.render_widget(volume_panel, right[0]);
+        frame.render_widget(volume_panel, layout.list);
     } else {
+        let main_title = match (app.active_tab, app.active_environment()) {
+            (Tab::Project, Some(environment)) => format!("Project (merged {})", environment.name),
+            _ => app.active_tab.title().to_string(),
+        };
         let main_panel = Paragraph::new(main_text)
             .style(Style::default().fg(THEME.text_fg))
-            .block(pane_block(app.active_tab.title(), matches!(app.focus, FocusArea::Main)));
+            .block(pane_block(
+                &main_title,
+                matches!(app.focus, FocusArea::Main),
+            ))
+            .scroll((app.preview_scroll, 0));
         frame.render_widget(main_panel, right[0]);
     }
 
@@ -254,7 +717,27 @@ pub fn render(frame: &mut Frame, app: &App) {
         .map(|entry| ListItem::new(entry.as_str()))
         .collect();
     let log = List::new(log_items).block(pane_block("Actions", false));
-    frame.render_widget(log, right[1]);
+    frame.render_widget(log, actions_area);
+
+    if let Some(viewer) = app.log_viewer.as_ref() {
+        let view_height = right[1].height.saturating_sub(2) as usize;
+        let end = (viewer.scroll + 1).min(viewer.lines.len());
+        let start = end.saturating_sub(view_height.max(1));
+        let text = if viewer.lines.is_empty() {
+            "waiting for log output...".to_string()
+        } else {
+            viewer.lines[start..end].join("\n")
+        };
+        let title = format!(
+            "{} [{}]  j/k scroll  g top  G follow  Esc close",
+            viewer.title,
+            if viewer.follow { "following" } else { "paused" }
+        );
+        let widget = Paragraph::new(text)
+            .style(Style::default().fg(THEME.text_fg))
+            .block(pane_block(&title, true));
+        frame.render_widget(widget, right[1]);
+    }
 
     let footer_text = format!(
         "focus: {:?}   tab: {}   keys: Tab switch focus, j/k tab select, {}, q quit",
@@ -268,13 +751,33 @@ pub fn render(frame: &mut Frame, app: &App) {
     frame.render_widget(footer, root[2]);
 
     if let Some(modal) = &app.modal {
-        let popup = centered_rect(70, 70, area);
+        let popup = layout.popup;
         frame.render_widget(Clear, popup);
 
         match modal {
-            ModalState::AddImageType { input } => {
+            ModalState::AddImageType {
+                input,
+                local_images,
+                selected,
+            } => {
+                let matches = engine::matching_images(local_images, input);
+                let local_text = if matches.is_empty() {
+                    String::new()
+                } else {
+                    let lines: Vec<String> = matches
+                        .iter()
+                        .enumerate()
+                        .map(|(index, reference)| {
+                            format!(
+                                "{} {reference}",
+                                if *selected == Some(index) { "▶" } else { " " }
+                            )
+                        })
+                        .collect();
+                    format!("Local images (Up/Down to pick):\n{}\n\n", lines.join("\n"))
+                };
                 let text = format!(
-                    "Add New Image\n\nType image name/org (examples: python, nginx, node)\n\nImage: {input}\n\nEnter: resolve and fetch tags\nEsc: cancel"
+                    "Add New Image\n\nType image name/org (examples: python, nginx, node)\nor a local path (./app) to build from a Dockerfile\n\nImage: {input}\n\n{local_text}Enter: resolve and fetch tags  |  Tab: build service\nEsc: cancel"
                 );
                 let widget = Paragraph::new(text)
                     .alignment(Alignment::Left)
@@ -290,14 +793,7 @@ pub fn render(frame: &mut Frame, app: &App) {
                 selected,
                 ..
             } => {
-                let sections = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Length(6),
-                        Constraint::Min(8),
-                        Constraint::Length(3),
-                    ])
-                    .split(popup);
+                let sections = tag_picker_sections(popup);
 
                 let header_text = format!(
                     "Resolved image term: {image_term}\nUsing repo: {}/{}\nFilter tags: {}",
@@ -311,7 +807,8 @@ pub fn render(frame: &mut Frame, app: &App) {
                 } else {
                     let selected = (*selected).min(filtered_tags.len() - 1);
                     let view_height = sections[1].height.saturating_sub(2) as usize;
-                    let (start, end) = visible_window(filtered_tags.len(), selected, view_height.max(1));
+                    let (start, end) =
+                        visible_window(filtered_tags.len(), selected, view_height.max(1));
 
                     filtered_tags[start..end]
                         .iter()
@@ -350,8 +847,37 @@ pub fn render(frame: &mut Frame, app: &App) {
                 container_port_typed: _,
                 service_name_typed: _,
             } => {
+                let known_text = knowledge::lookup(namespace, repo)
+                    .map(|entry| {
+                        let mut lines = vec!["Known image defaults:".to_string()];
+                        if let Some(port) = entry.port {
+                            lines.push(format!("  port: {port}"));
+                        }
+                        if !entry.data_dirs.is_empty() {
+                            lines.push(format!("  data: {}", entry.data_dirs.join(", ")));
+                        }
+                        if !entry.required_env.is_empty() {
+                            lines
+                                .push(format!("  required env: {}", entry.required_env.join(", ")));
+                        }
+                        if let Some(healthcheck) = &entry.healthcheck {
+                            lines.push(format!("  healthcheck: {healthcheck}"));
+                        }
+                        format!("{}\n\n", lines.join("\n"))
+                    })
+                    .unwrap_or_default();
+                let port_text = match (
+                    host_port_input.trim().parse::<u16>(),
+                    container_port_input.trim().parse::<u16>(),
+                ) {
+                    (Ok(host), Ok(container)) => format!(
+                        "  host port: {}\n",
+                        app.explain_host_port(host, container, *existing_index)
+                    ),
+                    _ => String::new(),
+                };
                 let text = format!(
-                    "{}\n\nImage: {}/{}:{}\n\n{} In port (host): {}\n{} Out port (container): {}\n{} Service name: {}\n\nTab: switch field  |  Enter: save  |  Esc: cancel",
+                    "{}\n\nImage: {}/{}:{}\n\n{} In port (host): {}\n{} Out port (container): {}\n{port_text}{} Service name: {}\n\n{}Tab: switch field  |  Enter: save  |  Esc: cancel",
                     if existing_index.is_some() {
                         "Edit Image"
                     } else {
@@ -360,26 +886,82 @@ pub fn render(frame: &mut Frame, app: &App) {
                     namespace,
                     repo,
                     tag,
-                    if matches!(active_field, ConfigureField::HostPort) { ">" } else { " " },
+                    if matches!(active_field, ConfigureField::HostPort) {
+                        ">"
+                    } else {
+                        " "
+                    },
                     host_port_input,
-                    if matches!(active_field, ConfigureField::ContainerPort) { ">" } else { " " },
+                    if matches!(active_field, ConfigureField::ContainerPort) {
+                        ">"
+                    } else {
+                        " "
+                    },
                     container_port_input,
-                    if matches!(active_field, ConfigureField::Name) { ">" } else { " " },
-                    service_name_input
+                    if matches!(active_field, ConfigureField::Name) {
+                        ">"
+                    } else {
+                        " "
+                    },
+                    service_name_input,
+                    known_text
                 );
                 let widget = Paragraph::new(text)
                     .alignment(Alignment::Left)
                     .block(pane_block("Ports", true));
                 frame.render_widget(widget, popup);
             }
+            ModalState::DuplicateService {
+                image_index,
+                name_input,
+                clone_volumes,
+            } => {
+                let text = if let Some(image) = app.images.get(*image_index) {
+                    let volumes: Vec<&str> = image
+                        .mounts
+                        .iter()
+                        .filter(|mount| mount.is_named_volume())
+                        .map(|mount| mount.source.as_str())
+                        .collect();
+                    let volumes = if volumes.is_empty() {
+                        "none".to_string()
+                    } else {
+                        format!(
+                            "{} ({})",
+                            volumes.join(", "),
+                            if *clone_volumes {
+                                "clone: each copy gets its own volume"
+                            } else {
+                                "share: both services mount the same volume"
+                            }
+                        )
+                    };
+                    format!(
+                        "Duplicate {}: {}\n\nService name: {name_input}_\nHost port: {}\nNamed volumes: {volumes}\n\nType: edit name  |  Tab: share/clone volumes  |  Enter: duplicate  |  Esc: cancel",
+                        image.service_name,
+                        image.describe(),
+                        match ports::container_port(&image.port_mapping)
+                            .filter(|_| ports::host_port(&image.port_mapping).is_some())
+                        {
+                            Some(container) => app.allocate_host_port(container, None).reason,
+                            None => "none".to_string(),
+                        }
+                    )
+                } else {
+                    "Duplicate Service\n\nSelected image not found.\nPress Esc to cancel"
+                        .to_string()
+                };
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Duplicate Service", true));
+                frame.render_widget(widget, popup);
+            }
             ModalState::ConfirmDeleteImage { index } => {
                 let text = if let Some(image) = app.images.get(*index) {
                     format!(
-                        "Delete Image\n\n{}: {}/{}:{}\nports: {}\n\nPress y (or Enter) to confirm\nPress n or Esc to cancel",
+                        "Delete Image\n\n{}: {}\nports: {}\n\nPress y (or Enter) to confirm\nPress n or Esc to cancel",
                         image.service_name,
-                        image.namespace,
-                        image.repo,
-                        image.tag,
+                        image.describe(),
                         image.port_mapping
                     )
                 } else {
@@ -390,14 +972,139 @@ pub fn render(frame: &mut Frame, app: &App) {
                     .block(pane_block("Confirm Delete", true));
                 frame.render_widget(widget, popup);
             }
+            ModalState::ImportRun { input, report } => {
+                let text = match report {
+                    Some(lines) => format!(
+                        "Import docker run\n\n{input}\n\n{}\n\nEnter/Esc: close",
+                        lines.join("\n")
+                    ),
+                    None => format!(
+                        "Import docker run\n\nPaste a docker run command, for example\ndocker run -d -p 8080:80 -v data:/data -e FOO=bar --name web nginx:1.27\n\nCommand: {input}\n\nEnter: import  |  Esc: cancel"
+                    ),
+                };
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .wrap(Wrap { trim: false })
+                    .block(pane_block("Import", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::Export { format, written } => {
+                let result = export::export(app, *format);
+                let formats: Vec<String> = ExportFormat::all()
+                    .iter()
+                    .map(|candidate| {
+                        if candidate == format {
+                            format!("[{}]", candidate.label())
+                        } else {
+                            candidate.label().to_string()
+                        }
+                    })
+                    .collect();
+                let files: Vec<String> = result
+                    .files
+                    .iter()
+                    .map(|file| format!("  ./{}", file.path))
+                    .collect();
+                let warnings: Vec<String> = if result.warnings.is_empty() {
+                    vec!["  none".to_string()]
+                } else {
+                    result
+                        .warnings
+                        .iter()
+                        .map(|warning| format!("  {warning}"))
+                        .collect()
+                };
+                let footer = match written {
+                    Some(summary) => format!("{summary}\n\nEnter/Esc: close"),
+                    None => "Tab: next format  |  Enter: write files  |  Esc: cancel".to_string(),
+                };
+                let text = format!(
+                    "Export Project\n\nFormat: {}\n{}\n\nFiles:\n{}\n\nWarnings:\n{}\n\n{footer}",
+                    formats.join("  "),
+                    format.description(),
+                    files.join("\n"),
+                    warnings.join("\n")
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .wrap(Wrap { trim: false })
+                    .block(pane_block("Export", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::ConfirmPullImage { reference } => {
+                let text = format!(
+                    "Pull Image\n\n{reference} is not available on the local Docker engine.\n\nPress y (or Enter) to pull it now\nPress n or Esc to skip"
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Pull Image", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::ConfirmComposeDown => {
+                let files = app.compose_run_files();
+                let text = format!(
+                    "Compose Down\n\nThis stops and removes the containers and networks of {}.\nNamed volumes are kept.\n\n{}\n\nPress y (or Enter) to continue\nPress n or Esc to cancel",
+                    app.project_name,
+                    app.runner.command_line(&files, &["down"])
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .wrap(Wrap { trim: false })
+                    .block(pane_block("Compose Down", true));
+                frame.render_widget(widget, popup);
+            }
             ModalState::ConfirmWriteCompose => {
+                let mut warnings = Vec::new();
+                if app.images.is_empty() {
+                    warnings.push("Warning: no images are configured yet.".to_string());
+                }
+                for warning in app.secret_warnings() {
+                    warnings.push(format!("Warning: {warning}"));
+                }
+                for (file, _) in app.compose_files_output() {
+                    warnings.push(format!("Also writes included services to ./{file}"));
+                }
+                for (file, _) in app.environment_files() {
+                    warnings.push(format!("Also writes environment overrides to ./{file}"));
+                }
+                if !app.dotenv.is_empty() {
+                    warnings.push(format!(
+                        "Also writes {} variables to ./.env",
+                        app.dotenv.len()
+                    ));
+                }
+                let missing_dirs = app.missing_bind_dirs();
+                if !missing_dirs.is_empty() {
+                    warnings.push(format!(
+                        "Warning: bind mount paths do not exist: {} (press c to create them)",
+                        missing_dirs.join(", ")
+                    ));
+                }
+                if !app.secrets.is_empty() {
+                    warnings.push(format!(
+                        "Also writes {} secret files to ./{}",
+                        app.secrets.len(),
+                        secrets::SECRETS_DIR
+                    ));
+                }
+                let confirm = if app.dropped_content.is_empty() {
+                    "Press y (or Enter) to confirm"
+                } else {
+                    warnings.push(
+                        "Warning: the loaded files contain content this editor cannot keep:"
+                            .to_string(),
+                    );
+                    warnings.extend(
+                        app.dropped_content
+                            .iter()
+                            .map(|dropped| format!("  - {dropped}")),
+                    );
+                    "Press y to write anyway and drop the content above"
+                };
                 let text = format!(
-                    "Write Compose File\n\nThis will write ./docker-compose.yaml using the current Project preview.\n\n{}\n\nPress y (or Enter) to confirm\nPress n or Esc to cancel",
-                    if app.images.is_empty() {
-                        "Warning: no images are configured yet."
-                    } else {
-                        ""
-                    }
+                    "Write Compose File\n\nThis will write ./{} with the base services.\n\n{}\n\n{confirm}\nPress n or Esc to cancel",
+                    app.compose_file,
+                    warnings.join("\n")
                 );
                 let widget = Paragraph::new(text)
                     .alignment(Alignment::Left)
@@ -413,6 +1120,50 @@ pub fn render(frame: &mut Frame, app: &App) {
                     .block(pane_block("New Volume", true));
                 frame.render_widget(widget, popup);
             }
+            ModalState::DeleteVolume {
+                volume_index,
+                selected_option,
+            } => {
+                let Some(volume) = app.volumes.get(*volume_index) else {
+                    return;
+                };
+                let usage = app.volume_usage(&volume.name);
+                let mut lines = vec![format!("Delete volume {}?", volume.name), String::new()];
+                if usage.is_empty() {
+                    lines.push("No service mounts this volume.".to_string());
+                } else {
+                    lines.push("Mounted by:".to_string());
+                    lines.extend(usage.iter().map(|entry| format!("  {}", entry.describe())));
+                }
+                lines.push(String::new());
+                let mut options = Vec::new();
+                if usage.is_empty() {
+                    options.push("delete".to_string());
+                } else {
+                    options.push(format!("delete and unmount from {} places", usage.len()));
+                    options.extend(
+                        app.volumes
+                            .iter()
+                            .enumerate()
+                            .filter(|(index, _)| index != volume_index)
+                            .map(|(_, other)| format!("delete and move mounts to {}", other.name)),
+                    );
+                }
+                let selected = (*selected_option).min(options.len() - 1);
+                for (index, option) in options.iter().enumerate() {
+                    lines.push(format!(
+                        "{} {option}",
+                        if index == selected { ">" } else { " " }
+                    ));
+                }
+                lines.push(String::new());
+                lines.push("j/k: choose  |  Enter/y: confirm  |  n/Esc: cancel".to_string());
+                let widget = Paragraph::new(lines.join("\n"))
+                    .alignment(Alignment::Left)
+                    .wrap(Wrap { trim: false })
+                    .block(pane_block("Delete Volume", true));
+                frame.render_widget(widget, popup);
+            }
             ModalState::SelectImageVolumeSource {
                 image_index,
                 selected_option,
@@ -420,12 +1171,7 @@ pub fn render(frame: &mut Frame, app: &App) {
                 let image_desc = app
                     .images
                     .get(*image_index)
-                    .map(|image| {
-                        format!(
-                            "{}: {}/{}:{}",
-                            image.service_name, image.namespace, image.repo, image.tag
-                        )
-                    })
+                    .map(|image| format!("{}: {}", image.service_name, image.describe()))
                     .unwrap_or_else(|| "selected image not found".to_string());
 
                 let options = [
@@ -535,6 +1281,7 @@ pub fn render(frame: &mut Frame, app: &App) {
                 active_field,
                 local_path_typed: _,
                 target_typed: _,
+                path_assist,
             } => {
                 let image_desc = app
                     .images
@@ -543,7 +1290,7 @@ pub fn render(frame: &mut Frame, app: &App) {
                     .unwrap_or_else(|| "unknown-image".to_string());
 
                 let text = format!(
-                    "Mount Local Path\n\nImage: {image_desc}\n\n{} Local source path: {}\n{} Container path: {}\n\nSource must start with ./ or /\nTab: switch field  |  Enter: mount  |  Esc: cancel",
+                    "Mount Local Path\n\nImage: {image_desc}\n\n{} Local source path: {}\n{} Container path: {}\n\nRelative paths resolve against the compose file directory.\n{}\nTab: switch field  |  Enter: mount  |  Esc: cancel",
                     if matches!(active_field, MountInputField::Source) {
                         ">"
                     } else {
@@ -555,7 +1302,12 @@ pub fn render(frame: &mut Frame, app: &App) {
                     } else {
                         " "
                     },
-                    target_input
+                    target_input,
+                    if matches!(active_field, MountInputField::Source) {
+                        path_assist_text(path_assist, local_path_input, &app.project_dir())
+                    } else {
+                        String::new()
+                    }
                 );
                 let widget = Paragraph::new(text)
                     .alignment(Alignment::Left)
@@ -581,9 +1333,9 @@ pub fn render(frame: &mut Frame, app: &App) {
                             .enumerate()
                             .map(|(index, mount)| {
                                 if index == *selected_mount {
-                                    format!("> {}:{}", mount.source, mount.target)
+                                    format!("> {}", mount.describe())
                                 } else {
-                                    format!("  {}:{}", mount.source, mount.target)
+                                    format!("  {}", mount.describe())
                                 }
                             })
                             .collect::<Vec<_>>()
@@ -633,6 +1385,66 @@ pub fn render(frame: &mut Frame, app: &App) {
                     .block(pane_block("Env", true));
                 frame.render_widget(widget, popup);
             }
+            ModalState::ImportEnvFile {
+                image_index,
+                path_input,
+                mode,
+                required,
+                raw,
+                active_field,
+                path_assist,
+            } => {
+                let image_desc = app
+                    .images
+                    .get(*image_index)
+                    .map(|image| image.service_name.clone())
+                    .unwrap_or_else(|| "unknown-image".to_string());
+                let marker = |field: EnvFileField| {
+                    if *active_field == field { ">" } else { " " }
+                };
+                let base = app.project_dir();
+                let preview = {
+                    let vars = env_files::read_env_file(&paths::resolve(&base, path_input), *raw);
+                    let duplicates = env_files::duplicate_keys(&vars);
+                    if vars.is_empty() {
+                        String::new()
+                    } else if duplicates.is_empty() {
+                        format!("File defines {} variables", vars.len())
+                    } else {
+                        format!(
+                            "File defines {} variables; duplicate keys: {}",
+                            vars.len(),
+                            duplicates.join(", ")
+                        )
+                    }
+                };
+                let text = format!(
+                    "Env File\n\nImage: {image_desc}\n\n{} Path: {}\n{} Mode: {}\n{} Required: {}\n{} Format: {}\n\n{}\n{}\nRequired and format only apply to env_file references.\nTab: next field  |  Space/arrows: change option  |  Enter: apply  |  Esc: cancel",
+                    marker(EnvFileField::Path),
+                    path_input,
+                    marker(EnvFileField::Mode),
+                    mode.label(),
+                    marker(EnvFileField::Required),
+                    if *required { "yes" } else { "no (optional)" },
+                    marker(EnvFileField::Format),
+                    if *raw {
+                        "raw (no interpolation)"
+                    } else {
+                        "default"
+                    },
+                    preview,
+                    if matches!(active_field, EnvFileField::Path) {
+                        path_assist_text(path_assist, path_input, &base)
+                    } else {
+                        String::new()
+                    }
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .wrap(Wrap { trim: false })
+                    .block(pane_block("Env File", true));
+                frame.render_widget(widget, popup);
+            }
             ModalState::RemoveImageEnv {
                 image_index,
                 selected_env,
@@ -643,18 +1455,25 @@ pub fn render(frame: &mut Frame, app: &App) {
                     .unwrap_or_else(|| "unknown-image".to_string());
 
                 let env_text = if let Some(entry) = image {
-                    if entry.env_vars.is_empty() {
+                    if entry.env_vars.is_empty() && entry.env_files.is_empty() {
                         "No env vars on this image.".to_string()
                     } else {
                         entry
                             .env_vars
                             .iter()
+                            .map(|env| format!("{}={}", env.key, env.value))
+                            .chain(
+                                entry
+                                    .env_files
+                                    .iter()
+                                    .map(|file| format!("env_file: {}", file.describe())),
+                            )
                             .enumerate()
-                            .map(|(index, env)| {
+                            .map(|(index, line)| {
                                 if index == *selected_env {
-                                    format!("> {}={}", env.key, env.value)
+                                    format!("> {line}")
                                 } else {
-                                    format!("  {}={}", env.key, env.value)
+                                    format!("  {line}")
                                 }
                             })
                             .collect::<Vec<_>>()
@@ -672,22 +1491,488 @@ pub fn render(frame: &mut Frame, app: &App) {
                     .block(pane_block("Remove Env", true));
                 frame.render_widget(widget, popup);
             }
-            ModalState::SetImageCommand { image_index, input } => {
+            ModalState::SetImageCommand {
+                image_index,
+                field,
+                command,
+                entrypoint,
+                input,
+            } => {
                 let image_desc = app
                     .images
                     .get(*image_index)
                     .map(|entry| entry.service_name.clone())
                     .unwrap_or_else(|| "unknown-image".to_string());
+                let draft = match field {
+                    CommandField::Command => command,
+                    CommandField::Entrypoint => entrypoint,
+                };
+                let fields: Vec<String> = [CommandField::Command, CommandField::Entrypoint]
+                    .iter()
+                    .map(|candidate| {
+                        if candidate == field {
+                            format!("[{}]", candidate.label())
+                        } else {
+                            candidate.label().to_string()
+                        }
+                    })
+                    .collect();
 
+                let body = if draft.exec {
+                    let args = if draft.args.is_empty() {
+                        "  (no args)".to_string()
+                    } else {
+                        draft
+                            .args
+                            .iter()
+                            .enumerate()
+                            .map(|(index, arg)| {
+                                let marker = if index == draft.selected { ">" } else { " " };
+                                format!("{marker} {index}: {arg}")
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    };
+                    format!(
+                        "Form: exec\n\nArgs:\n{args}\n\nNew arg: {input}\n\nEnter: add arg (pasted text is split into words), empty saves\nUp/Down: select  |  PgUp/PgDn: move  |  Del: remove  |  Backspace on empty: edit arg"
+                    )
+                } else {
+                    let words = draft
+                        .spec()
+                        .map(|spec| spec.words().join("  |  "))
+                        .unwrap_or_default();
+                    format!(
+                        "Form: shell\n\n{}: {}\nWords: {words}\n\nEnter: save",
+                        field.label(),
+                        draft.shell
+                    )
+                };
+                let mut yaml = String::new();
+                if let Some(spec) = draft.spec() {
+                    spec.render(field.label(), &mut yaml);
+                }
                 let text = format!(
-                    "Set Image Command\n\nImage: {image_desc}\n\nCommand: {}\n\nEnter: save  |  Esc: cancel\nTip: use shell form like: sh -c \"npm install && npm run dev\"\nTip: leave empty and press Enter to clear command",
-                    input
+                    "Command and Entrypoint\n\nImage: {image_desc}\nEditing: {}\n{body}\n\n{}\nTab: switch shell/exec form  |  Shift-Tab: switch field  |  Esc: cancel\nLeave empty to clear",
+                    fields.join("  "),
+                    if yaml.is_empty() {
+                        "(not set)\n".to_string()
+                    } else {
+                        yaml
+                    }
                 );
                 let widget = Paragraph::new(text)
                     .alignment(Alignment::Left)
+                    .wrap(Wrap { trim: false })
                     .block(pane_block("Command", true));
                 frame.render_widget(widget, popup);
             }
+            ModalState::GenerateSecret {
+                image_index,
+                key_input,
+                storage,
+            } => {
+                let image_desc = app
+                    .images
+                    .get(*image_index)
+                    .map(|entry| entry.service_name.clone())
+                    .unwrap_or_else(|| "unknown-image".to_string());
+
+                let storage_hint = match storage {
+                    SecretStorage::DotEnv => {
+                        "Value goes to ./.env and the service references it as ${VAR}."
+                    }
+                    SecretStorage::File => {
+                        "Value goes to ./secrets/<name>.txt, mounted as a secret and exposed via VAR_FILE."
+                    }
+                };
+                let text = format!(
+                    "Generate Secret\n\nImage: {image_desc}\n\nVariable: {key_input}\nStorage: {}\n\n{storage_hint}\n\nTab: switch storage  |  Enter: generate  |  Esc: cancel",
+                    storage.label()
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Secret", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::ServiceProperties {
+                image_index,
+                sheet,
+                selected,
+                input,
+            } => {
+                let image = app.images.get(*image_index);
+                let image_desc = image
+                    .map(|entry| entry.service_name.clone())
+                    .unwrap_or_else(|| "unknown-image".to_string());
+                let properties = sheet.properties();
+                let selected = (*selected).min(properties.len() - 1);
+                let rows: Vec<(&str, String)> = properties
+                    .iter()
+                    .map(|property| {
+                        (
+                            property.label(),
+                            image
+                                .map(|entry| property.display(entry))
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                render_property_sheet(
+                    frame,
+                    popup,
+                    PropertySheetView {
+                        title: sheet.title(),
+                        header: format!("Image: {image_desc}"),
+                        rows,
+                        selected,
+                        input: input.as_deref(),
+                        format_hint: properties[selected].hint(),
+                        extra_keys: "Tab: switch sheet",
+                    },
+                );
+            }
+            ModalState::VolumeProperties {
+                volume_index,
+                selected,
+                input,
+            } => {
+                let volume = app.volumes.get(*volume_index);
+                let properties = VolumeProperty::all();
+                let selected = (*selected).min(properties.len() - 1);
+                let rows: Vec<(&str, String)> = properties
+                    .iter()
+                    .map(|property| {
+                        (
+                            property.label(),
+                            volume
+                                .map(|entry| property.display(entry))
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                let header = format!(
+                    "Volume: {}",
+                    volume
+                        .map(|entry| entry.name.as_str())
+                        .unwrap_or("unknown-volume")
+                );
+                render_property_sheet(
+                    frame,
+                    popup,
+                    PropertySheetView {
+                        title: "Volume",
+                        header,
+                        rows,
+                        selected,
+                        input: input.as_deref(),
+                        format_hint: properties[selected].hint(),
+                        extra_keys: "Enter on preset: cycle",
+                    },
+                );
+            }
+            ModalState::ServiceDetail { image_index, .. } => {
+                if let Some(image) = app.images.get(*image_index) {
+                    render_service_detail(frame, popup, image, modal);
+                }
+            }
+            ModalState::MountProperties {
+                image_index,
+                mount_index,
+                selected,
+                input,
+            } => {
+                let image = app.images.get(*image_index);
+                let mount = image.and_then(|entry| entry.mounts.get(*mount_index));
+                let properties = MountProperty::all();
+                let selected = (*selected).min(properties.len() - 1);
+                let rows: Vec<(&str, String)> = properties
+                    .iter()
+                    .map(|property| {
+                        (
+                            property.label(),
+                            mount
+                                .map(|entry| property.display(entry))
+                                .unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                let header = match (image, mount) {
+                    (Some(image), Some(mount)) => format!(
+                        "Image: {}  |  mount {}/{}: {}",
+                        image.service_name,
+                        mount_index + 1,
+                        image.mounts.len(),
+                        mount.describe()
+                    ),
+                    (Some(image), None) => format!(
+                        "Image: {}  |  no mounts, press t to add a tmpfs mount",
+                        image.service_name
+                    ),
+                    _ => "Image: unknown-image".to_string(),
+                };
+                render_property_sheet(
+                    frame,
+                    popup,
+                    PropertySheetView {
+                        title: "Mount Options",
+                        header,
+                        rows,
+                        selected,
+                        input: input.as_deref(),
+                        format_hint: properties[selected].hint(),
+                        extra_keys: "Tab: next mount  |  t: add tmpfs",
+                    },
+                );
+            }
+            ModalState::EditBuild {
+                existing_index,
+                context_input,
+                dockerfile_input,
+                target_input,
+                args_input,
+                cache_from_input,
+                image_input,
+                service_name_input,
+                active_field,
+                context_assist,
+            } => {
+                let marker = |field: BuildField| {
+                    if *active_field == field { ">" } else { " " }
+                };
+
+                let text = format!(
+                    "{}\n\n{} Context: {}\n{} Dockerfile: {}\n{} Target: {}\n{} Args: {}\n{} Cache from: {}\n{} Image: {}\n{} Service name: {}\n\nDockerfile defaults to <context>/Dockerfile. Args look like KEY=value, KEY2=value.\nImage is optional; set it to tag the built image (myorg/app:dev).\nEXPOSE, VOLUME and ENV lines pre-fill new services.\nTab: switch field  |  Enter: save  |  Esc: cancel",
+                    if existing_index.is_some() {
+                        "Edit Build"
+                    } else {
+                        "Add Build Service"
+                    },
+                    marker(BuildField::Context),
+                    context_input,
+                    marker(BuildField::Dockerfile),
+                    dockerfile_input,
+                    marker(BuildField::Target),
+                    target_input,
+                    marker(BuildField::Args),
+                    args_input,
+                    marker(BuildField::CacheFrom),
+                    cache_from_input,
+                    marker(BuildField::Image),
+                    image_input,
+                    marker(BuildField::Name),
+                    service_name_input
+                );
+                let text = if matches!(active_field, BuildField::Context) {
+                    format!(
+                        "{text}\n\n{}",
+                        path_assist_text(context_assist, context_input, &app.project_dir())
+                    )
+                } else {
+                    text
+                };
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Build", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::EditHealthcheck {
+                image_index,
+                form,
+                test_input,
+                interval_input,
+                timeout_input,
+                retries_input,
+                start_period_input,
+                disable,
+                active_field,
+                ..
+            } => {
+                let image_desc = app
+                    .images
+                    .get(*image_index)
+                    .map(|entry| entry.service_name.clone())
+                    .unwrap_or_else(|| "unknown-image".to_string());
+                let marker = |field: HealthcheckField| {
+                    if *active_field == field { ">" } else { " " }
+                };
+
+                let text = format!(
+                    "Healthcheck\n\nImage: {image_desc}\n\n{} Form: {}\n{} Test: {}\n{} Interval: {}\n{} Timeout: {}\n{} Retries: {}\n{} Start period: {}\n{} Disabled: {}\n\nDurations look like 30s, 1m30s or 500ms. Empty test clears the healthcheck.\nTab: switch field  |  Space: toggle form/disable  |  Enter: save  |  Esc: cancel",
+                    marker(HealthcheckField::Form),
+                    form.label(),
+                    marker(HealthcheckField::Test),
+                    test_input,
+                    marker(HealthcheckField::Interval),
+                    interval_input,
+                    marker(HealthcheckField::Timeout),
+                    timeout_input,
+                    marker(HealthcheckField::Retries),
+                    retries_input,
+                    marker(HealthcheckField::StartPeriod),
+                    start_period_input,
+                    marker(HealthcheckField::Disable),
+                    if *disable { "yes" } else { "no" }
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Healthcheck", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::SelectTemplate {
+                templates,
+                selected,
+            } => {
+                let templates_text = templates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, template)| {
+                        format!(
+                            "{} {}{} - {}",
+                            if index == *selected { ">" } else { " " },
+                            template.name,
+                            if matches!(template.source, TemplateSource::User) {
+                                " (user)"
+                            } else {
+                                ""
+                            },
+                            template.description
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                let services_text = templates
+                    .get(*selected)
+                    .map(|template| {
+                        template
+                            .services
+                            .iter()
+                            .map(|service| {
+                                format!("  {}: {}:{}", service.name, service.image, service.tag)
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })
+                    .unwrap_or_default();
+
+                let text = format!(
+                    "Apply Stack Template\n\n{templates_text}\n\nServices:\n{services_text}\n\nTags are resolved against Docker Hub on apply.\nj/k or arrows: move  |  Enter: apply  |  Esc: cancel"
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Templates", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::AddEnvironment { input } => {
+                let text = format!(
+                    "Add Environment\n\nEnter environment name (dev writes compose.override.yaml,\nothers write compose.<name>.yaml):\n\nName: {input}\n\nEnter: add environment\nEsc: cancel"
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("New Environment", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::EditOverride {
+                image_index,
+                environment_index,
+                port_input,
+                env_input,
+                mounts_input,
+                replicas_input,
+                active_field,
+            } => {
+                let image = app.images.get(*image_index);
+                let environment = app.environments.get(*environment_index);
+                let marker = |field: OverrideField| {
+                    if *active_field == field { ">" } else { " " }
+                };
+
+                let text = format!(
+                    "Environment Override\n\nService: {}\nEnvironment: {} ({})\nBase ports: {}\n\n{} Ports: {}\n{} Env: {}\n{} Mounts: {}\n{} Replicas: {}\n\nPorts replace the base mapping (host:container, none removes them).\nEnv is KEY=value, KEY2=value; mounts are source:target, source2:target2.\nEmpty fields keep the base value.\nTab: switch field  |  Enter: save  |  Esc: cancel",
+                    image
+                        .map(|image| image.service_name.as_str())
+                        .unwrap_or("unknown-image"),
+                    environment
+                        .map(|environment| environment.name.as_str())
+                        .unwrap_or("?"),
+                    environment
+                        .map(|environment| environment.file_name())
+                        .unwrap_or_default(),
+                    image.map(|image| image.port_mapping.as_str()).unwrap_or(""),
+                    marker(OverrideField::Ports),
+                    port_input,
+                    marker(OverrideField::Env),
+                    env_input,
+                    marker(OverrideField::Mounts),
+                    mounts_input,
+                    marker(OverrideField::Replicas),
+                    replicas_input
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Override", true));
+                frame.render_widget(widget, popup);
+            }
+            ModalState::ReviewScan {
+                proposals,
+                notes,
+                selected,
+            } => {
+                let proposals_text = if proposals.is_empty() {
+                    "  nothing to propose".to_string()
+                } else {
+                    proposals
+                        .iter()
+                        .enumerate()
+                        .map(|(index, proposal)| {
+                            format!(
+                                "{} [{}] {}: {}",
+                                if index == *selected { ">" } else { " " },
+                                if proposal.accepted { "x" } else { " " },
+                                proposal.entry.service_name,
+                                proposal.entry.describe()
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                };
+
+                let details_text = proposals
+                    .get(*selected)
+                    .map(|proposal| {
+                        let entry = &proposal.entry;
+                        let mut lines = vec![format!("  {}", proposal.reason)];
+                        if !entry.port_mapping.is_empty() {
+                            lines.push(format!("  port: {}", entry.port_mapping));
+                        }
+                        for mount in &entry.mounts {
+                            lines.push(format!("  mount: {}", mount.describe()));
+                        }
+                        for env in &entry.env_vars {
+                            lines.push(format!("  env: {}={}", env.key, env.value));
+                        }
+                        if !entry.depends_on.is_empty() {
+                            lines.push(format!("  depends_on: {}", entry.depends_on.join(", ")));
+                        }
+                        lines.join("\n")
+                    })
+                    .unwrap_or_default();
+
+                let notes_text = notes
+                    .iter()
+                    .map(|note| format!("  {note}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                let text = format!(
+                    "Review Proposed Services\n\n{proposals_text}\n\nDetails:\n{details_text}\n\nNotes:\n{notes_text}\n\nj/k or arrows: move  |  Space: accept/skip  |  Enter: add accepted  |  Esc: cancel"
+                );
+                let widget = Paragraph::new(text)
+                    .alignment(Alignment::Left)
+                    .block(pane_block("Project Scan", true));
+                frame.render_widget(widget, popup);
+            }
         }
+        render_modal_buttons(frame, popup, modal);
     }
 }
@@ -0,0 +1,799 @@
+use crate::tui::{
+    app::{VolumeEntry, VolumeMount, VolumeUsage, yaml_quote},
+    deploy,
+    properties::{self, KeyValue},
+};
+
+const PROPAGATION_MODES: [&str; 6] = [
+    "rprivate", "private", "rshared", "shared", "rslave", "slave",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MountType {
+    Bind,
+    Volume,
+    Tmpfs,
+    Npipe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelinuxLabel {
+    Shared,
+    Private,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MountOptions {
+    pub kind: Option<MountType>,
+    pub long_syntax: bool,
+    pub read_only: bool,
+    pub selinux: Option<SelinuxLabel>,
+    pub propagation: Option<String>,
+    pub create_host_path: bool,
+    pub nocopy: bool,
+    pub subpath: Option<String>,
+    pub tmpfs_size: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumePreset {
+    Nfs,
+    Tmpfs,
+    Bind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeProperty {
+    Preset,
+    Driver,
+    DriverOpts,
+    External,
+    Name,
+    Labels,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MountProperty {
+    Type,
+    Source,
+    Target,
+    ReadOnly,
+    LongSyntax,
+    Propagation,
+    CreateHostPath,
+    Selinux,
+    NoCopy,
+    Subpath,
+    TmpfsSize,
+}
+
+pub fn is_path_source(source: &str) -> bool {
+    source.starts_with('.')
+        || source.starts_with('/')
+        || source.starts_with('~')
+        || source.starts_with('$')
+        || source.starts_with("\\\\")
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+fn optional_text(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+fn parse_driver_opts(input: &str) -> Result<Vec<KeyValue>, String> {
+    input
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok(KeyValue {
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+            }),
+            _ => Err(format!("expected key=value, got '{pair}'")),
+        })
+        .collect()
+}
+
+fn join_pairs(pairs: &[KeyValue], separator: &str) -> String {
+    pairs
+        .iter()
+        .map(|pair| format!("{}={}", pair.key, pair.value))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn render_pairs(output: &mut String, key: &str, pairs: &[KeyValue]) {
+    if pairs.is_empty() {
+        return;
+    }
+    output.push_str(&format!("    {key}:\n"));
+    for pair in pairs {
+        output.push_str(&format!(
+            "      {}: {}\n",
+            pair.key,
+            yaml_quote(&pair.value)
+        ));
+    }
+}
+
+impl SelinuxLabel {
+    pub fn flag(self) -> &'static str {
+        match self {
+            Self::Shared => "z",
+            Self::Private => "Z",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim() {
+            "z" => Some(Self::Shared),
+            "Z" => Some(Self::Private),
+            _ => None,
+        }
+    }
+}
+
+impl MountType {
+    pub fn all() -> [Self; 4] {
+        [Self::Bind, Self::Volume, Self::Tmpfs, Self::Npipe]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Bind => "bind",
+            Self::Volume => "volume",
+            Self::Tmpfs => "tmpfs",
+            Self::Npipe => "npipe",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|kind| kind.label() == input.trim())
+    }
+
+    pub fn infer(source: &str) -> Self {
+        if source.is_empty() {
+            Self::Tmpfs
+        } else if source.starts_with("\\\\.\\pipe") || source.starts_with("//./pipe") {
+            Self::Npipe
+        } else if is_path_source(source) {
+            Self::Bind
+        } else {
+            Self::Volume
+        }
+    }
+}
+
+impl VolumeMount {
+    pub fn new(source: impl Into<String>, target: impl Into<String>) -> Self {
+        let mut mount = Self {
+            source: source.into(),
+            target: String::new(),
+            options: MountOptions::default(),
+        };
+        mount.set_target(&target.into());
+        mount
+    }
+
+    pub fn tmpfs(target: impl Into<String>) -> Self {
+        Self {
+            source: String::new(),
+            target: target.into(),
+            options: MountOptions {
+                kind: Some(MountType::Tmpfs),
+                ..MountOptions::default()
+            },
+        }
+    }
+
+    pub fn kind(&self) -> MountType {
+        self.options
+            .kind
+            .unwrap_or_else(|| MountType::infer(&self.source))
+    }
+
+    pub fn is_named_volume(&self) -> bool {
+        self.kind() == MountType::Volume && !self.source.is_empty()
+    }
+
+    pub fn set_target(&mut self, target: &str) {
+        let (path, mode) = target.split_once(':').unwrap_or((target, ""));
+        self.target = path.to_string();
+        self.options.read_only = false;
+        self.options.selinux = None;
+        for flag in mode.split(',') {
+            match flag {
+                "ro" => self.options.read_only = true,
+                "nocopy" => self.options.nocopy = true,
+                flag if PROPAGATION_MODES.contains(&flag) => {
+                    self.options.propagation = Some(flag.to_string())
+                }
+                flag => {
+                    if let Some(label) = SelinuxLabel::parse(flag) {
+                        self.options.selinux = Some(label);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn container_path(&self) -> &str {
+        &self.target
+    }
+
+    pub fn short_target(&self) -> String {
+        let flags: Vec<&str> = [
+            self.options.read_only.then_some("ro"),
+            self.options.selinux.map(SelinuxLabel::flag),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if flags.is_empty() {
+            self.target.clone()
+        } else {
+            format!("{}:{}", self.target, flags.join(","))
+        }
+    }
+
+    pub fn requires_long_syntax(&self) -> bool {
+        let options = &self.options;
+        matches!(self.kind(), MountType::Tmpfs | MountType::Npipe)
+            || options.propagation.is_some()
+            || options.create_host_path
+            || options.nocopy
+            || options.subpath.is_some()
+            || options.tmpfs_size.is_some()
+            || self
+                .options
+                .kind
+                .is_some_and(|kind| kind != MountType::infer(&self.source))
+    }
+
+    pub fn uses_long_syntax(&self) -> bool {
+        self.options.long_syntax || self.requires_long_syntax()
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = match self.kind() {
+            MountType::Tmpfs => format!("tmpfs:{}", self.short_target()),
+            _ => format!("{}:{}", self.source, self.short_target()),
+        };
+        let mut extras = Vec::new();
+        if let Some(size) = self.options.tmpfs_size.as_ref() {
+            extras.push(format!("size {size}"));
+        }
+        if let Some(subpath) = self.options.subpath.as_ref() {
+            extras.push(format!("subpath {subpath}"));
+        }
+        if let Some(propagation) = self.options.propagation.as_ref() {
+            extras.push(propagation.clone());
+        }
+        if self.options.nocopy {
+            extras.push("nocopy".to_string());
+        }
+        if !extras.is_empty() {
+            text.push_str(&format!(" ({})", extras.join(", ")));
+        }
+        text
+    }
+
+    pub fn render(&self, output: &mut String) {
+        if !self.uses_long_syntax() {
+            output.push_str(&format!(
+                "      - {}:{}\n",
+                self.source,
+                self.short_target()
+            ));
+            return;
+        }
+
+        let kind = self.kind();
+        output.push_str(&format!("      - type: {}\n", kind.label()));
+        if !self.source.is_empty() && kind != MountType::Tmpfs {
+            output.push_str(&format!("        source: {}\n", self.source));
+        }
+        output.push_str(&format!("        target: {}\n", self.container_path()));
+        if self.options.read_only {
+            output.push_str("        read_only: true\n");
+        }
+        let options = &self.options;
+        match kind {
+            MountType::Bind => {
+                if options.propagation.is_some()
+                    || options.create_host_path
+                    || options.selinux.is_some()
+                {
+                    output.push_str("        bind:\n");
+                }
+                if let Some(propagation) = options.propagation.as_ref() {
+                    output.push_str(&format!("          propagation: {propagation}\n"));
+                }
+                if options.create_host_path {
+                    output.push_str("          create_host_path: true\n");
+                }
+                if let Some(selinux) = options.selinux {
+                    output.push_str(&format!("          selinux: {}\n", selinux.flag()));
+                }
+            }
+            MountType::Volume => {
+                if options.nocopy || options.subpath.is_some() {
+                    output.push_str("        volume:\n");
+                }
+                if options.nocopy {
+                    output.push_str("          nocopy: true\n");
+                }
+                if let Some(subpath) = options.subpath.as_ref() {
+                    output.push_str(&format!("          subpath: {subpath}\n"));
+                }
+            }
+            MountType::Tmpfs => {
+                if let Some(size) = options.tmpfs_size.as_ref() {
+                    output.push_str(&format!("        tmpfs:\n          size: {size}\n"));
+                }
+            }
+            MountType::Npipe => {}
+        }
+    }
+}
+
+impl VolumePreset {
+    pub fn all() -> [Self; 3] {
+        [Self::Nfs, Self::Tmpfs, Self::Bind]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Nfs => "nfs",
+            Self::Tmpfs => "tmpfs",
+            Self::Bind => "bind",
+        }
+    }
+
+    fn opts_type(self) -> &'static str {
+        match self {
+            Self::Nfs => "nfs",
+            Self::Tmpfs => "tmpfs",
+            Self::Bind => "none",
+        }
+    }
+
+    pub fn detect(volume: &VolumeEntry) -> Option<Self> {
+        let opts_type = volume
+            .driver_opts
+            .iter()
+            .find(|pair| pair.key == "type")
+            .map(|pair| pair.value.as_str())?;
+        Self::all().into_iter().find(|preset| {
+            let kind = preset.opts_type();
+            opts_type == kind
+                || opts_type
+                    .strip_prefix(kind)
+                    .is_some_and(|rest| rest.starts_with(char::is_numeric))
+        })
+    }
+
+    pub fn apply(self, volume: &mut VolumeEntry) {
+        let pairs: [(&str, &str); 3] = match self {
+            Self::Nfs => [
+                ("type", "nfs"),
+                ("o", "addr=127.0.0.1,rw,nfsvers=4"),
+                ("device", ":/export"),
+            ],
+            Self::Tmpfs => [("type", "tmpfs"), ("device", "tmpfs"), ("o", "size=100m")],
+            Self::Bind => [("type", "none"), ("o", "bind"), ("device", "/srv/data")],
+        };
+        volume.driver = Some("local".to_string());
+        volume.external = false;
+        volume.driver_opts = pairs
+            .iter()
+            .map(|(key, value)| KeyValue {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+    }
+}
+
+impl VolumeEntry {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            driver: None,
+            driver_opts: Vec::new(),
+            external: false,
+            volume_name: None,
+            labels: Vec::new(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut details = Vec::new();
+        if self.external {
+            details.push("external".to_string());
+        }
+        if let Some(name) = self.volume_name.as_ref() {
+            details.push(format!("name {name}"));
+        }
+        match (VolumePreset::detect(self), self.driver.as_ref()) {
+            (Some(preset), _) => details.push(preset.label().to_string()),
+            (None, Some(driver)) => details.push(format!("driver {driver}")),
+            (None, None) => {}
+        }
+        if details.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, details.join(", "))
+        }
+    }
+
+    pub fn render(&self, output: &mut String) {
+        output.push_str(&format!("  {}:\n", self.name));
+        if self.external {
+            output.push_str("    external: true\n");
+        } else {
+            if let Some(driver) = self.driver.as_ref() {
+                output.push_str(&format!("    driver: {driver}\n"));
+            }
+            render_pairs(output, "driver_opts", &self.driver_opts);
+        }
+        if let Some(name) = self.volume_name.as_ref() {
+            output.push_str(&format!("    name: {}\n", yaml_quote(name)));
+        }
+        render_pairs(output, "labels", &self.labels);
+    }
+}
+
+impl VolumeUsage {
+    pub fn describe(&self) -> String {
+        match self.environment.as_ref() {
+            Some(environment) => format!("{} [{environment}] at {}", self.service, self.target),
+            None => format!("{} at {}", self.service, self.target),
+        }
+    }
+}
+
+pub fn usage_summary(usage: &[VolumeUsage]) -> String {
+    if usage.is_empty() {
+        return "orphan: not mounted by any service".to_string();
+    }
+    let services = usage
+        .iter()
+        .map(VolumeUsage::describe)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("used by {services}")
+}
+
+impl VolumeProperty {
+    pub fn all() -> [Self; 6] {
+        [
+            Self::Preset,
+            Self::Driver,
+            Self::DriverOpts,
+            Self::External,
+            Self::Name,
+            Self::Labels,
+        ]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Preset => "preset",
+            Self::Driver => "driver",
+            Self::DriverOpts => "driver_opts",
+            Self::External => "external",
+            Self::Name => "name",
+            Self::Labels => "labels",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Preset => "Enter cycles nfs, tmpfs and bind-backed local driver_opts",
+            Self::Driver => "local, or a plugin driver name",
+            Self::DriverOpts => {
+                "key=value; key=value (e.g. type=nfs; o=addr=10.0.0.2,rw; device=:/export)"
+            }
+            Self::External => {
+                "toggle: volume is created outside compose (driver settings are not written)"
+            }
+            Self::Name => {
+                "actual volume name, e.g. shared_data (for external volumes or fixed names)"
+            }
+            Self::Labels => "key=value, key=value",
+        }
+    }
+
+    pub fn is_toggle(self) -> bool {
+        matches!(self, Self::Preset | Self::External)
+    }
+
+    pub fn toggle(self, volume: &mut VolumeEntry) {
+        match self {
+            Self::Preset => {
+                let presets = VolumePreset::all();
+                let next = match VolumePreset::detect(volume) {
+                    Some(current) => presets
+                        .iter()
+                        .position(|preset| *preset == current)
+                        .and_then(|index| presets.get(index + 1)),
+                    None => presets.first(),
+                };
+                match next {
+                    Some(preset) => preset.apply(volume),
+                    None => {
+                        volume.driver = None;
+                        volume.driver_opts.clear();
+                    }
+                }
+            }
+            Self::External => volume.external = !volume.external,
+            _ => {}
+        }
+    }
+
+    pub fn display(self, volume: &VolumeEntry) -> String {
+        match self {
+            Self::Preset => VolumePreset::detect(volume)
+                .map(|preset| preset.label().to_string())
+                .unwrap_or_else(|| {
+                    if volume.driver_opts.is_empty() {
+                        "none"
+                    } else {
+                        "custom"
+                    }
+                    .to_string()
+                }),
+            Self::Driver => volume.driver.clone().unwrap_or_default(),
+            Self::DriverOpts => join_pairs(&volume.driver_opts, "; "),
+            Self::External => yes_no(volume.external),
+            Self::Name => volume.volume_name.clone().unwrap_or_default(),
+            Self::Labels => join_pairs(&volume.labels, ", "),
+        }
+    }
+
+    pub fn apply(self, volume: &mut VolumeEntry, input: &str) -> Result<(), String> {
+        match self {
+            Self::Preset => {
+                let trimmed = input.trim();
+                match VolumePreset::all()
+                    .into_iter()
+                    .find(|preset| preset.label() == trimmed)
+                {
+                    Some(preset) => preset.apply(volume),
+                    None if trimmed.is_empty() => {
+                        volume.driver = None;
+                        volume.driver_opts.clear();
+                    }
+                    None => return Err(format!("unknown preset '{trimmed}' (nfs, tmpfs, bind)")),
+                }
+            }
+            Self::Driver => volume.driver = optional_text(input),
+            Self::DriverOpts => volume.driver_opts = parse_driver_opts(input)?,
+            Self::External => volume.external = matches!(input.trim(), "yes" | "true"),
+            Self::Name => volume.volume_name = optional_text(input),
+            Self::Labels => volume.labels = properties::parse_key_values(input)?,
+        }
+        Ok(())
+    }
+}
+
+impl MountProperty {
+    pub fn all() -> [Self; 11] {
+        [
+            Self::Type,
+            Self::Source,
+            Self::Target,
+            Self::ReadOnly,
+            Self::LongSyntax,
+            Self::Propagation,
+            Self::CreateHostPath,
+            Self::Selinux,
+            Self::NoCopy,
+            Self::Subpath,
+            Self::TmpfsSize,
+        ]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Type => "type",
+            Self::Source => "source",
+            Self::Target => "target",
+            Self::ReadOnly => "read_only",
+            Self::LongSyntax => "long syntax",
+            Self::Propagation => "bind.propagation",
+            Self::CreateHostPath => "bind.create_host_path",
+            Self::Selinux => "bind.selinux",
+            Self::NoCopy => "volume.nocopy",
+            Self::Subpath => "volume.subpath",
+            Self::TmpfsSize => "tmpfs.size",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Type => "bind, volume, tmpfs or npipe (empty: infer from source)",
+            Self::Source => "named volume or host path (./data, /srv/data); empty for tmpfs",
+            Self::Target => "absolute path in the container, e.g. /var/lib/data",
+            Self::ReadOnly => "toggle: mount read-only",
+            Self::LongSyntax => {
+                "toggle: always write the long syntax (forced when other options need it)"
+            }
+            Self::Propagation => "rprivate, private, rshared, shared, rslave or slave (bind only)",
+            Self::CreateHostPath => "toggle: create the host directory if missing (bind only)",
+            Self::Selinux => "z (shared) or Z (private) relabel; empty to keep the label",
+            Self::NoCopy => "toggle: don't copy image data into a new volume (volume only)",
+            Self::Subpath => "path inside the volume to mount (volume only)",
+            Self::TmpfsSize => "size such as 64m or 1g (tmpfs only)",
+        }
+    }
+
+    pub fn is_toggle(self) -> bool {
+        matches!(
+            self,
+            Self::ReadOnly | Self::LongSyntax | Self::CreateHostPath | Self::NoCopy
+        )
+    }
+
+    pub fn toggle(self, mount: &mut VolumeMount) {
+        match self {
+            Self::ReadOnly => mount.options.read_only = !mount.options.read_only,
+            Self::LongSyntax => mount.options.long_syntax = !mount.options.long_syntax,
+            Self::CreateHostPath => {
+                mount.options.create_host_path = !mount.options.create_host_path
+            }
+            Self::NoCopy => mount.options.nocopy = !mount.options.nocopy,
+            _ => {}
+        }
+    }
+
+    pub fn display(self, mount: &VolumeMount) -> String {
+        match self {
+            Self::Type => match mount.options.kind {
+                Some(kind) => kind.label().to_string(),
+                None => format!("{} (inferred)", mount.kind().label()),
+            },
+            Self::Source => mount.source.clone(),
+            Self::Target => mount.container_path().to_string(),
+            Self::ReadOnly => yes_no(mount.options.read_only),
+            Self::LongSyntax => {
+                if mount.requires_long_syntax() {
+                    "yes (required)".to_string()
+                } else {
+                    yes_no(mount.options.long_syntax)
+                }
+            }
+            Self::Propagation => mount.options.propagation.clone().unwrap_or_default(),
+            Self::CreateHostPath => yes_no(mount.options.create_host_path),
+            Self::Selinux => mount
+                .options
+                .selinux
+                .map(SelinuxLabel::flag)
+                .unwrap_or_default()
+                .to_string(),
+            Self::NoCopy => yes_no(mount.options.nocopy),
+            Self::Subpath => mount.options.subpath.clone().unwrap_or_default(),
+            Self::TmpfsSize => mount.options.tmpfs_size.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn apply(self, mount: &mut VolumeMount, input: &str) -> Result<(), String> {
+        let value = optional_text(input);
+        match self {
+            Self::Type => {
+                mount.options.kind = match value {
+                    Some(value) => Some(MountType::parse(&value).ok_or_else(|| {
+                        format!("unknown mount type '{value}' (bind, volume, tmpfs, npipe)")
+                    })?),
+                    None => None,
+                };
+            }
+            Self::Source => {
+                let source = value.unwrap_or_default();
+                if source.is_empty() && mount.kind() != MountType::Tmpfs {
+                    return Err("source is required unless the mount is tmpfs".to_string());
+                }
+                mount.source = source;
+            }
+            Self::Target => match value {
+                Some(path) if path.starts_with('/') || path.contains(":\\") => mount.target = path,
+                _ => return Err("target must be an absolute container path".to_string()),
+            },
+            Self::Propagation => {
+                if let Some(mode) = value.as_deref()
+                    && !PROPAGATION_MODES.contains(&mode)
+                {
+                    return Err(format!("unknown propagation '{mode}'"));
+                }
+                mount.options.propagation = value;
+            }
+            Self::Selinux => {
+                mount.options.selinux = match value.as_deref() {
+                    Some(flag) => Some(
+                        SelinuxLabel::parse(flag)
+                            .ok_or_else(|| format!("unknown selinux label '{flag}' (z or Z)"))?,
+                    ),
+                    None => None,
+                };
+            }
+            Self::Subpath => mount.options.subpath = value,
+            Self::TmpfsSize => {
+                if let Some(size) = value.as_deref()
+                    && !deploy::is_valid_memory(size)
+                {
+                    return Err(format!("invalid size '{size}' (e.g. 64m)"));
+                }
+                mount.options.tmpfs_size = value;
+            }
+            Self::ReadOnly | Self::LongSyntax | Self::CreateHostPath | Self::NoCopy => {
+                let enabled = matches!(value.as_deref(), Some("yes" | "true"));
+                match self {
+                    Self::ReadOnly => mount.options.read_only = enabled,
+                    Self::LongSyntax => mount.options.long_syntax = enabled,
+                    Self::CreateHostPath => mount.options.create_host_path = enabled,
+                    _ => mount.options.nocopy = enabled,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(mount: &VolumeMount) -> String {
+        let mut output = String::new();
+        mount.render(&mut output);
+        output
+    }
+
+    #[test]
+    fn short_syntax_flags_become_typed_options() {
+        let mount = VolumeMount::new("./config", "/etc/app:ro,Z");
+        assert_eq!(mount.container_path(), "/etc/app");
+        assert!(mount.options.read_only);
+        assert_eq!(mount.options.selinux, Some(SelinuxLabel::Private));
+        assert_eq!(rendered(&mount), "      - ./config:/etc/app:ro,Z\n");
+        assert_eq!(mount.describe(), "./config:/etc/app:ro,Z");
+
+        let mount = VolumeMount::new("data", "/data:rw,nocopy");
+        assert_eq!(mount.container_path(), "/data");
+        assert!(!mount.options.read_only && mount.options.nocopy);
+    }
+
+    #[test]
+    fn long_syntax_renders_read_only_and_selinux() {
+        let mut mount = VolumeMount::new("./config", "/etc/app");
+        mount.options.read_only = true;
+        mount.options.selinux = Some(SelinuxLabel::Shared);
+        mount.options.create_host_path = true;
+        let output = rendered(&mount);
+        assert!(output.contains("        target: /etc/app\n"), "{output}");
+        assert!(output.contains("        read_only: true\n"), "{output}");
+        assert!(output.contains("          selinux: z\n"), "{output}");
+    }
+
+    #[test]
+    fn mount_properties_edit_typed_fields() {
+        let mut mount = VolumeMount::new("./config", "/etc/app:ro");
+        MountProperty::Target.apply(&mut mount, "/srv/app").unwrap();
+        assert_eq!(mount.short_target(), "/srv/app:ro");
+        MountProperty::Selinux.apply(&mut mount, "Z").unwrap();
+        MountProperty::ReadOnly.toggle(&mut mount);
+        assert_eq!(mount.short_target(), "/srv/app:Z");
+        assert!(MountProperty::Selinux.apply(&mut mount, "x").is_err());
+        MountProperty::Selinux.apply(&mut mount, "").unwrap();
+        assert_eq!(mount.options.selinux, None);
+    }
+}